@@ -4,7 +4,7 @@ use gpui::{
     div, hsla, impl_actions, px, App, AppContext, Application, BorrowAppContext, Context, Edges, Entity, EventEmitter, FocusHandle, Focusable, InteractiveElement, IntoElement, MouseButton, ParentElement, Pixels, Point, Render, SharedString, Size as GpuiSize, Styled, Subscription, Window
 };
 use gpui_component::{
-    button::{Button, ButtonVariant, ButtonVariants}, dock::{DockArea, DockItem, DockPlacement, Panel, PanelEvent, PanelView}, dropdown::{Dropdown, DropdownEvent}, h_flex, input::{InputEvent, TextInput}, label::Label, notification::{Notification, NotificationType}, popup_menu::PopupMenuExt, scroll::ScrollbarShow, table::{self, Table, TableDelegate, TableEvent}, v_flex, ContextModal, Disableable as _, Sizable, Size, Theme
+    button::{Button, ButtonVariant, ButtonVariants}, dock::{DockArea, DockItem, DockPlacement, Panel, PanelEvent, PanelView}, dropdown::{Dropdown, DropdownEvent}, h_flex, input::{InputEvent, TextInput}, label::Label, notification::{Notification, NotificationType}, popup_menu::PopupMenuExt, scroll::ScrollbarShow, table::{self, CellValue, Table, TableDelegate, TableEvent}, v_flex, ContextModal, Disableable as _, Sizable, Size, Theme
 };
 use log::{debug, error, info, LevelFilter};
 use log4rs::{
@@ -1511,36 +1511,10 @@ impl TableDelegate for ResultTableDelegate {
         Some(Edges::all(px(4.)))
     }
 
-    fn render_th(
-        &self,
-        col_ix: usize,
-        _: &mut Window,
-        cx: &mut Context<Table<Self>>,
-    ) -> impl IntoElement {
-        div()
-            .flex()
-            .justify_center() // 水平居中
-            .items_center() // 垂直居中
-            .size_full()
-            .font_weight(gpui::FontWeight::MEDIUM) // 加粗
-            .child(self.col_name(col_ix, cx))
-    }
-
-    fn render_td(
-        &self,
-        row_ix: usize,
-        col_ix: usize,
-        window: &mut Window,
-        cx: &mut Context<Table<Self>>,
-    ) -> impl IntoElement {
+    fn cell_value(&self, row_ix: usize, col_ix: usize, _: &App) -> CellValue {
         let row = &self.total_rows[row_ix];
-        
-        // Check if this is a category row - now more dynamic
-        // It's a category row if:
-        // 1. The name doesn't contain spaces (single word)
-        // 2. Any field other than 序号 and 项目名称 is empty
         let is_category_row = !row.项目名称.contains(' ') && row.单位.is_empty() && row.碳排放指数.is_empty();
-        
+
         let value = match col_ix {
             0 => row.序号.clone(),
             1 => row.项目名称.clone(), // Already correctly formatted from the SQL query
@@ -1570,6 +1544,41 @@ impl TableDelegate for ResultTableDelegate {
             _ => String::new(),
         };
 
+        value.into()
+    }
+
+    fn render_th(
+        &self,
+        col_ix: usize,
+        _: &mut Window,
+        cx: &mut Context<Table<Self>>,
+    ) -> impl IntoElement {
+        div()
+            .flex()
+            .justify_center() // 水平居中
+            .items_center() // 垂直居中
+            .size_full()
+            .font_weight(gpui::FontWeight::MEDIUM) // 加粗
+            .child(self.col_name(col_ix, cx))
+    }
+
+    fn render_td(
+        &self,
+        row_ix: usize,
+        col_ix: usize,
+        window: &mut Window,
+        cx: &mut Context<Table<Self>>,
+    ) -> impl IntoElement {
+        let row = &self.total_rows[row_ix];
+        
+        // Check if this is a category row - now more dynamic
+        // It's a category row if:
+        // 1. The name doesn't contain spaces (single word)
+        // 2. Any field other than 序号 and 项目名称 is empty
+        let is_category_row = !row.项目名称.contains(' ') && row.单位.is_empty() && row.碳排放指数.is_empty();
+        
+        let value = self.cell_value(row_ix, col_ix, cx).to_string();
+
         let mut element = div();
         
         // 对列进行特殊处理
@@ -1848,8 +1857,19 @@ impl CarbonResultPanel {
     fn export_results_to_excel(&self, window: &mut Window, cx: &mut Context<CarbonResultPanel>) {
         debug!("Exporting results to Excel");
         
-        // 获取结果数据
-        let result_rows = self.table.read(cx).delegate().total_rows.clone();
+        // 获取结果数据，单元格的值与表格显示的一致
+        let delegate = self.table.read(cx).delegate();
+        let cols_count = delegate.cols_count(cx);
+        let headers: Vec<String> = (0..cols_count)
+            .map(|col_ix| delegate.col_name(col_ix, cx).to_string())
+            .collect();
+        let result_rows: Vec<Vec<String>> = (0..delegate.rows_count(cx))
+            .map(|row_ix| {
+                (0..cols_count)
+                    .map(|col_ix| delegate.cell_value(row_ix, col_ix, cx).to_string())
+                    .collect()
+            })
+            .collect();
         
         // 检查是否有数据可导出
         if result_rows.is_empty() {
@@ -1885,41 +1905,18 @@ impl CarbonResultPanel {
                     // 写入标题行
                     if let Err(e) = wb.write_sheet(&mut sheet, |sheet_writer| {
                         // 写入标题行
-                        let row = row!["序号", "项目名称", "单位", "工程量", "总碳排放量", "人工", "材料", "机械", "小计"];
-                        sheet_writer.append_row(row)?;
+                        let mut header_row = Row::new();
+                        for header in &headers {
+                            header_row.add_cell(header.clone());
+                        }
+                        sheet_writer.append_row(header_row)?;
                         
                         // 写入数据行
                         for row_data in &result_rows {
-                            let is_category_row = !row_data.项目名称.contains(' ') && row_data.单位.is_empty();
-                            
-                            let 总碳排放量 = if is_category_row { 
-                                String::new()
-                            } else { 
-                                if row_data.可研估算.is_empty() || row_data.可研估算 == "右键编辑" {
-                                    "-".to_string()
-                                } else {
-                                    match (row_data.可研估算.parse::<f64>(), row_data.小计.parse::<f64>()) {
-                                        (Ok(工程量), Ok(小计)) => {
-                                            let 总碳排放量 = 工程量 * 小计;
-                                            format!("{:.2}", 总碳排放量)
-                                        },
-                                        _ => "-".to_string()
-                                    }
-                                }
-                            };
-                            
-                            let data_row = row![
-                                row_data.序号.clone(),
-                                row_data.项目名称.clone(),
-                                if is_category_row { "".to_string() } else { row_data.单位.clone() },
-                                if is_category_row { "".to_string() } else { row_data.可研估算.clone() },
-                                总碳排放量,
-                                if is_category_row { "".to_string() } else { row_data.人工.clone() },
-                                if is_category_row { "".to_string() } else { row_data.材料.clone() },
-                                if is_category_row { "".to_string() } else { row_data.机械.clone() },
-                                if is_category_row { "".to_string() } else { row_data.小计.clone() }
-                            ];
-                            
+                            let mut data_row = Row::new();
+                            for value in row_data {
+                                data_row.add_cell(value.clone());
+                            }
                             sheet_writer.append_row(data_row)?;
                         }
                         
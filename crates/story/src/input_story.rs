@@ -1,7 +1,9 @@
+use std::rc::Rc;
+
 use gpui::{
     actions, div, prelude::FluentBuilder as _, px, App, AppContext as _, ClickEvent, Context,
     Entity, FocusHandle, Focusable, InteractiveElement, IntoElement, KeyBinding,
-    ParentElement as _, Render, SharedString, Styled, Window,
+    ParentElement as _, Render, SharedString, Styled, Task, Window,
 };
 use regex::Regex;
 
@@ -10,7 +12,10 @@ use gpui_component::{
     button::{Button, ButtonVariant, ButtonVariants as _},
     checkbox::Checkbox,
     h_flex,
-    input::{InputEvent, NumberInput, NumberInputEvent, OtpInput, StepAction, TextInput},
+    input::{
+        CompletionItem, CompletionProvider, InputEvent, NumberInput, NumberInputEvent, OtpInput,
+        StepAction, TextInput,
+    },
     v_flex, FocusableCycle, IconName, Sizable,
 };
 
@@ -18,6 +23,40 @@ actions!(input_story, [Tab, TabPrev]);
 
 const CONTEXT: &str = "InputStory";
 
+/// The emission factors (kgCO2e per unit) to complete in the formula input.
+const EMISSION_FACTORS: &[(&str, &str)] = &[
+    ("electricity_grid", "0.5810 / kWh"),
+    ("electricity_solar", "0.0480 / kWh"),
+    ("natural_gas", "2.1622 / m³"),
+    ("diesel", "2.6870 / L"),
+    ("gasoline", "2.3150 / L"),
+    ("coal", "2.4925 / kg"),
+    ("lpg", "1.5570 / L"),
+    ("steam", "0.1100 / MJ"),
+];
+
+/// Complete the emission factor names by the word before the cursor.
+struct EmissionFactorCompletion;
+
+impl CompletionProvider for EmissionFactorCompletion {
+    fn completions(
+        &self,
+        _: &TextInput,
+        query: &str,
+        _: usize,
+        _: &mut Window,
+        _: &mut App,
+    ) -> Task<anyhow::Result<Vec<CompletionItem>>> {
+        let query = query.to_lowercase();
+        let items = EMISSION_FACTORS
+            .iter()
+            .filter(|(name, _)| name.contains(&query))
+            .map(|(name, factor)| CompletionItem::new(*name).detail(*factor))
+            .collect();
+        Task::ready(Ok(items))
+    }
+}
+
 pub fn init(cx: &mut App) {
     cx.bind_keys([
         KeyBinding::new("shift-tab", TabPrev, Some(CONTEXT)),
@@ -29,6 +68,8 @@ pub struct InputStory {
     input1: Entity<TextInput>,
     input2: Entity<TextInput>,
     textarea: Entity<TextInput>,
+    code_editor: Entity<TextInput>,
+    completion_input: Entity<TextInput>,
     number_input1_value: i64,
     number_input1: Entity<NumberInput>,
     number_input2: Entity<NumberInput>,
@@ -118,6 +159,30 @@ impl InputStory {
         cx.subscribe_in(&textarea, window, Self::on_input_event)
             .detach();
 
+        let code_editor = cx.new(|cx| {
+            let mut input = TextInput::new(window, cx).code_editor("json").rows(10);
+            input.set_text(
+                unindent::unindent(
+                    r#"{
+                        "name": "GPUI Component",
+                        "version": "0.1.0",
+                        "components": ["Button", "Input", "Table"],
+                        "stable": false,
+                        "stars": 1024
+                    }"#,
+                ),
+                window,
+                cx,
+            );
+            input
+        });
+
+        let completion_input = cx.new(|cx| {
+            TextInput::new(window, cx)
+                .completion_provider(Rc::new(EmissionFactorCompletion))
+                .placeholder("Type a formula, e.g.: 120 * electricity_grid, or press ctrl-space.")
+        });
+
         let number_input1_value = 1;
         let number_input1 = cx.new(|cx| {
             let input = NumberInput::new(window, cx).placeholder("Number Input", window, cx);
@@ -179,6 +244,8 @@ impl InputStory {
             input1,
             input2,
             textarea,
+            code_editor,
+            completion_input,
             number_input1,
             number_input1_value,
             number_input2,
@@ -443,6 +510,8 @@ impl Render for InputStory {
                             .child(self.mash_input.clone()),
                     ),
             )
+            .child(section("Code Editor", cx).child(self.code_editor.clone()))
+            .child(section("Completion", cx).child(self.completion_input.clone()))
             .child(
                 h_flex()
                     .gap_3()
@@ -7,19 +7,24 @@ use fake::{Fake, Faker};
 use gpui::{
     div, impl_internal_actions, prelude::FluentBuilder as _, px, AnyElement, App, AppContext,
     Context, Edges, Entity, Focusable, InteractiveElement, IntoElement, ParentElement, Pixels,
-    Render, SharedString, Styled, Timer, Window,
+    Render, SharedString, Styled, Task, Timer, Window,
 };
 use gpui_component::{
     button::Button,
     checkbox::Checkbox,
+    data_source::{PagedDataSource, PagedRows, RowPlaceholder, RowState},
     green, h_flex,
     indicator::Indicator,
     input::{InputEvent, TextInput},
     label::Label,
     popup_menu::{PopupMenu, PopupMenuExt},
     red,
-    table::{self, ColFixed, ColSort, Table, TableDelegate, TableEvent},
-    v_flex, ActiveTheme as _, Selectable, Sizable as _, Size, StyleSized as _,
+    table::{
+        self, Aggregate, CellStyle, CellValue, ColFilter, ColFilterKind, ColFixed, ColSort,
+        ExportFormat, ExportRows, FormatRule, Table, TableDelegate, TableEvent, TableLayoutState,
+    },
+    v_flex, ActiveTheme as _, ColorName, Disableable as _, Selectable, Sizable as _, Size,
+    StyleSized as _,
 };
 use serde::Deserialize;
 
@@ -173,6 +178,8 @@ impl Column {
 
 struct StockTableDelegate {
     stocks: Vec<Stock>,
+    /// All the stocks before the column filters are applied, `None` if there is no filter.
+    unfiltered_stocks: Option<Vec<Stock>>,
     columns: Vec<Column>,
     size: Size,
     loop_selection: bool,
@@ -183,9 +190,14 @@ struct StockTableDelegate {
     loading: bool,
     full_loading: bool,
     fixed_cols: bool,
+    group_rows: bool,
+    tall_rows: bool,
+    format_cells: bool,
     eof: bool,
     visible_rows: Range<usize>,
     visible_cols: Range<usize>,
+    /// The input to edit the name of the stock.
+    editor: Option<Entity<TextInput>>,
 }
 
 impl StockTableDelegate {
@@ -193,6 +205,7 @@ impl StockTableDelegate {
         Self {
             size: Size::default(),
             stocks: random_stocks(size),
+            unfiltered_stocks: None,
             columns: vec![
                 Column::new("id", "ID", None),
                 Column::new("symbol", "Symbol", Some(ColSort::Default)),
@@ -245,16 +258,38 @@ impl StockTableDelegate {
             col_sort: true,
             col_selection: true,
             fixed_cols: false,
+            group_rows: false,
+            tall_rows: false,
+            format_cells: false,
             loading: false,
             full_loading: false,
             eof: false,
             visible_cols: Range::default(),
             visible_rows: Range::default(),
+            editor: None,
+        }
+    }
+
+    /// Returns the group of the stock, every 50 stocks by the id.
+    fn group_ix(stock: &Stock) -> usize {
+        stock.id / 50
+    }
+
+    /// Sort the stocks by the group first if the rows are grouped, so the rows of a group are adjacent.
+    ///
+    /// The sort is stable, the stocks in a group keep the order of the column sort.
+    fn sort_by_group(&mut self) {
+        if self.group_rows {
+            self.stocks.sort_by_key(Self::group_ix);
+            if let Some(stocks) = self.unfiltered_stocks.as_mut() {
+                stocks.sort_by_key(Self::group_ix);
+            }
         }
     }
 
     fn update_stocks(&mut self, size: usize) {
         self.stocks = random_stocks(size);
+        self.unfiltered_stocks = None;
         self.eof = size <= 50;
         self.loading = false;
         self.full_loading = false;
@@ -305,6 +340,13 @@ impl TableDelegate for StockTableDelegate {
         }
     }
 
+    fn col_key(&self, col_ix: usize, _: &App) -> SharedString {
+        self.columns
+            .get(col_ix)
+            .map(|col| col.id.clone())
+            .unwrap_or_default()
+    }
+
     fn col_width(&self, col_ix: usize, _: &App) -> Pixels {
         if col_ix < 10 {
             120.0.into()
@@ -330,6 +372,8 @@ impl TableDelegate for StockTableDelegate {
 
         if col_ix < 4 {
             Some(ColFixed::Left)
+        } else if col_ix + 1 == self.columns.len() {
+            Some(ColFixed::Right)
         } else {
             None
         }
@@ -376,6 +420,20 @@ impl TableDelegate for StockTableDelegate {
         .menu("Size XSmall", Box::new(ChangeSize(Size::XSmall)))
     }
 
+    fn header_context_menu(
+        &self,
+        _col_ix: usize,
+        menu: PopupMenu,
+        _window: &Window,
+        _cx: &App,
+    ) -> PopupMenu {
+        menu.separator()
+            .menu("Size Large", Box::new(ChangeSize(Size::Large)))
+            .menu("Size Medium", Box::new(ChangeSize(Size::Medium)))
+            .menu("Size Small", Box::new(ChangeSize(Size::Small)))
+            .menu("Size XSmall", Box::new(ChangeSize(Size::XSmall)))
+    }
+
     /// NOTE: Performance metrics
     ///
     /// last render 561 cells total: 232.745µs, avg: 414ns
@@ -449,6 +507,232 @@ impl TableDelegate for StockTableDelegate {
         }
     }
 
+    fn can_edit_cell(&self, _: usize, col_ix: usize, _: &App) -> bool {
+        self.columns
+            .get(col_ix)
+            .map_or(false, |col| col.id.as_ref() == "name")
+    }
+
+    fn start_cell_edit(
+        &mut self,
+        row_ix: usize,
+        _: usize,
+        window: &mut Window,
+        cx: &mut Context<Table<Self>>,
+    ) {
+        let name = self.stocks[row_ix].name.clone();
+        let editor = cx.new(|cx| {
+            let mut input = TextInput::new(window, cx).appearance(false).small();
+            input.set_text(name, window, cx);
+            input
+        });
+        editor.focus_handle(cx).focus(window);
+        self.editor = Some(editor);
+    }
+
+    fn render_cell_editor(
+        &self,
+        _: usize,
+        _: usize,
+        _: &mut Window,
+        _: &mut Context<Table<Self>>,
+    ) -> impl IntoElement {
+        self.editor.clone()
+    }
+
+    fn commit_cell_edit(
+        &mut self,
+        row_ix: usize,
+        _: usize,
+        _: &mut Window,
+        cx: &mut Context<Table<Self>>,
+    ) -> bool {
+        let Some(editor) = self.editor.take() else {
+            return true;
+        };
+
+        self.stocks[row_ix].name = editor.read(cx).text();
+        true
+    }
+
+    fn cancel_cell_edit(
+        &mut self,
+        _: usize,
+        _: usize,
+        _: &mut Window,
+        _: &mut Context<Table<Self>>,
+    ) {
+        self.editor = None;
+    }
+
+    fn has_footer(&self, _: &App) -> bool {
+        true
+    }
+
+    fn footer_aggregate(&self, col_ix: usize, _: &App) -> Option<Aggregate> {
+        match self.columns.get(col_ix)?.id.as_ref() {
+            "id" => Some(Aggregate::Count),
+            "price" | "change_percent" => Some(Aggregate::Avg),
+            "volume" | "turnover" => Some(Aggregate::Sum),
+            _ => None,
+        }
+    }
+
+    fn cell_value(&self, row_ix: usize, col_ix: usize, _: &App) -> CellValue {
+        let Some(stock) = self.stocks.get(row_ix) else {
+            return CellValue::Empty;
+        };
+        let Some(col) = self.columns.get(col_ix) else {
+            return CellValue::Empty;
+        };
+
+        match col.id.as_ref() {
+            "id" => (stock.id as f64).into(),
+            "symbol" => stock.symbol.clone().into(),
+            "name" => stock.name.clone().into(),
+            "price" => stock.price.into(),
+            "change" => stock.change.into(),
+            "change_percent" => stock.change_percent.into(),
+            "volume" => stock.volume.into(),
+            "turnover" => stock.turnover.into(),
+            "market_cap" => stock.market_cap.into(),
+            "ttm" => stock.ttm.into(),
+            "five_mins_ranking" => stock.five_mins_ranking.into(),
+            "th60_days_ranking" => stock.th60_days_ranking.into(),
+            "year_change_percent" => stock.year_change_percent.into(),
+            "bid" => stock.bid.into(),
+            "bid_volume" => stock.bid_volume.into(),
+            "ask" => stock.ask.into(),
+            "ask_volume" => stock.ask_volume.into(),
+            "open" => stock.open.into(),
+            "prev_close" => stock.prev_close.into(),
+            "high" => stock.high.into(),
+            "low" => stock.low.into(),
+            "turnover_rate" => stock.turnover_rate.into(),
+            "rise_rate" => stock.rise_rate.into(),
+            "amplitude" => stock.amplitude.into(),
+            "pe_status" => stock.pe_status.into(),
+            "pb_status" => stock.pb_status.into(),
+            "volume_ratio" => stock.volume_ratio.into(),
+            "bid_ask_ratio" => stock.bid_ask_ratio.into(),
+            "latest_pre_close" => stock.latest_pre_close.into(),
+            "latest_post_close" => stock.latest_post_close.into(),
+            "pre_market_cap" => stock.pre_market_cap.into(),
+            "pre_market_percent" => stock.pre_market_percent.into(),
+            "pre_market_change" => stock.pre_market_change.into(),
+            "post_market_cap" => stock.post_market_cap.into(),
+            "post_market_percent" => stock.post_market_percent.into(),
+            "post_market_change" => stock.post_market_change.into(),
+            "float_cap" => stock.float_cap.into(),
+            "day_5_ranking" => stock.day_5_ranking.into(),
+            "day_10_ranking" => stock.day_10_ranking.into(),
+            "day_30_ranking" => stock.day_30_ranking.into(),
+            "day_120_ranking" => stock.day_120_ranking.into(),
+            "day_250_ranking" => stock.day_250_ranking.into(),
+            "shares" => (stock.shares as f64).into(),
+            "shares_float" => (stock.shares_float as f64).into(),
+            _ => CellValue::Empty,
+        }
+    }
+
+    fn cell_number(&self, row_ix: usize, col_ix: usize, _: &App) -> Option<f64> {
+        let stock = self.stocks.get(row_ix)?;
+        match self.columns.get(col_ix)?.id.as_ref() {
+            "id" => Some(stock.id as f64),
+            "price" => Some(stock.price),
+            "change_percent" => Some(stock.change_percent),
+            "volume" => Some(stock.volume),
+            "turnover" => Some(stock.turnover),
+            _ => None,
+        }
+    }
+
+    fn col_filter_kind(&self, col_ix: usize, _: &App) -> Option<ColFilterKind> {
+        match self.columns.get(col_ix)?.id.as_ref() {
+            "symbol" | "name" => Some(ColFilterKind::Text),
+            "price" | "change" | "change_percent" | "volume" | "turnover" => {
+                Some(ColFilterKind::Number)
+            }
+            _ => None,
+        }
+    }
+
+    fn perform_filter(
+        &mut self,
+        filters: &[(usize, ColFilter)],
+        _: &mut Window,
+        cx: &mut Context<Table<Self>>,
+    ) {
+        if let Some(stocks) = self.unfiltered_stocks.take() {
+            self.stocks = stocks;
+        }
+        if filters.is_empty() {
+            return;
+        }
+
+        let matched = (0..self.stocks.len())
+            .map(|row_ix| {
+                filters
+                    .iter()
+                    .all(|(col_ix, filter)| filter.matches(&self.cell_text(row_ix, *col_ix, cx)))
+            })
+            .collect::<Vec<_>>();
+        let stocks = std::mem::take(&mut self.stocks);
+        self.stocks = stocks
+            .iter()
+            .zip(matched)
+            .filter_map(|(stock, matched)| matched.then(|| stock.clone()))
+            .collect();
+        self.unfiltered_stocks = Some(stocks);
+    }
+
+    fn has_row_groups(&self, _: &App) -> bool {
+        self.group_rows
+    }
+
+    fn row_group(&self, row_ix: usize, _: &App) -> Option<SharedString> {
+        let stock = self.stocks.get(row_ix)?;
+        Some(format!("Group {}", Self::group_ix(stock) + 1).into())
+    }
+
+    fn col_format_rules(&self, col_ix: usize, cx: &App) -> Vec<FormatRule> {
+        if !self.format_cells {
+            return vec![];
+        }
+
+        match self.columns[col_ix].id.as_ref() {
+            "price" => vec![FormatRule::top(10, CellStyle::danger(cx.theme()))],
+            "change_percent" => vec![
+                FormatRule::above(0.05, CellStyle::color(ColorName::Green)),
+                FormatRule::below(-0.05, CellStyle::color(ColorName::Red)),
+            ],
+            "volume" => vec![FormatRule::data_bar(ColorName::Blue)],
+            "turnover" => vec![FormatRule::color_scale(ColorName::Amber)],
+            _ => vec![],
+        }
+    }
+
+    fn has_row_heights(&self, _: &App) -> bool {
+        self.tall_rows
+    }
+
+    fn row_height(&self, row_ix: usize, _: &App) -> Option<Pixels> {
+        // Make every 3rd row taller to wrap the long text.
+        if row_ix % 3 == 0 {
+            Some(px(64.))
+        } else {
+            None
+        }
+    }
+
+    fn group_aggregate(&self, col_ix: usize, _: &App) -> Option<Aggregate> {
+        match self.columns.get(col_ix)?.id.as_ref() {
+            "price" | "change_percent" => Some(Aggregate::Avg),
+            "volume" | "turnover" => Some(Aggregate::Sum),
+            _ => None,
+        }
+    }
+
     fn can_loop_select(&self, _: &App) -> bool {
         self.loop_selection
     }
@@ -507,6 +791,49 @@ impl TableDelegate for StockTableDelegate {
                 _ => {}
             }
         }
+        self.sort_by_group();
+    }
+
+    fn perform_multi_sort(
+        &mut self,
+        sorts: &[(usize, ColSort)],
+        _: &mut Window,
+        _: &mut Context<Table<Self>>,
+    ) {
+        if !self.col_sort {
+            return;
+        }
+
+        let keys = sorts
+            .iter()
+            .filter_map(|(col_ix, sort)| {
+                self.columns.get(*col_ix).map(|col| (col.id.clone(), *sort))
+            })
+            .collect::<Vec<_>>();
+
+        self.stocks.sort_by(|a, b| {
+            for (id, sort) in keys.iter() {
+                let ordering = match id.as_ref() {
+                    "symbol" => Some(a.symbol.cmp(&b.symbol)),
+                    "price" => a.price.partial_cmp(&b.price),
+                    "change" => a.change.partial_cmp(&b.change),
+                    "change_percent" => a.change_percent.partial_cmp(&b.change_percent),
+                    _ => None,
+                }
+                .unwrap_or(std::cmp::Ordering::Equal);
+
+                let ordering = match sort {
+                    ColSort::Descending => ordering.reverse(),
+                    _ => ordering,
+                };
+                if ordering.is_ne() {
+                    return ordering;
+                }
+            }
+
+            a.id.cmp(&b.id)
+        });
+        self.sort_by_group();
     }
 
     fn loading(&self, _: &App) -> bool {
@@ -531,6 +858,7 @@ impl TableDelegate for StockTableDelegate {
             cx.update(|cx| {
                 let _ = view.update(cx, |view, _| {
                     view.delegate_mut().stocks.extend(random_stocks(200));
+                    view.delegate_mut().sort_by_group();
                     view.delegate_mut().loading = false;
                     view.delegate_mut().eof = view.delegate().stocks.len() >= 6000;
                 });
@@ -558,12 +886,264 @@ impl TableDelegate for StockTableDelegate {
     }
 }
 
+/// The (name, depth, amount) of the cost items, in the pre-order of the tree.
+const COST_ITEMS: &[(&str, usize, f64)] = &[
+    ("Building", 0, 1_280_000.),
+    ("Foundation", 1, 320_000.),
+    ("Concrete", 2, 180_000.),
+    ("Rebar", 2, 140_000.),
+    ("Structure", 1, 640_000.),
+    ("Steel", 2, 410_000.),
+    ("Labor", 2, 230_000.),
+    ("Finishing", 1, 320_000.),
+    ("Road", 0, 460_000.),
+    ("Asphalt", 1, 300_000.),
+    ("Machine", 1, 160_000.),
+];
+
+/// The delegate of the tree table of the cost items.
+struct CostTreeDelegate {
+    expanded: Vec<bool>,
+    /// The item index of each visible row.
+    rows: Vec<usize>,
+}
+
+impl CostTreeDelegate {
+    fn new() -> Self {
+        let mut this = Self {
+            expanded: vec![true; COST_ITEMS.len()],
+            rows: vec![],
+        };
+        this.update_rows();
+        this
+    }
+
+    fn has_children(item_ix: usize) -> bool {
+        COST_ITEMS
+            .get(item_ix + 1)
+            .map_or(false, |next| next.1 > COST_ITEMS[item_ix].1)
+    }
+
+    /// Flatten the items into the rows, the children of the collapsed items are skipped.
+    fn update_rows(&mut self) {
+        self.rows.clear();
+        let mut collapsed_depth = None;
+        for (item_ix, (_, depth, _)) in COST_ITEMS.iter().enumerate() {
+            match collapsed_depth {
+                Some(collapsed_depth) if *depth > collapsed_depth => continue,
+                _ => collapsed_depth = None,
+            }
+
+            self.rows.push(item_ix);
+            if !self.expanded[item_ix] && Self::has_children(item_ix) {
+                collapsed_depth = Some(*depth);
+            }
+        }
+    }
+}
+
+impl TableDelegate for CostTreeDelegate {
+    fn cols_count(&self, _: &App) -> usize {
+        2
+    }
+
+    fn rows_count(&self, _: &App) -> usize {
+        self.rows.len()
+    }
+
+    fn col_name(&self, col_ix: usize, _: &App) -> SharedString {
+        match col_ix {
+            0 => "Name".into(),
+            _ => "Amount".into(),
+        }
+    }
+
+    fn col_width(&self, col_ix: usize, _: &App) -> Pixels {
+        match col_ix {
+            0 => px(240.),
+            _ => px(140.),
+        }
+    }
+
+    fn render_td(
+        &self,
+        row_ix: usize,
+        col_ix: usize,
+        _: &mut Window,
+        cx: &mut Context<Table<Self>>,
+    ) -> impl IntoElement {
+        self.cell_text(row_ix, col_ix, cx)
+    }
+
+    fn cell_value(&self, row_ix: usize, col_ix: usize, _: &App) -> CellValue {
+        let Some((name, _, amount)) = self.rows.get(row_ix).map(|ix| COST_ITEMS[*ix]) else {
+            return CellValue::Empty;
+        };
+
+        match col_ix {
+            0 => SharedString::from(name).into(),
+            _ => amount.into(),
+        }
+    }
+
+    fn is_tree(&self, _: &App) -> bool {
+        true
+    }
+
+    fn row_depth(&self, row_ix: usize, _: &App) -> usize {
+        self.rows.get(row_ix).map_or(0, |ix| COST_ITEMS[*ix].1)
+    }
+
+    fn row_has_children(&self, row_ix: usize, _: &App) -> bool {
+        self.rows
+            .get(row_ix)
+            .map_or(false, |ix| Self::has_children(*ix))
+    }
+
+    fn is_row_expanded(&self, row_ix: usize, _: &App) -> bool {
+        self.rows.get(row_ix).map_or(false, |ix| self.expanded[*ix])
+    }
+
+    fn toggle_expand(&mut self, row_ix: usize, _: &mut Window, _: &mut Context<Table<Self>>) {
+        let Some(item_ix) = self.rows.get(row_ix).copied() else {
+            return;
+        };
+
+        self.expanded[item_ix] = !self.expanded[item_ix];
+        self.update_rows();
+    }
+}
+
+/// A row of the `RemoteSource`.
+struct Order {
+    id: usize,
+    customer: SharedString,
+    amount: f64,
+}
+
+/// A simulated remote data source with 2000 rows, about 10% of the pages are failed to fetch.
+struct RemoteSource;
+
+impl RemoteSource {
+    const TOTAL: usize = 2000;
+}
+
+impl PagedDataSource for RemoteSource {
+    type Row = Order;
+
+    fn page_size(&self) -> usize {
+        50
+    }
+
+    fn fetch_page(
+        &self,
+        page: usize,
+        page_size: usize,
+        cx: &mut App,
+    ) -> Task<anyhow::Result<Vec<Self::Row>>> {
+        let delay = cx.background_executor().timer(Duration::from_millis(500));
+        cx.background_executor().spawn(async move {
+            // Simulate the network request.
+            delay.await;
+            if (0..10).fake::<usize>() == 0 {
+                anyhow::bail!("Failed to fetch page {}", page);
+            }
+
+            let start = page * page_size;
+            let end = (start + page_size).min(RemoteSource::TOTAL);
+            Ok((start..end)
+                .map(|id| Order {
+                    id,
+                    customer: Faker.fake::<String>().into(),
+                    amount: (0.0..10000.0).fake(),
+                })
+                .collect())
+        })
+    }
+}
+
+/// The delegate of the table that fetches the rows by pages when they are visible.
+struct PagedTableDelegate {
+    rows: PagedRows<RemoteSource>,
+}
+
+fn paged_rows(table: &mut Table<PagedTableDelegate>) -> &mut PagedRows<RemoteSource> {
+    &mut table.delegate_mut().rows
+}
+
+impl TableDelegate for PagedTableDelegate {
+    fn cols_count(&self, _: &App) -> usize {
+        3
+    }
+
+    fn rows_count(&self, _: &App) -> usize {
+        self.rows.rows_count()
+    }
+
+    fn col_name(&self, col_ix: usize, _: &App) -> SharedString {
+        match col_ix {
+            0 => "ID".into(),
+            1 => "Customer".into(),
+            _ => "Amount".into(),
+        }
+    }
+
+    fn col_width(&self, col_ix: usize, _: &App) -> Pixels {
+        match col_ix {
+            0 => px(80.),
+            _ => px(240.),
+        }
+    }
+
+    fn render_td(
+        &self,
+        row_ix: usize,
+        col_ix: usize,
+        _: &mut Window,
+        cx: &mut Context<Table<Self>>,
+    ) -> impl IntoElement {
+        let state = self.rows.row(row_ix);
+        let RowState::Loaded(order) = state else {
+            if col_ix > 0 {
+                return div().into_any_element();
+            }
+
+            return RowPlaceholder::new(("paged-row", row_ix), &state)
+                .on_retry(cx.listener(move |table, _, _, cx| {
+                    table.delegate_mut().rows.retry(row_ix, cx, paged_rows);
+                }))
+                .into_any_element();
+        };
+
+        match col_ix {
+            0 => order.id.to_string().into_any_element(),
+            1 => order.customer.clone().into_any_element(),
+            _ => format!("{:.2}", order.amount).into_any_element(),
+        }
+    }
+
+    fn visible_rows_changed(
+        &mut self,
+        visible_range: Range<usize>,
+        _: &mut Window,
+        cx: &mut Context<Table<Self>>,
+    ) {
+        self.rows.load(visible_range, cx, paged_rows);
+    }
+}
+
 pub struct TableStory {
     table: Entity<Table<StockTableDelegate>>,
+    tree_table: Entity<Table<CostTreeDelegate>>,
+    show_tree: bool,
+    paged_table: Entity<Table<PagedTableDelegate>>,
+    show_paged: bool,
     num_stocks_input: Entity<TextInput>,
     stripe: bool,
     refresh_data: bool,
     size: Size,
+    /// The saved columns layout.
+    layout: Option<TableLayoutState>,
 }
 
 impl super::Story for TableStory {
@@ -607,6 +1187,11 @@ impl TableStory {
 
         let delegate = StockTableDelegate::new(5000);
         let table = cx.new(|cx| Table::new(delegate, window, cx));
+        let tree_table = cx.new(|cx| Table::new(CostTreeDelegate::new(), window, cx));
+        let paged_delegate = PagedTableDelegate {
+            rows: PagedRows::new(RemoteSource),
+        };
+        let paged_table = cx.new(|cx| Table::new(paged_delegate, window, cx));
 
         cx.subscribe_in(&table, window, Self::on_table_event)
             .detach();
@@ -623,7 +1208,7 @@ impl TableStory {
                         return;
                     }
 
-                    this.table.update(cx, |table, _| {
+                    this.table.update(cx, |table, cx| {
                         table.delegate_mut().stocks.iter_mut().enumerate().for_each(
                             |(i, stock)| {
                                 let n = (3..10).fake::<usize>();
@@ -633,6 +1218,7 @@ impl TableStory {
                                 }
                             },
                         );
+                        table.refresh_rows(cx);
                     });
                     cx.notify();
                 })
@@ -643,9 +1229,14 @@ impl TableStory {
 
         Self {
             table,
+            tree_table,
+            show_tree: false,
+            paged_table,
+            show_paged: false,
             num_stocks_input,
             stripe: false,
             refresh_data: false,
+            layout: None,
             size: Size::default(),
         }
     }
@@ -655,7 +1246,7 @@ impl TableStory {
         &mut self,
         _: &Entity<TextInput>,
         event: &InputEvent,
-        _: &mut Window,
+        window: &mut Window,
         cx: &mut Context<Self>,
     ) {
         match event {
@@ -663,8 +1254,10 @@ impl TableStory {
             InputEvent::PressEnter | InputEvent::Blur => {
                 let text = self.num_stocks_input.read(cx).text().to_string();
                 if let Ok(num) = text.parse::<usize>() {
-                    self.table.update(cx, |table, _| {
+                    self.table.update(cx, |table, cx| {
                         table.delegate_mut().update_stocks(num);
+                        table.clear_filters(window, cx);
+                        table.refresh_rows(cx);
                     });
                     cx.notify();
                 }
@@ -729,6 +1322,28 @@ impl TableStory {
         });
     }
 
+    fn toggle_group_rows(&mut self, checked: &bool, _: &mut Window, cx: &mut Context<Self>) {
+        self.table.update(cx, |table, cx| {
+            table.delegate_mut().group_rows = *checked;
+            table.delegate_mut().sort_by_group();
+            table.refresh_rows(cx);
+        });
+    }
+
+    fn toggle_format_cells(&mut self, checked: &bool, _: &mut Window, cx: &mut Context<Self>) {
+        self.table.update(cx, |table, cx| {
+            table.delegate_mut().format_cells = *checked;
+            table.refresh_rows(cx);
+        });
+    }
+
+    fn toggle_tall_rows(&mut self, checked: &bool, _: &mut Window, cx: &mut Context<Self>) {
+        self.table.update(cx, |table, cx| {
+            table.delegate_mut().tall_rows = *checked;
+            table.refresh_rows(cx);
+        });
+    }
+
     fn on_change_size(&mut self, a: &ChangeSize, _: &mut Window, cx: &mut Context<Self>) {
         self.size = a.0;
         self.table.update(cx, |table, cx| {
@@ -759,6 +1374,20 @@ impl TableStory {
             TableEvent::MoveCol(origin_idx, target_idx) => {
                 println!("Move col index: {} -> {}", origin_idx, target_idx);
             }
+            TableEvent::CellEdited(row_ix, col_ix) => {
+                println!("Cell edited: ({}, {})", row_ix, col_ix);
+            }
+            TableEvent::SelectionChanged(selection) => {
+                println!("Selection changed: {:?}", selection.ranges());
+            }
+            TableEvent::FilterChanged(filters) => println!("Filter changed: {:?}", filters),
+            TableEvent::GroupToggled(key, collapsed) => {
+                println!("Group toggled: {} collapsed: {}", key, collapsed);
+            }
+            TableEvent::RowExpandToggled(ix) => println!("Row expand toggled: {}", ix),
+            TableEvent::ColHiddenChanged(ix, hidden) => {
+                println!("Col hidden changed: {} hidden: {}", ix, hidden);
+            }
         }
     }
 }
@@ -821,6 +1450,42 @@ impl Render for TableStory {
                             .selected(delegate.fixed_cols)
                             .on_click(cx.listener(Self::toggle_fixed_cols)),
                     )
+                    .child(
+                        Checkbox::new("group-rows")
+                            .label("Group Rows")
+                            .selected(delegate.group_rows)
+                            .on_click(cx.listener(Self::toggle_group_rows)),
+                    )
+                    .child(
+                        Checkbox::new("tall-rows")
+                            .label("Tall Rows")
+                            .selected(delegate.tall_rows)
+                            .on_click(cx.listener(Self::toggle_tall_rows)),
+                    )
+                    .child(
+                        Checkbox::new("tree")
+                            .label("Tree")
+                            .selected(self.show_tree)
+                            .on_click(cx.listener(|this, checked: &bool, _, cx| {
+                                this.show_tree = *checked;
+                                cx.notify();
+                            })),
+                    )
+                    .child(
+                        Checkbox::new("paged")
+                            .label("Paged")
+                            .selected(self.show_paged)
+                            .on_click(cx.listener(|this, checked: &bool, _, cx| {
+                                this.show_paged = *checked;
+                                cx.notify();
+                            })),
+                    )
+                    .child(
+                        Checkbox::new("format-cells")
+                            .label("Format Cells")
+                            .selected(delegate.format_cells)
+                            .on_click(cx.listener(Self::toggle_format_cells)),
+                    )
                     .child(
                         Checkbox::new("loading")
                             .label("Loading")
@@ -888,6 +1553,60 @@ impl Render for TableStory {
                                     table.scroll_to_row(table.delegate().rows_count(cx) - 1, cx);
                                 })
                             })),
+                    )
+                    .child(
+                        Button::new("save-layout")
+                            .child("Save Layout")
+                            .small()
+                            .on_click(cx.listener(|this, _, _, cx| {
+                                let layout = this.table.read(cx).dump_layout(cx);
+                                println!(
+                                    "Save layout: {}",
+                                    serde_json::to_string(&layout).unwrap_or_default()
+                                );
+                                this.layout = Some(layout);
+                            })),
+                    )
+                    .child(
+                        Button::new("load-layout")
+                            .child("Load Layout")
+                            .small()
+                            .disabled(self.layout.is_none())
+                            .on_click(cx.listener(|this, _, window, cx| {
+                                let Some(layout) = this.layout.clone() else {
+                                    return;
+                                };
+                                this.table.update(cx, |table, cx| {
+                                    table.load_layout(layout, window, cx);
+                                })
+                            })),
+                    )
+                    .child(
+                        Button::new("export-csv")
+                            .child("Export CSV")
+                            .small()
+                            .on_click(cx.listener(|this, _, _, cx| {
+                                // Export the selected rows to stdout.
+                                let mut stdout = std::io::stdout();
+                                if let Err(err) = this.table.read(cx).export(
+                                    &mut stdout,
+                                    ExportFormat::Csv,
+                                    ExportRows::Selected,
+                                    cx,
+                                ) {
+                                    println!("Failed to export: {}", err);
+                                }
+                            })),
+                    )
+                    .child(
+                        Button::new("auto-fit-cols")
+                            .child("Auto Fit Columns")
+                            .small()
+                            .on_click(cx.listener(|this, _, window, cx| {
+                                this.table.update(cx, |table, cx| {
+                                    table.auto_fit_columns(window, cx);
+                                })
+                            })),
                     ), // .child(
                        //     Button::new("scroll-first-col")
                        //         .child("Scroll to First Column")
@@ -930,6 +1649,14 @@ impl Render for TableStory {
                         .when(delegate.eof, |this| this.child("All data loaded.")),
                 ),
             )
-            .child(self.table.clone())
+            .map(|this| {
+                if self.show_paged {
+                    this.child(self.paged_table.clone())
+                } else if self.show_tree {
+                    this.child(self.tree_table.clone())
+                } else {
+                    this.child(self.table.clone())
+                }
+            })
     }
 }
@@ -0,0 +1,418 @@
+//! Paged data source for the `Table` and `List`.
+//!
+//! The [`PagedRows`] fetches the rows page by page from a [`PagedDataSource`] when they become visible,
+//! a page is only fetched once at a time, and the fetching is cancelled if the page is scrolled out
+//! before it is done.
+//!
+//! The `PagedRows` is owned by the delegate, for example of the `Table`:
+//!
+//! - `TableDelegate::rows_count` returns [`PagedRows::rows_count`].
+//! - `TableDelegate::visible_rows_changed` calls [`PagedRows::load`].
+//! - `TableDelegate::render_td` renders the [`PagedRows::row`], or a [`RowPlaceholder`] if it is not loaded.
+//!
+//! The `List` is the same with `ListDelegate::items_count`, `ListDelegate::visible_items_changed`
+//! and `ListDelegate::render_item`.
+use std::{collections::HashMap, ops::Range, rc::Rc};
+
+use gpui::{
+    div, prelude::FluentBuilder as _, App, ClickEvent, Context, ElementId, IntoElement,
+    ParentElement as _, RenderOnce, SharedString, Styled as _, Task, Window,
+};
+use rust_i18n::t;
+
+use crate::{
+    button::{Button, ButtonVariants as _},
+    h_flex,
+    skeleton::Skeleton,
+    ActiveTheme as _, Sizable as _,
+};
+
+/// A data source to fetch the rows by pages, used by [`PagedRows`].
+pub trait PagedDataSource: 'static {
+    type Row: 'static;
+
+    /// Returns the number of rows in a page.
+    ///
+    /// Default: 100
+    fn page_size(&self) -> usize {
+        100
+    }
+
+    /// Fetch the rows of the given page, that is the rows in `page * page_size..(page + 1) * page_size`.
+    ///
+    /// Returns fewer rows than the `page_size` means there is no more rows after this page.
+    ///
+    /// The task is dropped to cancel the fetching when the page is scrolled out before it is done,
+    /// the blocking work (e.g.: the database query) should be done in a background task.
+    fn fetch_page(
+        &self,
+        page: usize,
+        page_size: usize,
+        cx: &mut App,
+    ) -> Task<anyhow::Result<Vec<Self::Row>>>;
+}
+
+/// The state of a row in the [`PagedRows`].
+#[derive(Debug, Clone, PartialEq)]
+pub enum RowState<'a, T> {
+    /// The page of the row is being fetched, or not fetched yet.
+    Loading,
+    /// The row is loaded.
+    Loaded(&'a T),
+    /// Failed to fetch the page of the row, with the error message.
+    Failed(SharedString),
+}
+
+enum Page<T> {
+    /// The page is being fetched, drop the task to cancel it.
+    Loading(Task<()>),
+    Loaded(Vec<T>),
+    Failed(SharedString),
+}
+
+/// The rows fetched page by page from a [`PagedDataSource`].
+///
+/// The `view` argument of the methods is the `Table` or `List` view that owns the delegate,
+/// and the `rows` argument returns this `PagedRows` from the view, to store the fetched pages.
+pub struct PagedRows<S: PagedDataSource> {
+    source: S,
+    pages: HashMap<usize, Page<S::Row>>,
+    /// The total number of rows, None if it is not known yet.
+    total: Option<usize>,
+    /// The last visible range, to fetch the rows again after reset.
+    visible_range: Range<usize>,
+}
+
+impl<S: PagedDataSource> PagedRows<S> {
+    pub fn new(source: S) -> Self {
+        Self {
+            source,
+            pages: HashMap::new(),
+            total: None,
+            visible_range: 0..0,
+        }
+    }
+
+    pub fn source(&self) -> &S {
+        &self.source
+    }
+
+    pub fn source_mut(&mut self) -> &mut S {
+        &mut self.source
+    }
+
+    fn page_size(&self) -> usize {
+        self.source.page_size().max(1)
+    }
+
+    /// Set the total number of rows, if the data source knows it, e.g.: by `SELECT COUNT(*)`.
+    ///
+    /// Otherwise, a page of placeholder rows is shown after the loaded pages to fetch more rows,
+    /// until a page returns fewer rows than the page size.
+    pub fn set_total(&mut self, total: Option<usize>) {
+        self.total = total;
+    }
+
+    /// Returns the number of rows, including the placeholder rows that are not loaded.
+    pub fn rows_count(&self) -> usize {
+        if let Some(total) = self.total {
+            return total;
+        }
+
+        let loaded_pages = self
+            .pages
+            .iter()
+            .filter(|(_, page)| matches!(page, Page::Loaded(_)))
+            .map(|(page, _)| page + 1)
+            .max()
+            .unwrap_or(0);
+        (loaded_pages + 1) * self.page_size()
+    }
+
+    /// Returns the state of the row at the given index.
+    pub fn row(&self, row_ix: usize) -> RowState<'_, S::Row> {
+        let page_size = self.page_size();
+        match self.pages.get(&(row_ix / page_size)) {
+            Some(Page::Loaded(rows)) => rows
+                .get(row_ix % page_size)
+                .map_or(RowState::Loading, RowState::Loaded),
+            Some(Page::Failed(err)) => RowState::Failed(err.clone()),
+            _ => RowState::Loading,
+        }
+    }
+
+    /// Returns true if there is any page being fetched.
+    pub fn is_loading(&self) -> bool {
+        self.pages
+            .values()
+            .any(|page| matches!(page, Page::Loading(_)))
+    }
+
+    /// Fetch the pages of the visible rows that are not loaded or being fetched,
+    /// and cancel the fetching of the pages that are out of the visible range.
+    ///
+    /// The failed pages are not fetched again, see [`PagedRows::retry`].
+    pub fn load<V: 'static>(
+        &mut self,
+        visible_range: Range<usize>,
+        cx: &mut Context<V>,
+        rows: fn(&mut V) -> &mut Self,
+    ) {
+        for page in self.update_visible_range(visible_range) {
+            self.fetch(page, cx, rows);
+        }
+    }
+
+    /// Set the visible range and cancel the fetching of the pages out of it,
+    /// returns the pages of the visible rows that are not loaded, failed or being fetched.
+    fn update_visible_range(&mut self, visible_range: Range<usize>) -> Vec<usize> {
+        let page_size = self.page_size();
+        let mut pages = page_range(&visible_range, page_size);
+        if let Some(total) = self.total {
+            pages.end = pages.end.min(total.div_ceil(page_size));
+        }
+        self.visible_range = visible_range;
+
+        self.pages
+            .retain(|page, state| !matches!(state, Page::Loading(_)) || pages.contains(page));
+        pages
+            .filter(|page| !self.pages.contains_key(page))
+            .collect()
+    }
+
+    /// Fetch the failed page of the row at the given index again.
+    pub fn retry<V: 'static>(
+        &mut self,
+        row_ix: usize,
+        cx: &mut Context<V>,
+        rows: fn(&mut V) -> &mut Self,
+    ) {
+        if let Some(page) = self.failed_page(row_ix) {
+            self.fetch(page, cx, rows);
+            cx.notify();
+        }
+    }
+
+    /// Returns the page of the row at the given index if the page is failed.
+    fn failed_page(&self, row_ix: usize) -> Option<usize> {
+        let page = row_ix / self.page_size();
+        matches!(self.pages.get(&page), Some(Page::Failed(_))).then_some(page)
+    }
+
+    /// Clear the loaded pages and the total, cancel the fetching, then fetch the visible rows again.
+    ///
+    /// Call this when the rows of the data source are changed, e.g.: the sort or filter is changed.
+    pub fn reset<V: 'static>(&mut self, cx: &mut Context<V>, rows: fn(&mut V) -> &mut Self) {
+        self.pages.clear();
+        self.total = None;
+        self.load(self.visible_range.clone(), cx, rows);
+        cx.notify();
+    }
+
+    fn fetch<V: 'static>(
+        &mut self,
+        page: usize,
+        cx: &mut Context<V>,
+        rows: fn(&mut V) -> &mut Self,
+    ) {
+        let fetch = self.source.fetch_page(page, self.page_size(), cx);
+        let task = cx.spawn(|view, mut cx| async move {
+            let result = fetch.await;
+            _ = view.update(&mut cx, |view, cx| {
+                rows(view).finish_fetch(page, result);
+                cx.notify();
+            });
+        });
+        self.pages.insert(page, Page::Loading(task));
+    }
+
+    fn finish_fetch(&mut self, page: usize, result: anyhow::Result<Vec<S::Row>>) {
+        let page_size = self.page_size();
+        let state = match result {
+            Ok(rows) => {
+                if rows.len() < page_size {
+                    self.total = Some(page * page_size + rows.len());
+                }
+                Page::Loaded(rows)
+            }
+            Err(err) => Page::Failed(err.to_string().into()),
+        };
+        self.pages.insert(page, state);
+    }
+}
+
+/// Returns the range of the pages that contain the rows in the given range.
+fn page_range(rows: &Range<usize>, page_size: usize) -> Range<usize> {
+    if rows.is_empty() {
+        return 0..0;
+    }
+
+    rows.start / page_size..(rows.end - 1) / page_size + 1
+}
+
+/// The placeholder of a row that is not loaded, a skeleton when loading,
+/// or the error message with a retry button when failed.
+#[derive(IntoElement)]
+pub struct RowPlaceholder {
+    id: ElementId,
+    error: Option<SharedString>,
+    on_retry: Option<Rc<dyn Fn(&ClickEvent, &mut Window, &mut App)>>,
+}
+
+impl RowPlaceholder {
+    pub fn new<T>(id: impl Into<ElementId>, state: &RowState<T>) -> Self {
+        Self {
+            id: id.into(),
+            error: match state {
+                RowState::Failed(err) => Some(err.clone()),
+                _ => None,
+            },
+            on_retry: None,
+        }
+    }
+
+    /// Set the handler of the retry button, the retry button is hidden if it is not set.
+    pub fn on_retry(
+        mut self,
+        handler: impl Fn(&ClickEvent, &mut Window, &mut App) + 'static,
+    ) -> Self {
+        self.on_retry = Some(Rc::new(handler));
+        self
+    }
+}
+
+impl RenderOnce for RowPlaceholder {
+    fn render(self, _: &mut Window, cx: &mut App) -> impl IntoElement {
+        h_flex()
+            .w_full()
+            .gap_2()
+            .overflow_hidden()
+            .map(|this| match self.error {
+                None => this.child(Skeleton::new().h_4().w_full()),
+                Some(error) => this
+                    .text_color(cx.theme().danger)
+                    .child(div().flex_1().truncate().child(error))
+                    .when_some(self.on_retry, |this, on_retry| {
+                        this.child(
+                            Button::new(self.id)
+                                .xsmall()
+                                .ghost()
+                                .label(t!("DataSource.Retry"))
+                                .on_click(move |ev, window, cx| on_retry(ev, window, cx)),
+                        )
+                    }),
+            })
+    }
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+
+    struct TestSource;
+
+    impl PagedDataSource for TestSource {
+        type Row = usize;
+
+        fn fetch_page(
+            &self,
+            _: usize,
+            _: usize,
+            _: &mut App,
+        ) -> Task<anyhow::Result<Vec<Self::Row>>> {
+            Task::ready(Ok(vec![]))
+        }
+    }
+
+    /// Start fetching the pages like `PagedRows::load`, but without the fetching tasks.
+    fn load(rows: &mut PagedRows<TestSource>, visible_range: Range<usize>) -> Vec<usize> {
+        let pages = rows.update_visible_range(visible_range);
+        for page in pages.iter() {
+            rows.pages.insert(*page, Page::Loading(Task::ready(())));
+        }
+        pages
+    }
+
+    fn page_rows(page: usize, len: usize) -> anyhow::Result<Vec<usize>> {
+        Ok((page * 100..page * 100 + len).collect())
+    }
+
+    #[test]
+    fn test_page_range() {
+        assert_eq!(page_range(&(0..0), 100), 0..0);
+        assert_eq!(page_range(&(0..1), 100), 0..1);
+        assert_eq!(page_range(&(0..100), 100), 0..1);
+        assert_eq!(page_range(&(99..101), 100), 0..2);
+        assert_eq!(page_range(&(250..320), 100), 2..4);
+    }
+
+    #[test]
+    fn test_load_dedup_and_cancel() {
+        let mut rows = PagedRows::new(TestSource);
+        assert_eq!(load(&mut rows, 0..150), vec![0, 1]);
+        assert!(rows.is_loading());
+        assert_eq!(rows.row(120), RowState::Loading);
+
+        // The pages being fetched are not fetched again.
+        assert_eq!(load(&mut rows, 50..180), vec![]);
+
+        rows.finish_fetch(0, page_rows(0, 100));
+        assert_eq!(rows.row(5), RowState::Loaded(&5));
+
+        // The fetching of page 1 is cancelled when it is scrolled out, the loaded page 0 is kept.
+        assert_eq!(load(&mut rows, 250..320), vec![2, 3]);
+        assert!(!rows.pages.contains_key(&1));
+        assert_eq!(rows.row(5), RowState::Loaded(&5));
+
+        // The loaded page is not fetched again, the cancelled page is fetched again.
+        assert_eq!(load(&mut rows, 0..150), vec![1]);
+        assert!(!rows.pages.contains_key(&2));
+        assert!(!rows.pages.contains_key(&3));
+    }
+
+    #[test]
+    fn test_rows_count() {
+        let mut rows = PagedRows::new(TestSource);
+        // A page of placeholder rows to fetch more.
+        assert_eq!(rows.rows_count(), 100);
+
+        load(&mut rows, 0..200);
+        rows.finish_fetch(0, page_rows(0, 100));
+        assert_eq!(rows.rows_count(), 200);
+        assert_eq!(rows.total, None);
+
+        // A page with fewer rows than the page size is the last page.
+        rows.finish_fetch(1, page_rows(1, 30));
+        assert_eq!(rows.total, Some(130));
+        assert_eq!(rows.rows_count(), 130);
+        assert_eq!(rows.row(129), RowState::Loaded(&129));
+
+        // The pages after the total are not fetched.
+        assert_eq!(load(&mut rows, 100..500), vec![]);
+
+        rows.set_total(Some(1000));
+        assert_eq!(rows.rows_count(), 1000);
+        assert_eq!(load(&mut rows, 100..500), vec![2, 3, 4]);
+    }
+
+    #[test]
+    fn test_failed_and_retry() {
+        let mut rows = PagedRows::new(TestSource);
+        load(&mut rows, 0..50);
+        rows.finish_fetch(0, Err(anyhow::anyhow!("Connection lost")));
+        assert!(!rows.is_loading());
+        assert_eq!(rows.row(5), RowState::Failed("Connection lost".into()));
+
+        // The failed page is only fetched again by retry.
+        assert_eq!(load(&mut rows, 0..50), vec![]);
+        assert_eq!(rows.failed_page(120), None);
+        assert_eq!(rows.failed_page(5), Some(0));
+
+        rows.pages.insert(0, Page::Loading(Task::ready(())));
+        assert_eq!(rows.row(5), RowState::Loading);
+        assert_eq!(rows.failed_page(5), None);
+
+        rows.finish_fetch(0, page_rows(0, 100));
+        assert_eq!(rows.row(5), RowState::Loaded(&5));
+    }
+}
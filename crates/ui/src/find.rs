@@ -0,0 +1,112 @@
+/// The matches of a find bar, with the current match to navigate by next/previous.
+///
+/// This is used by the find bar of the `Table` (the matched cells) and the `TextInput` (the matched ranges).
+#[derive(Debug, Clone)]
+pub(crate) struct FindState<T> {
+    /// The matches, in the order of the document.
+    matches: Vec<T>,
+    /// The index of the current match in the `matches`.
+    current: Option<usize>,
+}
+
+impl<T> Default for FindState<T> {
+    fn default() -> Self {
+        Self {
+            matches: vec![],
+            current: None,
+        }
+    }
+}
+
+impl<T: Clone> FindState<T> {
+    pub(crate) fn clear(&mut self) {
+        *self = Self::default();
+    }
+
+    /// Set the matches of a new search.
+    ///
+    /// The current match is the first one that `is_before` returns false for, e.g. the first match
+    /// at or after the cursor, or the first match if all the matches are before.
+    pub(crate) fn set_matches(
+        &mut self,
+        matches: Vec<T>,
+        is_before: impl FnMut(&T) -> bool,
+    ) -> Option<T> {
+        let ix = matches.partition_point(is_before);
+        self.current = if matches.is_empty() {
+            None
+        } else if ix < matches.len() {
+            Some(ix)
+        } else {
+            Some(0)
+        };
+        self.matches = matches;
+        self.current()
+    }
+
+    pub(crate) fn matches(&self) -> &[T] {
+        &self.matches
+    }
+
+    pub(crate) fn current(&self) -> Option<T> {
+        self.current.map(|ix| self.matches[ix].clone())
+    }
+
+    /// Returns the (1-based index of the current match, the number of matches).
+    pub(crate) fn position(&self) -> (usize, usize) {
+        (self.current.map_or(0, |ix| ix + 1), self.matches.len())
+    }
+
+    /// Move to the next match, or the previous one if `reverse` is true, wraps around at the end.
+    pub(crate) fn next(&mut self, reverse: bool) -> Option<T> {
+        let len = self.matches.len();
+        if len == 0 {
+            return None;
+        }
+
+        self.current = Some(match self.current {
+            None => 0,
+            Some(ix) if reverse => (ix + len - 1) % len,
+            Some(ix) => (ix + 1) % len,
+        });
+        self.current()
+    }
+}
+
+impl<T: Ord> FindState<T> {
+    /// Returns true if the given item is one of the matches, the matches must be sorted.
+    pub(crate) fn contains(&self, item: &T) -> bool {
+        self.matches.binary_search(item).is_ok()
+    }
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+
+    #[test]
+    fn test_find_state() {
+        let mut state = FindState::default();
+        assert_eq!(state.set_matches(vec![2, 8, 15], |&ix| ix < 5), Some(8));
+        assert_eq!(state.position(), (2, 3));
+        assert_eq!(state.next(false), Some(15));
+        assert_eq!(state.next(false), Some(2));
+        assert_eq!(state.next(true), Some(15));
+        assert_eq!(state.matches(), &[2, 8, 15]);
+        assert!(state.contains(&8));
+        assert!(!state.contains(&5));
+
+        // All the matches are before, starts from the first match.
+        assert_eq!(state.set_matches(vec![2, 8], |&ix| ix < 20), Some(2));
+        assert_eq!(state.position(), (1, 2));
+
+        assert_eq!(state.set_matches(vec![], |_| true), None);
+        assert_eq!(state.position(), (0, 0));
+        assert_eq!(state.next(false), None);
+
+        state.set_matches(vec![2, 8], |_| false);
+        state.clear();
+        assert_eq!(state.current(), None);
+        assert!(state.matches().is_empty());
+    }
+}
@@ -1,12 +1,17 @@
-use gpui::{HighlightStyle, Hsla, SharedString};
+use gpui::{App, Global, HighlightStyle, Hsla, SharedString};
+use serde::{Deserialize, Serialize};
 use std::{
     ops::Range,
+    rc::Rc,
+    str::FromStr as _,
     sync::{Arc, LazyLock},
 };
 use syntect::{highlighting, parsing};
 
+use crate::{ActiveTheme as _, Colorize as _, Theme};
+
 static SYNTAXES: LazyLock<parsing::SyntaxSet> =
-    LazyLock::new(parsing::SyntaxSet::load_defaults_nonewlines);
+    LazyLock::new(parsing::SyntaxSet::load_defaults_newlines);
 
 static DEFAULT_LIGHT: LazyLock<Arc<highlighting::Theme>> = LazyLock::new(|| {
     let mut cursor = std::io::Cursor::new(include_bytes!("./themes/light.tmTheme"));
@@ -18,6 +23,41 @@ static DEFAULT_DARK: LazyLock<Arc<highlighting::Theme>> = LazyLock::new(|| {
     Arc::new(highlighting::ThemeSet::load_from_reader(&mut cursor).unwrap())
 });
 
+/// The scope selectors of the token kinds, in the order of keyword, string, comment, number, function and type.
+const TOKEN_SCOPES: [&str; 6] = [
+    "keyword, storage, constant.language, variable.language",
+    "string, constant.character",
+    "comment, punctuation.definition.comment",
+    "constant.numeric",
+    "entity.name.function, support.function, variable.function",
+    "entity.name.type, entity.name.class, entity.name.struct, entity.name.enum, support.type, support.class",
+];
+
+/// A simple token color map to build a [`HighlightTheme`], the colors are in hex format.
+///
+/// The tokens without a color use the default text color, e.g.:
+///
+/// ```json
+/// {
+///   "foreground": "#24292E",
+///   "keyword": "#D73A49",
+///   "string": "#032F62",
+///   "comment": "#6A737D"
+/// }
+/// ```
+#[derive(Debug, Clone, Default, PartialEq, Eq, Deserialize, Serialize)]
+#[serde(default)]
+pub struct TokenColors {
+    pub background: Option<String>,
+    pub foreground: Option<String>,
+    pub keyword: Option<String>,
+    pub string: Option<String>,
+    pub comment: Option<String>,
+    pub number: Option<String>,
+    pub function: Option<String>,
+    pub r#type: Option<String>,
+}
+
 /// Represents a theme for syntax highlighting.
 #[derive(Debug, Clone, PartialEq)]
 pub struct HighlightTheme {
@@ -52,56 +92,359 @@ impl HighlightTheme {
             inner: Arc::new(theme),
         })
     }
+
+    /// Create a theme by the syntax colors of the [`Theme`].
+    ///
+    /// So the highlighting follows the custom theme colors, and the `foreground` and `background`
+    /// follow [`Theme::apply_color`].
+    pub fn from_theme(theme: &Theme) -> Self {
+        let name = if theme.is_dark() {
+            "theme-dark"
+        } else {
+            "theme-light"
+        };
+
+        Self::from_colors(
+            name,
+            Some(theme.background),
+            Some(theme.foreground),
+            [
+                Some(theme.syntax.keyword),
+                Some(theme.syntax.string),
+                Some(theme.syntax.comment),
+                Some(theme.syntax.number),
+                Some(theme.syntax.function),
+                Some(theme.syntax.r#type),
+            ],
+        )
+    }
+
+    /// Returns the [`HighlightTheme::from_theme`] of the current theme.
+    ///
+    /// The highlight theme is cached, and only created again when the colors or the appearance of the theme are changed.
+    pub fn global(cx: &mut App) -> Rc<Self> {
+        let colors = ThemeSyntaxColors::new(cx.theme());
+        if let Some((cached_colors, theme)) = &cx.default_global::<GlobalHighlightTheme>().0 {
+            if *cached_colors == colors {
+                return theme.clone();
+            }
+        }
+
+        let theme = Rc::new(Self::from_theme(cx.theme()));
+        cx.global_mut::<GlobalHighlightTheme>().0 = Some((colors, theme.clone()));
+        theme
+    }
+
+    /// Create a theme from a [`TokenColors`].
+    pub fn from_token_colors(name: &str, colors: &TokenColors) -> anyhow::Result<Self> {
+        let parse = |color: &Option<String>| color.as_deref().map(Hsla::parse_hex).transpose();
+
+        Ok(Self::from_colors(
+            name,
+            parse(&colors.background)?,
+            parse(&colors.foreground)?,
+            [
+                parse(&colors.keyword)?,
+                parse(&colors.string)?,
+                parse(&colors.comment)?,
+                parse(&colors.number)?,
+                parse(&colors.function)?,
+                parse(&colors.r#type)?,
+            ],
+        ))
+    }
+
+    /// Parse a theme from a JSON string of [`TokenColors`].
+    pub fn parse_json(name: &str, json: &str) -> anyhow::Result<Self> {
+        Self::from_token_colors(name, &serde_json::from_str(json)?)
+    }
+
+    /// Parse a theme from a TOML string of [`TokenColors`].
+    pub fn parse_toml(name: &str, toml: &str) -> anyhow::Result<Self> {
+        Self::from_token_colors(name, &toml::from_str(toml)?)
+    }
+
+    fn from_colors(
+        name: &str,
+        background: Option<Hsla>,
+        foreground: Option<Hsla>,
+        tokens: [Option<Hsla>; 6],
+    ) -> Self {
+        let scopes = TOKEN_SCOPES
+            .iter()
+            .zip(tokens)
+            .filter_map(|(scope, color)| {
+                Some(highlighting::ThemeItem {
+                    scope: highlighting::ScopeSelectors::from_str(scope).ok()?,
+                    style: highlighting::StyleModifier {
+                        foreground: Some(hsla_to_color(color?)),
+                        background: None,
+                        font_style: None,
+                    },
+                })
+            })
+            .collect();
+
+        Self {
+            name: SharedString::from(name.to_string()),
+            inner: Arc::new(highlighting::Theme {
+                name: Some(name.to_string()),
+                settings: highlighting::ThemeSettings {
+                    background: background.map(hsla_to_color),
+                    foreground: foreground.map(hsla_to_color),
+                    ..Default::default()
+                },
+                scopes,
+                ..Default::default()
+            }),
+        }
+    }
+}
+
+/// The colors of the [`Theme`] used by [`HighlightTheme::from_theme`].
+#[derive(Debug, Clone, Copy, PartialEq)]
+struct ThemeSyntaxColors {
+    is_dark: bool,
+    background: Hsla,
+    foreground: Hsla,
+    tokens: [Hsla; 6],
+}
+
+impl ThemeSyntaxColors {
+    fn new(theme: &Theme) -> Self {
+        Self {
+            is_dark: theme.is_dark(),
+            background: theme.background,
+            foreground: theme.foreground,
+            tokens: [
+                theme.syntax.keyword,
+                theme.syntax.string,
+                theme.syntax.comment,
+                theme.syntax.number,
+                theme.syntax.function,
+                theme.syntax.r#type,
+            ],
+        }
+    }
+}
+
+/// The cache of [`HighlightTheme::global`], with the theme colors it is created by.
+#[derive(Default)]
+struct GlobalHighlightTheme(Option<(ThemeSyntaxColors, Rc<HighlightTheme>)>);
+
+impl Global for GlobalHighlightTheme {}
+
+/// A parsed line of the [`Highlighter`], with the parse states at the end of the line.
+struct ParsedLine {
+    ops: Vec<(usize, parsing::ScopeStackOp)>,
+    /// The length of the line, without the `\n`.
+    len: usize,
+    parse_state: parsing::ParseState,
+    scope_stack: parsing::ScopeStack,
 }
 
 /// Inspired by the `iced` crate's `Highlighter` struct.
 ///
 /// https://github.com/iced-rs/iced/blob/master/highlighter/src/lib.rs#L24
-pub struct Highlighter<'a> {
+///
+/// The text is parsed line by line, so the multi-line syntax (e.g.: block comments) is highlighted correctly.
+/// For an editor, use [`Highlighter::highlight_lines`] with [`Highlighter::edit`], the parse states of
+/// each line are cached, and the lines are only parsed again from the first edited line.
+pub struct Highlighter {
     syntax: &'static parsing::SyntaxReference,
-    highlighter: highlighting::Highlighter<'a>,
+    theme: HighlightTheme,
+    /// The parsed lines, `None` is a line inserted by an edit and not parsed yet.
+    lines: Vec<Option<ParsedLine>>,
+    /// The number of the lines (from the first line) that are parsed and up to date.
+    valid: usize,
 }
 
-impl<'a> Highlighter<'a> {
-    pub fn new(lang: Option<&str>, theme: &'a HighlightTheme) -> Self {
+impl Highlighter {
+    pub fn new(lang: Option<&str>, theme: &HighlightTheme) -> Self {
         let syntax = lang
             .and_then(|lang| SYNTAXES.find_syntax_by_token(&lang))
             .unwrap_or_else(|| SYNTAXES.find_syntax_plain_text());
-        let highlighter = highlighting::Highlighter::new(&theme.inner);
 
         Self {
             syntax,
-            highlighter,
+            theme: theme.clone(),
+            lines: vec![],
+            valid: 0,
         }
     }
 
+    /// Set the theme, the parsed lines are kept.
+    pub fn set_theme(&mut self, theme: &HighlightTheme) {
+        self.theme = theme.clone();
+    }
+
     /// Highlight a text and returns a vector of ranges and highlight styles
+    ///
+    /// The whole text is parsed, the parsed lines are not cached.
     pub fn highlight(&self, text: &str) -> Vec<(Range<usize>, HighlightStyle)> {
-        let mut parser = parsing::ParseState::new(self.syntax);
-        let mut stack = parsing::ScopeStack::new();
+        let highlighter = highlighting::Highlighter::new(&self.theme.inner);
+        let mut parse_state = parsing::ParseState::new(self.syntax);
+        let mut scope_stack = parsing::ScopeStack::new();
 
-        let ops = parser.parse_line(text, &SYNTAXES).unwrap_or_default();
+        let mut styles = vec![];
+        let mut offset = 0;
+        for line in text.split_inclusive('\n') {
+            let ops = parse_state.parse_line(line, &SYNTAXES).unwrap_or_default();
+            styles.extend(
+                line_styles(&highlighter, &mut scope_stack, &ops, line.len())
+                    .into_iter()
+                    .map(|(range, style)| (offset + range.start..offset + range.end, style)),
+            );
+            offset += line.len();
+        }
+        styles
+    }
 
-        ScopeRangeIterator {
-            ops,
-            line_length: text.len(),
-            index: 0,
-            last_str_index: 0,
+    /// Update the parsed lines after an edit of the text.
+    ///
+    /// The edit starts at the line `row`, and the `removed` lines after it are replaced
+    /// with the `inserted` lines, e.g.: typing a char is `(row, 0, 0)`, and pasting 2 lines is `(row, 0, 2)`.
+    pub fn edit(&mut self, row: usize, removed: usize, inserted: usize) {
+        // Only the lines of the last edit are kept to check, the lines after an earlier edit
+        // that is not parsed yet may be outdated.
+        if self.valid < self.lines.len() {
+            self.lines.truncate(self.valid);
         }
-        .filter_map(move |(range, scope)| {
-            let _ = stack.apply(&scope);
-            if range.is_empty() {
-                return None;
-            } else {
-                let style_mod = self.highlighter.style_mod_for_stack(&stack.scopes);
-                let mut style = HighlightStyle::default();
-                style.color = style_mod.foreground.map(color_to_hsla);
-                style.background_color = style_mod.background.map(color_to_hsla);
-                Some((range, style))
-            }
+
+        self.valid = self.valid.min(row);
+        if row + removed >= self.lines.len() {
+            self.lines.truncate(row);
+            return;
+        }
+
+        // Keep the parsed last line of the edit, its parse states are used to check whether the lines after
+        // it need to be parsed again.
+        self.lines.splice(
+            row..row + removed,
+            std::iter::repeat_with(|| None).take(inserted),
+        );
+    }
+
+    /// Returns the highlights of the lines in the `rows`, the ranges are relative to the start of each line.
+    ///
+    /// The `line` returns the text of a line without the `\n`, the lines are parsed if they are not parsed
+    /// or edited, and the parsing is stopped at the first line that has the same parse states as before.
+    pub fn highlight_lines(
+        &mut self,
+        rows: Range<usize>,
+        line: impl Fn(usize) -> String,
+    ) -> Vec<Vec<(Range<usize>, HighlightStyle)>> {
+        if rows.is_empty() {
+            return vec![];
+        }
+
+        self.parse_to(rows.end - 1, &line);
+        let highlighter = highlighting::Highlighter::new(&self.theme.inner);
+        rows.map(|row| {
+            let mut scope_stack = match row.checked_sub(1) {
+                Some(prev) => self.parsed_line(prev).scope_stack.clone(),
+                None => parsing::ScopeStack::new(),
+            };
+            let parsed = self.parsed_line(row);
+            line_styles(&highlighter, &mut scope_stack, &parsed.ops, parsed.len)
         })
         .collect()
     }
+
+    /// Returns the parsed line, the line must be in the `valid` lines.
+    fn parsed_line(&self, row: usize) -> &ParsedLine {
+        self.lines[row]
+            .as_ref()
+            .expect("the valid line must be parsed")
+    }
+
+    /// Parse the lines until the line `row` is up to date.
+    fn parse_to(&mut self, row: usize, line: &impl Fn(usize) -> String) {
+        let mut outdated = false;
+        while self.valid <= row {
+            let ix = self.valid;
+            let (mut parse_state, mut scope_stack) = match ix.checked_sub(1) {
+                Some(prev) => {
+                    let prev = self.parsed_line(prev);
+                    (prev.parse_state.clone(), prev.scope_stack.clone())
+                }
+                None => (
+                    parsing::ParseState::new(self.syntax),
+                    parsing::ScopeStack::new(),
+                ),
+            };
+
+            let mut text = line(ix);
+            let len = text.len();
+            text.push('\n');
+            let ops = parse_state.parse_line(&text, &SYNTAXES).unwrap_or_default();
+            for (_, op) in &ops {
+                let _ = scope_stack.apply(op);
+            }
+
+            let converged = matches!(
+                self.lines.get(ix),
+                Some(Some(old)) if old.parse_state == parse_state && old.scope_stack == scope_stack
+            );
+            let parsed = Some(ParsedLine {
+                ops,
+                len,
+                parse_state,
+                scope_stack,
+            });
+            if ix < self.lines.len() {
+                self.lines[ix] = parsed;
+            } else {
+                self.lines.push(parsed);
+            }
+
+            self.valid = ix + 1;
+            outdated = !converged;
+            if converged {
+                // The parse states are not changed, so the lines after it are still up to date,
+                // until the next edited line.
+                self.valid = self.lines[ix + 1..]
+                    .iter()
+                    .position(Option::is_none)
+                    .map_or(self.lines.len(), |n| ix + 1 + n);
+            }
+        }
+
+        // The lines after are parsed with the old parse states, they can't be used to check anymore.
+        if outdated {
+            self.lines.truncate(self.valid);
+        }
+    }
+}
+
+/// Returns the styles of a line by the scope stack at the start of the line, the scope stack is
+/// updated to the end of the line.
+fn line_styles(
+    highlighter: &highlighting::Highlighter,
+    scope_stack: &mut parsing::ScopeStack,
+    ops: &[(usize, parsing::ScopeStackOp)],
+    len: usize,
+) -> Vec<(Range<usize>, HighlightStyle)> {
+    ScopeRangeIterator {
+        ops,
+        line_length: len,
+        index: 0,
+        last_str_index: 0,
+    }
+    .filter_map(|(range, op)| {
+        let _ = scope_stack.apply(op);
+        let range = range.start.min(len)..range.end.min(len);
+        if range.is_empty() {
+            return None;
+        }
+
+        let style_mod = highlighter.style_mod_for_stack(&scope_stack.scopes);
+        let mut style = HighlightStyle::default();
+        style.color = style_mod.foreground.map(color_to_hsla);
+        style.background_color = style_mod.background.map(color_to_hsla);
+        Some((range, style))
+    })
+    .collect()
 }
 
 fn color_to_hsla(color: highlighting::Color) -> Hsla {
@@ -109,20 +452,31 @@ fn color_to_hsla(color: highlighting::Color) -> Hsla {
         r: color.r as f32 / 255.,
         g: color.g as f32 / 255.,
         b: color.b as f32 / 255.,
-        a: color.a as f32 / 100.,
+        a: color.a as f32 / 255.,
     }
     .into()
 }
 
-struct ScopeRangeIterator {
-    ops: Vec<(usize, parsing::ScopeStackOp)>,
+fn hsla_to_color(color: Hsla) -> highlighting::Color {
+    let rgba = gpui::Rgba::from(color);
+    let to_u8 = |v: f32| (v.clamp(0., 1.) * 255.).round() as u8;
+    highlighting::Color {
+        r: to_u8(rgba.r),
+        g: to_u8(rgba.g),
+        b: to_u8(rgba.b),
+        a: to_u8(rgba.a),
+    }
+}
+
+struct ScopeRangeIterator<'a> {
+    ops: &'a [(usize, parsing::ScopeStackOp)],
     line_length: usize,
     index: usize,
     last_str_index: usize,
 }
 
-impl Iterator for ScopeRangeIterator {
-    type Item = (std::ops::Range<usize>, parsing::ScopeStackOp);
+impl<'a> Iterator for ScopeRangeIterator<'a> {
+    type Item = (std::ops::Range<usize>, &'a parsing::ScopeStackOp);
 
     fn next(&mut self) -> Option<Self::Item> {
         if self.index > self.ops.len() {
@@ -139,12 +493,184 @@ impl Iterator for ScopeRangeIterator {
         self.last_str_index = next_str_i;
 
         let op = if self.index == 0 {
-            parsing::ScopeStackOp::Noop
+            &parsing::ScopeStackOp::Noop
         } else {
-            self.ops[self.index - 1].1.clone()
+            &self.ops[self.index - 1].1
         };
 
         self.index += 1;
         Some((range, op))
     }
 }
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+    use std::cell::Cell;
+
+    fn style_at(
+        styles: &[(Range<usize>, HighlightStyle)],
+        offset: usize,
+    ) -> Option<HighlightStyle> {
+        styles
+            .iter()
+            .find(|(range, _)| range.contains(&offset))
+            .map(|(_, style)| *style)
+    }
+
+    #[test]
+    fn test_highlight_multi_line() {
+        let highlighter = Highlighter::new(Some("rust"), &HighlightTheme::default_light());
+        let text = "let a = 1;\n/* comment\nstill comment */\nlet b = 2;";
+        let styles = highlighter.highlight(text);
+
+        let comment = style_at(&styles, text.find("comment").unwrap());
+        assert!(comment.is_some());
+        assert_eq!(style_at(&styles, text.find("still").unwrap()), comment);
+        assert_ne!(style_at(&styles, text.rfind("let").unwrap()), comment);
+        assert_eq!(
+            style_at(&styles, text.rfind("let").unwrap()),
+            style_at(&styles, 0)
+        );
+    }
+
+    #[test]
+    fn test_theme_from_token_colors() {
+        let theme = HighlightTheme::parse_json(
+            "custom",
+            r##"{ "foreground": "#24292E", "keyword": "#D73A49", "comment": "#6A737D" }"##,
+        )
+        .unwrap();
+        let highlighter = Highlighter::new(Some("rust"), &theme);
+        let text = "// hello\nlet a = \"foo\";";
+        let styles = highlighter.highlight(text);
+
+        let color_at = |offset| style_at(&styles, offset).and_then(|style| style.color);
+        assert_eq!(color_at(3), Some(Hsla::parse_hex("#6A737D").unwrap()));
+        assert_eq!(
+            color_at(text.find("let").unwrap()),
+            Some(Hsla::parse_hex("#D73A49").unwrap())
+        );
+        // No string color, use the default text color.
+        assert_eq!(color_at(text.find("foo").unwrap()), None);
+
+        let toml_theme = HighlightTheme::parse_toml(
+            "custom",
+            "foreground = \"#24292E\"\nkeyword = \"#D73A49\"\ncomment = \"#6A737D\"",
+        )
+        .unwrap();
+        assert_eq!(toml_theme, theme);
+        assert!(HighlightTheme::parse_json("invalid", r#"{ "keyword": "red" }"#).is_err());
+    }
+
+    #[test]
+    fn test_theme_from_theme() {
+        let theme = Theme::from(crate::ThemeColor::light());
+        let highlighter = Highlighter::new(Some("rust"), &HighlightTheme::from_theme(&theme));
+        let text = "// hello\nfn main() { let a = \"foo\"; }";
+        let styles = highlighter.highlight(text);
+
+        let assert_color = |offset: usize, expected: Hsla| {
+            let color = style_at(&styles, offset)
+                .and_then(|style| style.color)
+                .unwrap()
+                .to_rgb();
+            let expected = expected.to_rgb();
+            for (a, b) in [
+                (color.r, expected.r),
+                (color.g, expected.g),
+                (color.b, expected.b),
+            ] {
+                assert!((a - b).abs() < 1. / 255.);
+            }
+        };
+        assert_color(3, theme.syntax.comment);
+        assert_color(text.find("main").unwrap(), theme.syntax.function);
+        assert_color(text.find("foo").unwrap(), theme.syntax.string);
+    }
+
+    #[test]
+    fn test_color_to_hsla() {
+        let color = |a| highlighting::Color {
+            r: 0xD7,
+            g: 0x3A,
+            b: 0x49,
+            a,
+        };
+
+        // The alpha is in `0..=255`, like the other channels.
+        assert_eq!(color_to_hsla(color(0xFF)).a, 1.);
+        assert_eq!(color_to_hsla(color(0x00)).a, 0.);
+        assert!((color_to_hsla(color(0x80)).a - 0.5).abs() < 1. / 255.);
+        assert_eq!(
+            color_to_hsla(color(0xFF)),
+            Hsla::parse_hex("#D73A49").unwrap()
+        );
+        assert_eq!(hsla_to_color(color_to_hsla(color(0x80))), color(0x80));
+    }
+
+    #[test]
+    fn test_highlight_lines() {
+        let mut lines = vec![
+            "fn main() {".to_string(),
+            "    let a = 1;".to_string(),
+            "    let b = \"foo\";".to_string(),
+            "    let c = 3;".to_string(),
+            "}".to_string(),
+        ];
+        let parsed = Cell::new(0);
+        let mut highlighter = Highlighter::new(Some("rust"), &HighlightTheme::default_light());
+
+        let highlight_lines = |highlighter: &mut Highlighter, lines: &[String]| {
+            parsed.set(0);
+            highlighter.highlight_lines(0..lines.len(), |row| {
+                parsed.set(parsed.get() + 1);
+                lines[row].clone()
+            })
+        };
+
+        // Same as the whole text is highlighted.
+        let initial = highlight_lines(&mut highlighter, &lines);
+        assert_eq!(parsed.get(), 5);
+        let mut offset = 0;
+        let whole = highlighter.highlight(&lines.join("\n"));
+        for (line, line_styles) in lines.iter().zip(&initial) {
+            for (range, style) in line_styles {
+                assert_eq!(style_at(&whole, offset + range.start), Some(*style));
+            }
+            offset += line.len() + 1;
+        }
+
+        // Nothing is parsed again without edits.
+        highlight_lines(&mut highlighter, &lines);
+        assert_eq!(parsed.get(), 0);
+
+        // The edit without changing the parse states only parses the edited line.
+        lines[1] = "    let aa = 1;".to_string();
+        highlighter.edit(1, 0, 0);
+        highlight_lines(&mut highlighter, &lines);
+        assert_eq!(parsed.get(), 1);
+
+        // Open a block comment, the lines after it are parsed again.
+        lines[1] = "    /* let aa = 1;".to_string();
+        highlighter.edit(1, 0, 0);
+        let styles = highlight_lines(&mut highlighter, &lines);
+        assert_eq!(parsed.get(), 4);
+        assert_eq!(style_at(&styles[3], 4), style_at(&styles[1], 7));
+
+        // Insert a line closes the comment.
+        lines.insert(2, "    */".to_string());
+        highlighter.edit(1, 0, 1);
+        let styles = highlight_lines(&mut highlighter, &lines);
+        assert_eq!(parsed.get(), 5);
+        assert_eq!(styles[4], initial[3]);
+
+        // Remove the comment lines, the lines after are converged.
+        lines.drain(1..3);
+        lines.insert(1, "    let a = 1;".to_string());
+        highlighter.edit(1, 1, 0);
+        let styles = highlight_lines(&mut highlighter, &lines);
+        assert_eq!(parsed.get(), 1);
+        assert_eq!(styles, initial);
+    }
+}
@@ -22,6 +22,8 @@ pub struct History<I: HistoryItem> {
     pub(crate) ignore: bool,
     max_undo: usize,
     group_interval: Option<Duration>,
+    /// The depth of the nested groups, see [`History::start_grouping`].
+    grouping: usize,
     unique: bool,
 }
 
@@ -38,6 +40,7 @@ where
             version: 0,
             max_undo: 1000,
             group_interval: None,
+            grouping: 0,
             unique: false,
         }
     }
@@ -64,7 +67,7 @@ where
     /// Increment the version number if the last change was made more than `GROUP_INTERVAL` milliseconds ago.
     fn inc_version(&mut self) -> usize {
         let t = Instant::now();
-        if Some(self.last_changed_at.elapsed()) > self.group_interval {
+        if self.grouping == 0 && Some(self.last_changed_at.elapsed()) > self.group_interval {
             self.version += 1;
         }
 
@@ -72,6 +75,26 @@ where
         self.version
     }
 
+    /// Start a group of changes, the changes pushed before [`History::end_grouping`]
+    /// are undone and redone as one step, regardless of the `group_interval`.
+    ///
+    /// The groups can be nested, the changes of the inner groups are in the outermost group.
+    pub fn start_grouping(&mut self) {
+        if self.grouping == 0 {
+            self.version += 1;
+        }
+        self.grouping += 1;
+    }
+
+    /// End the group of changes started by [`History::start_grouping`].
+    pub fn end_grouping(&mut self) {
+        self.grouping = self.grouping.saturating_sub(1);
+        if self.grouping == 0 {
+            // The next change is not in the group, even if it is in the `group_interval`.
+            self.version += 1;
+        }
+    }
+
     /// Get the current version number.
     pub fn version(&self) -> usize {
         self.version
@@ -230,6 +253,43 @@ mod tests {
         assert_eq!(history.undo().is_none(), true);
     }
 
+    #[test]
+    fn test_grouping_history() {
+        let mut history: History<TabIndex> = History::new();
+        history.push(0.into());
+        history.start_grouping();
+        history.push(1.into());
+        history.push(2.into());
+        history.end_grouping();
+        history.push(3.into());
+
+        assert_eq!(history.undo().unwrap().len(), 1);
+        let changes = history.undo().unwrap();
+        assert_eq!(changes.len(), 2);
+        assert_eq!(changes[0].tab_index, 2);
+        assert_eq!(changes[1].tab_index, 1);
+        assert_eq!(history.redo().unwrap().len(), 2);
+    }
+
+    #[test]
+    fn test_nested_grouping_history() {
+        let mut history: History<TabIndex> =
+            History::new().group_interval(Duration::from_secs(60));
+        history.start_grouping();
+        history.push(0.into());
+        history.start_grouping();
+        history.push(1.into());
+        history.end_grouping();
+        history.push(2.into());
+        history.end_grouping();
+        history.push(3.into());
+        history.push(4.into());
+
+        assert_eq!(history.undo().unwrap().len(), 2);
+        assert_eq!(history.undo().unwrap().len(), 3);
+        assert!(history.undo().is_none());
+    }
+
     #[test]
     fn test_unique_history() {
         let mut history: History<TabIndex> = History::new().max_undo(100).unique();
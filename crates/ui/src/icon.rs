@@ -35,6 +35,7 @@ pub enum IconName {
     EllipsisVertical,
     Eye,
     EyeOff,
+    Filter,
     Frame,
     GalleryVerticalEnd,
     GitHub,
@@ -112,6 +113,7 @@ impl IconName {
             Self::EllipsisVertical => "icons/ellipsis-vertical.svg",
             Self::Eye => "icons/eye.svg",
             Self::EyeOff => "icons/eye-off.svg",
+            Self::Filter => "icons/filter.svg",
             Self::Frame => "icons/frame.svg",
             Self::GalleryVerticalEnd => "icons/gallery-vertical-end.svg",
             Self::GitHub => "icons/github.svg",
@@ -0,0 +1,451 @@
+use std::ops::Range;
+
+use gpui::{
+    div, prelude::FluentBuilder as _, App, Context, ParentElement as _, SharedString, Styled as _,
+    Task, WeakEntity, Window,
+};
+
+use crate::{
+    h_flex,
+    list::{List, ListDelegate, ListItem},
+    ActiveTheme as _, Size, StyleSized as _,
+};
+
+use super::{change::Change, rope::Rope, TextInput};
+
+/// An item of the completion menu of the `TextInput`.
+#[derive(Debug, Clone, PartialEq)]
+pub struct CompletionItem {
+    /// The text to replace the word before the cursor.
+    pub text: SharedString,
+    /// The label to show in the menu, defaults to the `text`.
+    pub label: SharedString,
+    /// The description to show on the right side of the label.
+    pub detail: Option<SharedString>,
+}
+
+impl CompletionItem {
+    pub fn new(text: impl Into<SharedString>) -> Self {
+        let text = text.into();
+        Self {
+            label: text.clone(),
+            text,
+            detail: None,
+        }
+    }
+
+    /// Set the label to show in the menu.
+    pub fn label(mut self, label: impl Into<SharedString>) -> Self {
+        self.label = label.into();
+        self
+    }
+
+    /// Set the description to show on the right side of the label.
+    pub fn detail(mut self, detail: impl Into<SharedString>) -> Self {
+        self.detail = Some(detail.into());
+        self
+    }
+}
+
+/// A provider of the completion items for the `TextInput`, see [`TextInput::completion_provider`].
+pub trait CompletionProvider {
+    /// Returns the completion items of the word `query` before the cursor at the `offset` (in UTF-8) of the `input`.
+    ///
+    /// The `query` is empty if the menu is opened by `ctrl-space` or a trigger char after a non-word char.
+    ///
+    /// The task is dropped to cancel the query when the word is changed before it is done,
+    /// the blocking work (e.g.: the database query) should be done in a background task.
+    fn completions(
+        &self,
+        input: &TextInput,
+        query: &str,
+        offset: usize,
+        window: &mut Window,
+        cx: &mut App,
+    ) -> Task<anyhow::Result<Vec<CompletionItem>>>;
+
+    /// Returns true if the char is a part of the word to complete.
+    ///
+    /// Default: alphanumeric and `_`.
+    fn is_word_char(&self, c: char) -> bool {
+        c.is_alphanumeric() || c == '_'
+    }
+
+    /// Returns true if typing the char opens the completion menu, in addition to the word chars, e.g.: `.`.
+    ///
+    /// Default: false
+    fn is_trigger_char(&self, _c: char) -> bool {
+        false
+    }
+}
+
+/// Returns the range of the word before the `offset` in the `text`, the word is in the line of the `offset`.
+pub(super) fn word_range(
+    text: &Rope,
+    offset: usize,
+    is_word_char: impl Fn(char) -> bool,
+) -> Range<usize> {
+    let line_start = text.line_start(text.offset_to_line(offset));
+    let line = text.slice(line_start..offset);
+    let start = line
+        .char_indices()
+        .rev()
+        .take_while(|(_, c)| is_word_char(*c))
+        .last()
+        .map_or(line.len(), |(ix, _)| ix);
+    line_start + start..offset
+}
+
+/// Returns true if typing the `new_text` queries the completions, otherwise the menu is closed.
+///
+/// Typing a word char or trigger char queries the completions, and deleting (the `new_text` is empty)
+/// queries again if the menu is visible and the word before the cursor is not empty.
+pub(super) fn should_query(
+    new_text: &str,
+    menu_visible: bool,
+    word_is_empty: bool,
+    is_word_char: impl Fn(char) -> bool,
+    is_trigger_char: impl Fn(char) -> bool,
+) -> bool {
+    match new_text.chars().last() {
+        Some(c) => is_word_char(c) || is_trigger_char(c),
+        None => menu_visible && !word_is_empty,
+    }
+}
+
+/// A key handled by the completion menu when it is visible.
+#[derive(Debug, Clone, Copy, PartialEq, Eq)]
+pub(super) enum MenuKey {
+    Up,
+    Down,
+    Enter,
+    Escape,
+}
+
+/// The action of a [`MenuKey`] on the completion menu.
+#[derive(Debug, Clone, Copy, PartialEq, Eq)]
+pub(super) enum MenuAction {
+    /// Select the item at the index.
+    Select(usize),
+    /// Accept the item at the index.
+    Confirm(usize),
+    /// Close the menu.
+    Close,
+    /// Nothing to do, e.g.: there are no items.
+    None,
+}
+
+/// Returns the action of the `key` on the completion menu, or `None` if the menu is not visible,
+/// then the key is handled by the input.
+///
+/// Up and down select the previous or next item and wrap around at the end.
+pub(super) fn menu_key_action(
+    key: MenuKey,
+    visible: bool,
+    selected_index: Option<usize>,
+    items_count: usize,
+) -> Option<MenuAction> {
+    if !visible {
+        return None;
+    }
+
+    Some(match key {
+        MenuKey::Up | MenuKey::Down if items_count == 0 => MenuAction::None,
+        MenuKey::Up | MenuKey::Down => MenuAction::Select(match selected_index {
+            None => 0,
+            Some(ix) if key == MenuKey::Up => (ix + items_count - 1) % items_count,
+            Some(ix) => (ix + 1) % items_count,
+        }),
+        MenuKey::Enter => selected_index
+            .filter(|ix| *ix < items_count)
+            .map_or(MenuAction::Close, MenuAction::Confirm),
+        MenuKey::Escape => MenuAction::Close,
+    })
+}
+
+/// Returns the change to replace the `word_range` of the `text` with the accepted `item`.
+pub(super) fn completion_change(
+    text: &Rope,
+    word_range: &Range<usize>,
+    item: &CompletionItem,
+) -> Change {
+    let start = word_range.start;
+    Change::new(
+        word_range.clone(),
+        &text.slice(word_range.clone()),
+        start..start + item.text.len(),
+        &item.text,
+    )
+}
+
+/// The delegate of the `List` in the completion menu.
+pub(super) struct CompletionListDelegate {
+    input: WeakEntity<TextInput>,
+    pub(super) items: Vec<CompletionItem>,
+}
+
+impl CompletionListDelegate {
+    pub(super) fn new(input: WeakEntity<TextInput>) -> Self {
+        Self {
+            input,
+            items: Vec::new(),
+        }
+    }
+}
+
+impl ListDelegate for CompletionListDelegate {
+    type Item = ListItem;
+
+    fn items_count(&self, _: &App) -> usize {
+        self.items.len()
+    }
+
+    fn render_item(
+        &self,
+        ix: usize,
+        _: &mut Window,
+        cx: &mut Context<List<Self>>,
+    ) -> Option<Self::Item> {
+        let item = self.items.get(ix)?;
+        let size = self
+            .input
+            .upgrade()
+            .map_or(Size::Medium, |input| input.read(cx).size);
+
+        Some(
+            ListItem::new(("completion-item", ix))
+                .cursor_pointer()
+                .input_text_size(size)
+                .list_size(size)
+                .child(
+                    h_flex()
+                        .w_full()
+                        .justify_between()
+                        .gap_4()
+                        .child(div().whitespace_nowrap().child(item.label.clone()))
+                        .when_some(item.detail.clone(), |this, detail| {
+                            this.child(
+                                div()
+                                    .whitespace_nowrap()
+                                    .text_xs()
+                                    .text_color(cx.theme().muted_foreground)
+                                    .child(detail),
+                            )
+                        }),
+                ),
+        )
+    }
+
+    fn set_selected_index(
+        &mut self,
+        _: Option<usize>,
+        _: &mut Window,
+        _: &mut Context<List<Self>>,
+    ) {
+    }
+
+    fn confirm(&mut self, ix: usize, window: &mut Window, cx: &mut Context<List<Self>>) {
+        let Some(item) = self.items.get(ix).cloned() else {
+            return;
+        };
+
+        let input = self.input.clone();
+        cx.defer_in(window, move |_, window, cx| {
+            _ = input.update(cx, |input, cx| {
+                input.accept_completion(item, window, cx);
+                input.focus(window, cx);
+            });
+        });
+    }
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+
+    fn is_word_char(c: char) -> bool {
+        c.is_alphanumeric() || c == '_'
+    }
+
+    #[test]
+    fn test_word_range() {
+        let text = Rope::from(
+            "1.5 * co2_fac
+sum(电力 排放",
+        );
+        let line2 = 14;
+
+        assert_eq!(word_range(&text, 0, is_word_char), 0..0);
+        assert_eq!(word_range(&text, 3, is_word_char), 2..3);
+        assert_eq!(word_range(&text, 13, is_word_char), 6..13);
+        assert_eq!(word_range(&text, 9, is_word_char), 6..9);
+        // The word does not cross the line.
+        assert_eq!(word_range(&text, line2 + 3, is_word_char), line2..line2 + 3);
+        assert_eq!(
+            word_range(&text, line2 + 4, is_word_char),
+            line2 + 4..line2 + 4
+        );
+        assert_eq!(
+            word_range(&text, text.len(), is_word_char),
+            text.len() - 6..text.len()
+        );
+        assert_eq!(
+            word_range(&text, 13, |c| is_word_char(c) || c == '.'),
+            6..13
+        );
+        assert_eq!(word_range(&text, 3, |c| is_word_char(c) || c == '.'), 0..3);
+    }
+
+    /// Type the `new_text` at the `cursor` like the input does, returns the query if the completions are queried.
+    fn type_text(
+        text: &mut Rope,
+        cursor: &mut usize,
+        new_text: &str,
+        menu_visible: bool,
+    ) -> Option<String> {
+        text.replace(*cursor..*cursor, new_text);
+        *cursor += new_text.len();
+        let word_range = word_range(text, *cursor, is_word_char);
+        should_query(
+            new_text,
+            menu_visible,
+            word_range.is_empty(),
+            is_word_char,
+            |c| c == '.',
+        )
+        .then(|| text.slice(word_range))
+    }
+
+    #[test]
+    fn test_query_on_typing() {
+        let mut text = Rope::from("let x = ");
+        let mut cursor = text.len();
+
+        assert_eq!(
+            type_text(&mut text, &mut cursor, "c", false).as_deref(),
+            Some("c")
+        );
+        assert_eq!(
+            type_text(&mut text, &mut cursor, "o", true).as_deref(),
+            Some("co")
+        );
+        assert_eq!(
+            type_text(&mut text, &mut cursor, "2", true).as_deref(),
+            Some("co2")
+        );
+        // The trigger char queries with an empty word.
+        assert_eq!(
+            type_text(&mut text, &mut cursor, ".", true).as_deref(),
+            Some("")
+        );
+        // A non-word char closes the menu.
+        assert_eq!(type_text(&mut text, &mut cursor, " ", true), None);
+        // Deleting queries again if the menu is visible and the word is not empty.
+        text.replace(2..3, "");
+        cursor = 2;
+        assert_eq!(
+            type_text(&mut text, &mut cursor, "", true).as_deref(),
+            Some("le")
+        );
+        assert_eq!(type_text(&mut text, &mut cursor, "", false), None);
+    }
+
+    #[test]
+    fn test_should_query() {
+        let is_word_char = |c: char| c.is_alphanumeric() || c == '_';
+        let is_trigger_char = |c: char| c == '.';
+        let query = |new_text: &str, menu_visible: bool, word_is_empty: bool| {
+            should_query(
+                new_text,
+                menu_visible,
+                word_is_empty,
+                is_word_char,
+                is_trigger_char,
+            )
+        };
+
+        // Typing a word char or a trigger char.
+        assert!(query("a", false, false));
+        assert!(query("_", true, false));
+        assert!(query(".", false, true));
+        assert!(query("电", false, false));
+        // Pasting a text ends with a word char.
+        assert!(query("foo bar", false, false));
+        // The other chars close the menu.
+        assert!(!query(" ", true, false));
+        assert!(!query("(", true, false));
+        assert!(!query("foo\n", true, false));
+
+        // Deleting only updates the visible menu of a word.
+        assert!(query("", true, false));
+        assert!(!query("", true, true));
+        assert!(!query("", false, false));
+    }
+
+    #[test]
+    fn test_menu_key_action() {
+        // The keys are handled by the input if the menu is not visible.
+        for key in [MenuKey::Up, MenuKey::Down, MenuKey::Enter, MenuKey::Escape] {
+            assert_eq!(menu_key_action(key, false, Some(0), 3), None);
+        }
+
+        assert_eq!(
+            menu_key_action(MenuKey::Down, true, Some(0), 3),
+            Some(MenuAction::Select(1))
+        );
+        assert_eq!(
+            menu_key_action(MenuKey::Down, true, Some(2), 3),
+            Some(MenuAction::Select(0))
+        );
+        assert_eq!(
+            menu_key_action(MenuKey::Up, true, Some(0), 3),
+            Some(MenuAction::Select(2))
+        );
+        assert_eq!(
+            menu_key_action(MenuKey::Up, true, None, 3),
+            Some(MenuAction::Select(0))
+        );
+        assert_eq!(
+            menu_key_action(MenuKey::Down, true, None, 0),
+            Some(MenuAction::None)
+        );
+
+        assert_eq!(
+            menu_key_action(MenuKey::Enter, true, Some(1), 3),
+            Some(MenuAction::Confirm(1))
+        );
+        assert_eq!(
+            menu_key_action(MenuKey::Enter, true, None, 3),
+            Some(MenuAction::Close)
+        );
+        assert_eq!(
+            menu_key_action(MenuKey::Enter, true, Some(3), 3),
+            Some(MenuAction::Close)
+        );
+        assert_eq!(
+            menu_key_action(MenuKey::Escape, true, Some(1), 3),
+            Some(MenuAction::Close)
+        );
+    }
+
+    #[test]
+    fn test_accept_completion() {
+        let mut text = Rope::from("let x = co2_f + 1");
+        let cursor = 13;
+        let word_range = word_range(&text, cursor, is_word_char);
+        assert_eq!(word_range, 8..13);
+
+        let item = CompletionItem::new("co2_factor").label("CO2 factor");
+        let change = completion_change(&text, &word_range, &item);
+        assert_eq!(change.old_text, "co2_f");
+        text.replace(change.old_range.clone(), &change.new_text);
+        assert_eq!(text.slice(0..text.len()), "let x = co2_factor + 1");
+        // The cursor is moved to the end of the inserted text.
+        assert_eq!(change.new_range.end, 18);
+        assert_eq!(text.slice(change.new_range.clone()), "co2_factor");
+
+        // Undo the change restores the typed word.
+        text.replace(change.new_range.clone(), &change.old_text);
+        assert_eq!(text.slice(0..text.len()), "let x = co2_f + 1");
+    }
+}
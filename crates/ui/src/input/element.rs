@@ -1,16 +1,34 @@
 use gpui::{
     fill, point, px, relative, size, App, Bounds, Corners, Element, ElementId, ElementInputHandler,
-    Entity, GlobalElementId, IntoElement, LayoutId, MouseButton, MouseMoveEvent, PaintQuad, Path,
-    Pixels, Point, Style, TextAlign, TextRun, UnderlineStyle, Window, WrappedLine,
+    Entity, GlobalElementId, HighlightStyle, IntoElement, LayoutId, MouseButton, MouseMoveEvent,
+    PaintQuad, Path, Pixels, Point, ShapedLine, SharedString, Style, TextAlign, TextRun, TextStyle,
+    UnderlineStyle, Window, WrappedLine,
 };
 use smallvec::SmallVec;
+use std::ops::Range;
 
-use crate::ActiveTheme as _;
+use crate::highlighter::HighlightTheme;
+use crate::{ActiveTheme as _, ColorName};
 
 use super::TextInput;
 
 const RIGHT_MARGIN: Pixels = px(5.);
 const BOTTOM_MARGIN: Pixels = px(20.);
+/// The gap between the line numbers and the text of the code editor.
+const GUTTER_GAP: Pixels = px(12.);
+
+/// The layout of the last paint, only the lines in the viewport are laid out.
+#[derive(Clone)]
+pub(super) struct LastLayout {
+    /// The wrapped lines of the `visible_range`.
+    pub(super) lines: SmallVec<[WrappedLine; 1]>,
+    /// The range of the laid out lines (0-based line numbers).
+    pub(super) visible_range: Range<usize>,
+    /// The offset of the first laid out line in the text.
+    pub(super) visible_offset: usize,
+    /// The y position of the first laid out line, relative to the text origin.
+    pub(super) visible_top: Pixels,
+}
 
 pub(super) struct TextElement {
     input: Entity<TextInput>,
@@ -37,8 +55,9 @@ impl TextElement {
 
     fn layout_cursor(
         &self,
-        lines: &[WrappedLine],
+        layout: &LastLayout,
         line_height: Pixels,
+        mut scroll_offset: Point<Pixels>,
         bounds: &mut Bounds<Pixels>,
         window: &mut Window,
         cx: &mut App,
@@ -46,7 +65,6 @@ impl TextElement {
         let input = self.input.read(cx);
         let selected_range = &input.selected_range;
         let cursor_offset = input.cursor_offset();
-        let mut scroll_offset = input.scroll_handle.offset();
         let mut cursor = None;
 
         // The cursor corresponds to the current cursor position in the text no only the line.
@@ -54,29 +72,29 @@ impl TextElement {
         let mut cursor_start = None;
         let mut cursor_end = None;
 
-        let mut prev_lines_offset = 0;
-        let mut offset_y = px(0.);
-        for line in lines.iter() {
+        let mut prev_lines_offset = layout.visible_offset;
+        let mut offset_y = layout.visible_top;
+        for line in layout.lines.iter() {
             // break loop if all cursor positions are found
             if cursor_pos.is_some() && cursor_start.is_some() && cursor_end.is_some() {
                 break;
             }
 
             let line_origin = point(px(0.), offset_y);
-            if cursor_pos.is_none() {
-                let offset = cursor_offset.saturating_sub(prev_lines_offset);
+            if cursor_pos.is_none() && cursor_offset >= prev_lines_offset {
+                let offset = cursor_offset - prev_lines_offset;
                 if let Some(pos) = line.position_for_index(offset, line_height) {
                     cursor_pos = Some(line_origin + pos);
                 }
             }
-            if cursor_start.is_none() {
-                let offset = selected_range.start.saturating_sub(prev_lines_offset);
+            if cursor_start.is_none() && selected_range.start >= prev_lines_offset {
+                let offset = selected_range.start - prev_lines_offset;
                 if let Some(pos) = line.position_for_index(offset, line_height) {
                     cursor_start = Some(line_origin + pos);
                 }
             }
-            if cursor_end.is_none() {
-                let offset = selected_range.end.saturating_sub(prev_lines_offset);
+            if cursor_end.is_none() && selected_range.end >= prev_lines_offset {
+                let offset = selected_range.end - prev_lines_offset;
                 if let Some(pos) = line.position_for_index(offset, line_height) {
                     cursor_end = Some(line_origin + pos);
                 }
@@ -87,9 +105,8 @@ impl TextElement {
             prev_lines_offset += line.len() + 1;
         }
 
-        if let (Some(cursor_pos), Some(cursor_start), Some(cursor_end)) =
-            (cursor_pos, cursor_start, cursor_end)
-        {
+        // The cursor may be out of the laid out lines, when the view is scrolled away from it.
+        if let Some(cursor_pos) = cursor_pos {
             let cursor_moved = input.last_cursor_offset != Some(cursor_offset);
             let selection_changed = input.last_selected_range != Some(selected_range.clone());
 
@@ -116,15 +133,17 @@ impl TextElement {
                     };
 
                 if input.selection_reversed {
-                    if scroll_offset.x + cursor_start.x < px(0.) {
-                        // selection start is out of left
-                        scroll_offset.x = -cursor_start.x;
-                    }
-                    if scroll_offset.y + cursor_start.y < px(0.) {
-                        // selection start is out of top
-                        scroll_offset.y = -cursor_start.y;
+                    if let Some(cursor_start) = cursor_start {
+                        if scroll_offset.x + cursor_start.x < px(0.) {
+                            // selection start is out of left
+                            scroll_offset.x = -cursor_start.x;
+                        }
+                        if scroll_offset.y + cursor_start.y < px(0.) {
+                            // selection start is out of top
+                            scroll_offset.y = -cursor_start.y;
+                        }
                     }
-                } else {
+                } else if let Some(cursor_end) = cursor_end {
                     if scroll_offset.x + cursor_end.x <= px(0.) {
                         // selection end is out of left
                         scroll_offset.x = -cursor_end.x;
@@ -135,9 +154,11 @@ impl TextElement {
                     }
                 }
             }
+        }
 
-            bounds.origin = bounds.origin + scroll_offset;
+        bounds.origin = bounds.origin + scroll_offset;
 
+        if let Some(cursor_pos) = cursor_pos {
             if input.show_cursor(window, cx) {
                 // cursor blink
                 let cursor_height =
@@ -158,16 +179,105 @@ impl TextElement {
         (cursor, scroll_offset)
     }
 
+    /// Returns the cursors of the other selections of the multiple cursors.
+    fn layout_other_cursors(
+        &self,
+        layout: &LastLayout,
+        line_height: Pixels,
+        bounds: &Bounds<Pixels>,
+        window: &mut Window,
+        cx: &mut App,
+    ) -> Vec<PaintQuad> {
+        let input = self.input.read(cx);
+        if input.other_selections.is_empty() || !input.show_cursor(window, cx) {
+            return vec![];
+        }
+
+        let cursor_height = window.text_style().font_size.to_pixels(window.rem_size()) + px(2.);
+        input
+            .other_selections
+            .iter()
+            .filter_map(|selection| {
+                let cursor_pos = position_for_offset(layout, selection.head(), line_height)?;
+                Some(fill(
+                    Bounds::new(
+                        point(
+                            bounds.left() + cursor_pos.x,
+                            bounds.top() + cursor_pos.y + ((line_height - cursor_height) / 2.),
+                        ),
+                        size(px(1.), cursor_height),
+                    ),
+                    cx.theme().caret,
+                ))
+            })
+            .collect()
+    }
+
+    /// Returns the current line background and the line numbers of the code editor.
+    fn layout_gutter(
+        &self,
+        layout: &LastLayout,
+        line_height: Pixels,
+        gutter_width: Pixels,
+        element_bounds: &Bounds<Pixels>,
+        bounds: &Bounds<Pixels>,
+        window: &mut Window,
+        cx: &mut App,
+    ) -> (Option<PaintQuad>, Vec<(ShapedLine, Point<Pixels>)>) {
+        let input = self.input.read(cx);
+        let style = window.text_style();
+        let font_size = style.font_size.to_pixels(window.rem_size());
+        let current_row = input.text.offset_to_line(input.cursor_offset());
+        let show_current_line = input.focus_handle.is_focused(window)
+            && input.selected_range.is_empty()
+            && input.other_selections.is_empty();
+        // The line numbers are right aligned, and are not scrolled horizontally.
+        let numbers_right = element_bounds.left() + gutter_width - GUTTER_GAP;
+
+        let mut current_line = None;
+        let mut line_numbers = vec![];
+        let mut y = bounds.top() + layout.visible_top;
+        for (row, line) in layout.visible_range.clone().zip(layout.lines.iter()) {
+            let height = line.size(line_height).height;
+            let is_current = row == current_row;
+            if is_current && show_current_line {
+                current_line = Some(fill(
+                    Bounds::new(
+                        point(element_bounds.left(), y),
+                        size(element_bounds.size.width, height),
+                    ),
+                    cx.theme().accent.opacity(0.5),
+                ));
+            }
+
+            let number: SharedString = (row + 1).to_string().into();
+            let mut number_style = style.clone();
+            number_style.color = if is_current {
+                cx.theme().foreground
+            } else {
+                cx.theme().muted_foreground
+            };
+            let run = number_style.to_run(number.len());
+            if let Ok(shaped) = window.text_system().shape_line(number, font_size, &[run]) {
+                let origin = point(numbers_right - shaped.width, y);
+                line_numbers.push((shaped, origin));
+            }
+
+            y += height;
+        }
+
+        (current_line, line_numbers)
+    }
+
     fn layout_selections(
         &self,
-        lines: &[WrappedLine],
+        selected_range: &Range<usize>,
+        layout: &LastLayout,
         line_height: Pixels,
         bounds: &mut Bounds<Pixels>,
         _: &mut Window,
-        cx: &mut App,
+        _: &mut App,
     ) -> Option<Path<Pixels>> {
-        let input = self.input.read(cx);
-        let selected_range = &input.selected_range;
         if selected_range.is_empty() {
             return None;
         }
@@ -178,11 +288,16 @@ impl TextElement {
             (selected_range.end, selected_range.start)
         };
 
-        let mut prev_lines_offset = 0;
+        // The selection is above the laid out lines.
+        if end_ix < layout.visible_offset {
+            return None;
+        }
+
+        let mut prev_lines_offset = layout.visible_offset;
         let mut line_corners = vec![];
 
-        let mut offset_y = px(0.);
-        for line in lines.iter() {
+        let mut offset_y = layout.visible_top;
+        for line in layout.lines.iter() {
             let line_size = line.size(line_height);
             let line_wrap_width = line_size.width;
 
@@ -284,11 +399,112 @@ impl TextElement {
     }
 }
 
+/// Returns the position of the offset in the laid out lines, relative to the text origin.
+fn position_for_offset(
+    layout: &LastLayout,
+    offset: usize,
+    line_height: Pixels,
+) -> Option<Point<Pixels>> {
+    let mut prev_lines_offset = layout.visible_offset;
+    let mut offset_y = layout.visible_top;
+    for line in layout.lines.iter() {
+        if offset < prev_lines_offset {
+            break;
+        }
+
+        if let Some(pos) = line.position_for_index(offset - prev_lines_offset, line_height) {
+            return Some(point(px(0.), offset_y) + pos);
+        }
+
+        offset_y += line.size(line_height).height;
+        // +1 for skip the last `\n`
+        prev_lines_offset += line.len() + 1;
+    }
+
+    None
+}
+
+/// Returns the text runs of the text with the highlights, the highlights must be sorted and not overlapped.
+fn runs_for_highlights(
+    style: &TextStyle,
+    len: usize,
+    highlights: impl IntoIterator<Item = (Range<usize>, HighlightStyle)>,
+) -> Vec<TextRun> {
+    let mut runs = vec![];
+    let mut ix = 0;
+    for (range, highlight) in highlights {
+        let range = range.start.max(ix)..range.end.min(len);
+        if range.is_empty() {
+            continue;
+        }
+
+        if range.start > ix {
+            runs.push(style.to_run(range.start - ix));
+        }
+        runs.push(style.clone().highlight(highlight).to_run(range.len()));
+        ix = range.end;
+    }
+    if ix < len || runs.is_empty() {
+        runs.push(style.to_run(len - ix));
+    }
+
+    runs
+}
+
+/// Returns the y position of the top of the line, estimated by the wrapped rows of the lines above.
+fn line_top(line_rows: &[usize], row: usize, line_height: Pixels) -> Pixels {
+    line_height * line_rows[..row].iter().sum::<usize>() as f32
+}
+
+/// Returns the range of the lines in the viewport and the y position of the first line,
+/// with one more line above and below the viewport.
+fn visible_lines(
+    line_rows: &[usize],
+    scroll_top: Pixels,
+    height: Pixels,
+    line_height: Pixels,
+) -> (Range<usize>, Pixels) {
+    let mut start = None;
+    let mut end = line_rows.len();
+    let mut top = px(0.);
+    let mut prev_top = px(0.);
+    for (row, rows) in line_rows.iter().enumerate() {
+        let bottom = top + line_height * *rows as f32;
+        if start.is_none() && bottom > scroll_top {
+            start = Some(if row > 0 {
+                (row - 1, prev_top)
+            } else {
+                (row, top)
+            });
+        }
+        if top > scroll_top + height {
+            end = row + 1;
+            break;
+        }
+
+        prev_top = top;
+        top = bottom;
+    }
+
+    // Scrolled out of the lines, e.g.: the lines are removed, keep the last line.
+    let (start, start_top) = start.unwrap_or((line_rows.len().saturating_sub(1), prev_top));
+    (start..end.max(start + 1), start_top)
+}
+
 pub(super) struct PrepaintState {
-    lines: SmallVec<[WrappedLine; 1]>,
+    layout: LastLayout,
     cursor: Option<PaintQuad>,
+    other_cursors: Vec<PaintQuad>,
     cursor_scroll_offset: Point<Pixels>,
-    selection_path: Option<Path<Pixels>>,
+    selection_paths: Vec<Path<Pixels>>,
+    /// The matches of the find bar, with true for the current match.
+    match_paths: Vec<(Path<Pixels>, bool)>,
+    /// The height of all the lines, include the lines that are not laid out.
+    scroll_height: Pixels,
+    /// The background of the cursor line of the code editor.
+    current_line: Option<PaintQuad>,
+    /// The line numbers of the code editor, with the paint origin.
+    line_numbers: Vec<(ShapedLine, Point<Pixels>)>,
     bounds: Bounds<Pixels>,
 }
 
@@ -370,73 +586,180 @@ impl Element for TextElement {
         window: &mut Window,
         cx: &mut App,
     ) -> Self::PrepaintState {
-        let multi_line = self.input.read(cx).is_multi_line();
-        let line_height = window.line_height();
         let input = self.input.read(cx);
-        let text = input.text.clone();
-        let placeholder = input.placeholder.clone();
+        let multi_line = input.is_multi_line();
+        let line_height = window.line_height();
         let style = window.text_style();
+        let font_size = style.font_size.to_pixels(window.rem_size());
+        let element_bounds = bounds;
         let mut bounds = bounds;
 
-        let (display_text, text_color) = if text.is_empty() {
+        // Leave the space of the line numbers for the code editor.
+        let gutter_width = if input.is_code_editor() {
+            let max_line_number: SharedString = input.text.lines_len().to_string().into();
+            let run = style.to_run(max_line_number.len());
+            window
+                .text_system()
+                .shape_line(max_line_number, font_size, &[run])
+                .map_or(px(0.), |line| line.width)
+                + GUTTER_GAP
+        } else {
+            px(0.)
+        };
+        bounds.origin.x += gutter_width;
+        bounds.size.width -= gutter_width;
+
+        let wrap_width = if multi_line {
+            Some(bounds.size.width - RIGHT_MARGIN)
+        } else {
+            None
+        };
+
+        self.input.update(cx, |input, _| {
+            let lines_len = input.text.lines_len();
+            input.line_rows.resize(lines_len, 1);
+            // The wrapped rows are changed with the width, reset them and update when the lines are laid out.
+            if input.last_bounds.map(|bounds| bounds.size.width) != Some(bounds.size.width) {
+                input.line_rows.fill(1);
+            }
+        });
+
+        let input = self.input.read(cx);
+        let mut scroll_offset = input.scroll_handle.offset();
+        let (visible_range, visible_top) = if multi_line {
+            let cursor_offset = input.cursor_offset();
+            let cursor_moved = input.last_cursor_offset != Some(cursor_offset)
+                || input.last_selected_range.as_ref() != Some(&input.selected_range);
+            // Scroll to the cursor line before the layout, to make sure the cursor line is laid out.
+            if cursor_moved {
+                let row = input.text.offset_to_line(cursor_offset);
+                let top = line_top(&input.line_rows, row, line_height);
+                let bottom = top + line_height * input.line_rows[row] as f32;
+                if scroll_offset.y + top < px(0.) {
+                    scroll_offset.y = -top;
+                } else if scroll_offset.y + bottom > bounds.size.height {
+                    scroll_offset.y = bounds.size.height - bottom;
+                }
+            }
+
+            visible_lines(
+                &input.line_rows,
+                -scroll_offset.y,
+                bounds.size.height,
+                line_height,
+            )
+        } else {
+            (0..input.text.lines_len(), px(0.))
+        };
+
+        let visible_offset = input.text.line_start(visible_range.start);
+        let visible_end = input.text.line_end(visible_range.end - 1);
+        let placeholder = input.placeholder.clone();
+
+        let (display_text, text_color): (SharedString, _) = if input.text.is_empty() {
             (placeholder, cx.theme().muted_foreground)
         } else if input.masked {
             (
-                "*".repeat(text.chars().count()).into(),
+                "*".repeat(
+                    input
+                        .text
+                        .slice(visible_offset..visible_end)
+                        .chars()
+                        .count(),
+                )
+                .into(),
                 cx.theme().foreground,
             )
         } else {
-            (text, cx.theme().foreground)
+            (
+                input.text.slice(visible_offset..visible_end).into(),
+                cx.theme().foreground,
+            )
         };
 
-        let run = TextRun {
-            len: display_text.len(),
-            font: style.font(),
-            color: text_color,
-            background_color: None,
-            underline: None,
-            strikethrough: None,
-        };
+        let mut text_style = style.clone();
+        text_style.color = text_color;
+
+        // The syntax highlights of the code editor, the highlighter only parses the lines from the first edited line.
+        let highlights = self.input.update(cx, |input, cx| {
+            if input.text.is_empty() || input.masked {
+                return vec![];
+            }
+            let Some(highlighter) = input.highlighter.as_mut() else {
+                return vec![];
+            };
 
-        let runs = if let Some(marked_range) = input.marked_range.as_ref() {
-            vec![
-                TextRun {
-                    len: marked_range.start,
-                    ..run.clone()
-                },
-                TextRun {
-                    len: marked_range.end - marked_range.start,
+            let theme = input
+                .highlight_theme
+                .clone()
+                .unwrap_or_else(|| HighlightTheme::global(cx));
+            highlighter.set_theme(&theme);
+
+            let text = &input.text;
+            highlighter
+                .highlight_lines(visible_range.clone(), |row| text.line(row))
+                .into_iter()
+                .zip(visible_range.clone())
+                .flat_map(|(styles, row)| {
+                    let line_offset = text.line_start(row) - visible_offset;
+                    styles.into_iter().map(move |(range, style)| {
+                        (line_offset + range.start..line_offset + range.end, style)
+                    })
+                })
+                .collect::<Vec<_>>()
+        });
+        let input = self.input.read(cx);
+
+        let marked_highlight = input
+            .marked_range
+            .as_ref()
+            .map(|range| {
+                let clamp = |offset: usize| {
+                    offset
+                        .saturating_sub(visible_offset)
+                        .min(display_text.len())
+                };
+                clamp(range.start)..clamp(range.end)
+            })
+            .filter(|range| !range.is_empty())
+            .map(|range| {
+                let style = HighlightStyle {
                     underline: Some(UnderlineStyle {
-                        color: Some(run.color),
+                        color: Some(text_color),
                         thickness: px(1.0),
                         wavy: false,
                     }),
-                    ..run.clone()
-                },
-                TextRun {
-                    len: display_text.len() - marked_range.end,
-                    ..run.clone()
-                },
-            ]
-            .into_iter()
-            .filter(|run| run.len > 0)
-            .collect()
-        } else {
-            vec![run]
-        };
-
-        let font_size = style.font_size.to_pixels(window.rem_size());
-        let wrap_width = if multi_line {
-            Some(bounds.size.width - RIGHT_MARGIN)
-        } else {
-            None
-        };
+                    ..Default::default()
+                };
+                (range, style)
+            });
+        let runs = runs_for_highlights(
+            &text_style,
+            display_text.len(),
+            gpui::combine_highlights(highlights, marked_highlight),
+        );
 
         let lines = window
             .text_system()
             .shape_text(display_text, font_size, &runs, wrap_width, None)
             .unwrap();
 
+        self.input.update(cx, |input, _| {
+            for (rows, line) in input.line_rows[visible_range.clone()]
+                .iter_mut()
+                .zip(lines.iter())
+            {
+                *rows = line.wrap_boundaries.len() + 1;
+            }
+        });
+
+        let layout = LastLayout {
+            lines,
+            visible_range,
+            visible_offset,
+            visible_top,
+        };
+
         // `position_for_index` for example
         //
         // #### text
@@ -469,16 +792,78 @@ impl Element for TextElement {
         // Calculate the scroll offset to keep the cursor in view
 
         let (cursor, cursor_scroll_offset) =
-            self.layout_cursor(&lines, line_height, &mut bounds, window, cx);
+            self.layout_cursor(&layout, line_height, scroll_offset, &mut bounds, window, cx);
 
-        let selection_path = self.layout_selections(&lines, line_height, &mut bounds, window, cx);
+        let other_cursors = self.layout_other_cursors(&layout, line_height, &bounds, window, cx);
+
+        let input = self.input.read(cx);
+        let selected_ranges = input
+            .other_selections
+            .iter()
+            .map(|selection| selection.range.clone())
+            .chain(Some(input.selected_range.clone()))
+            .collect::<Vec<_>>();
+        // Only the matches of the find bar in the laid out lines.
+        let layout_end = layout.visible_offset
+            + layout
+                .lines
+                .iter()
+                .map(|line| line.len() + 1)
+                .sum::<usize>();
+        let visible_matches = input
+            .find_matches()
+            .map(|(matches, current)| {
+                let start = matches.partition_point(|range| range.end < layout.visible_offset);
+                matches[start..]
+                    .iter()
+                    .take_while(|range| range.start <= layout_end)
+                    .map(|range| (range.clone(), current.as_ref() == Some(range)))
+                    .collect::<Vec<_>>()
+            })
+            .unwrap_or_default();
+        let scroll_height = line_height * input.line_rows.iter().sum::<usize>() as f32;
+        let is_code_editor = input.is_code_editor();
+
+        let selection_paths = selected_ranges
+            .iter()
+            .filter_map(|range| {
+                self.layout_selections(range, &layout, line_height, &mut bounds, window, cx)
+            })
+            .collect();
+        let match_paths = visible_matches
+            .into_iter()
+            .filter_map(|(range, is_current)| {
+                let path =
+                    self.layout_selections(&range, &layout, line_height, &mut bounds, window, cx)?;
+                Some((path, is_current))
+            })
+            .collect();
+
+        let (current_line, line_numbers) = if is_code_editor {
+            self.layout_gutter(
+                &layout,
+                line_height,
+                gutter_width,
+                &element_bounds,
+                &bounds,
+                window,
+                cx,
+            )
+        } else {
+            (None, vec![])
+        };
 
         PrepaintState {
             bounds,
-            lines,
+            layout,
+            scroll_height,
+            current_line,
+            line_numbers,
             cursor,
+            other_cursors,
             cursor_scroll_offset,
-            selection_path,
+            selection_paths,
+            match_paths,
         }
     }
 
@@ -502,25 +887,38 @@ impl Element for TextElement {
             cx,
         );
 
+        if let Some(current_line) = prepaint.current_line.take() {
+            window.paint_quad(current_line);
+        }
+
+        // Paint the matches of the find bar
+        for (path, is_current) in prepaint.match_paths.drain(..) {
+            let opacity = if is_current { 0.5 } else { 0.2 };
+            window.paint_path(path, ColorName::Yellow.scale(500).opacity(opacity));
+        }
+
         // Paint selections
-        if let Some(path) = prepaint.selection_path.take() {
+        for path in prepaint.selection_paths.drain(..) {
             window.paint_path(path, cx.theme().selection);
         }
 
         // Paint multi line text
         let line_height = window.line_height();
+        for (line_number, origin) in prepaint.line_numbers.drain(..) {
+            _ = line_number.paint(origin, line_height, window, cx);
+        }
         let origin = bounds.origin;
 
-        let mut offset_y = px(0.);
+        let mut offset_y = prepaint.layout.visible_top;
         if self.input.read(cx).masked {
             // Move down offset for vertical centering the *****
             if cfg!(target_os = "macos") {
-                offset_y = px(3.);
+                offset_y += px(3.);
             } else {
-                offset_y = px(2.5);
+                offset_y += px(2.5);
             }
         }
-        for line in prepaint.lines.iter() {
+        for line in prepaint.layout.lines.iter() {
             let p = point(origin.x, origin.y + offset_y);
             _ = line.paint(p, line_height, TextAlign::Left, None, window, cx);
             offset_y += line.size(line_height).height;
@@ -530,24 +928,23 @@ impl Element for TextElement {
             if let Some(cursor) = prepaint.cursor.take() {
                 window.paint_quad(cursor);
             }
+            for cursor in prepaint.other_cursors.drain(..) {
+                window.paint_quad(cursor);
+            }
         }
 
         let width = prepaint
+            .layout
             .lines
             .iter()
             .map(|l| l.width())
             .max()
             .unwrap_or_default();
-        let height = prepaint
-            .lines
-            .iter()
-            .map(|l| l.size(line_height).height.0)
-            .sum::<f32>();
 
-        let scroll_size = size(width, px(height));
+        let scroll_size = size(width, prepaint.scroll_height);
 
         self.input.update(cx, |input, _cx| {
-            input.last_layout = Some(prepaint.lines.clone());
+            input.last_layout = Some(prepaint.layout.clone());
             input.last_bounds = Some(bounds);
             input.last_cursor_offset = Some(input.cursor_offset());
             input.last_line_height = line_height;
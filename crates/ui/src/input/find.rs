@@ -0,0 +1,143 @@
+use std::ops::Range;
+
+use regex::{Regex, RegexBuilder};
+
+/// The toggles of the find bar in the `TextInput`.
+#[derive(Debug, Clone, Copy, Default, PartialEq, Eq)]
+pub(super) struct FindOptions {
+    pub(super) case_sensitive: bool,
+    pub(super) whole_word: bool,
+    pub(super) regex: bool,
+}
+
+impl FindOptions {
+    /// Build the regex to find the query, returns `None` if the query is empty or not a valid regex.
+    pub(super) fn build(&self, query: &str) -> Option<Regex> {
+        if query.is_empty() {
+            return None;
+        }
+
+        let mut pattern = if self.regex {
+            query.to_string()
+        } else {
+            regex::escape(query)
+        };
+        if self.whole_word {
+            pattern = format!(r"\b(?:{})\b", pattern);
+        }
+
+        RegexBuilder::new(&pattern)
+            .case_insensitive(!self.case_sensitive)
+            .multi_line(true)
+            .build()
+            .ok()
+    }
+}
+
+/// Returns the ranges of the matches in the text, the empty matches (e.g.: `^` of a regex) are skipped.
+pub(super) fn find_matches(text: &str, regex: &Regex) -> Vec<Range<usize>> {
+    regex
+        .find_iter(text)
+        .map(|m| m.range())
+        .filter(|range| !range.is_empty())
+        .collect()
+}
+
+/// Returns the text to replace the match at the `range`.
+///
+/// The groups (e.g.: `$1`, `${name}`) in the `replacement` are expanded if the query is a regex.
+pub(super) fn expand_replacement(
+    text: &str,
+    range: &Range<usize>,
+    regex: &Regex,
+    replacement: &str,
+    options: FindOptions,
+) -> String {
+    if !options.regex {
+        return replacement.to_string();
+    }
+
+    match regex
+        .captures_at(text, range.start)
+        .filter(|caps| caps.get(0).map(|m| m.range()).as_ref() == Some(range))
+    {
+        Some(caps) => {
+            let mut expanded = String::new();
+            caps.expand(replacement, &mut expanded);
+            expanded
+        }
+        None => replacement.to_string(),
+    }
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+
+    fn find(text: &str, query: &str, options: FindOptions) -> Vec<Range<usize>> {
+        options
+            .build(query)
+            .map(|regex| find_matches(text, &regex))
+            .unwrap_or_default()
+    }
+
+    #[test]
+    fn test_find_matches() {
+        let text = "Foo foo food (foo)\nfoo.bar";
+        let options = FindOptions::default();
+
+        assert_eq!(
+            find(text, "foo", options),
+            vec![0..3, 4..7, 8..11, 14..17, 19..22]
+        );
+        let case_sensitive = FindOptions {
+            case_sensitive: true,
+            ..options
+        };
+        assert_eq!(find(text, "Foo", case_sensitive), vec![0..3]);
+        let whole_word = FindOptions {
+            whole_word: true,
+            ..options
+        };
+        assert_eq!(
+            find(text, "foo", whole_word),
+            vec![0..3, 4..7, 14..17, 19..22]
+        );
+        // The query is not a regex without the toggle.
+        assert_eq!(find(text, "(foo)", options), vec![13..18]);
+        assert_eq!(find(text, ".", options), vec![22..23]);
+
+        let regex = FindOptions {
+            regex: true,
+            ..options
+        };
+        assert_eq!(find(text, r"fo+d?\b", regex).len(), 5);
+        assert_eq!(find(text, "^foo", regex), vec![0..3, 19..22]);
+        assert_eq!(find(text, "^", regex), vec![]);
+        // Invalid regex.
+        assert_eq!(find(text, "(foo", regex), vec![]);
+        assert_eq!(find(text, "", options), vec![]);
+    }
+
+    #[test]
+    fn test_expand_replacement() {
+        let text = "let foo = bar;";
+        let options = FindOptions {
+            regex: true,
+            ..Default::default()
+        };
+        let regex = options.build(r"(\w+) = (\w+)").unwrap();
+        let range = find_matches(text, &regex)[0].clone();
+        assert_eq!(
+            expand_replacement(text, &range, &regex, "$2 = $1", options),
+            "bar = foo"
+        );
+
+        let options = FindOptions::default();
+        let regex = options.build("foo").unwrap();
+        assert_eq!(
+            expand_replacement(text, &(4..7), &regex, "$1", options),
+            "$1"
+        );
+    }
+}
@@ -3,37 +3,48 @@
 //! Based on the `Input` example from the `gpui` crate.
 //! https://github.com/zed-industries/zed/blob/main/crates/gpui/examples/input.rs
 
-use smallvec::SmallVec;
-use std::cell::Cell;
+use std::cell::{Cell, OnceCell};
 use std::ops::Range;
 use std::rc::Rc;
 use unicode_segmentation::*;
 
 use gpui::prelude::FluentBuilder as _;
 use gpui::{
-    actions, div, point, px, relative, AnyElement, App, AppContext, Bounds, ClickEvent,
-    ClipboardItem, Context, DefiniteLength, Entity, EntityInputHandler, EventEmitter, FocusHandle,
-    Focusable, InteractiveElement as _, IntoElement, KeyBinding, KeyDownEvent, MouseButton,
-    MouseDownEvent, MouseMoveEvent, MouseUpEvent, ParentElement as _, Pixels, Point, Rems, Render,
-    ScrollHandle, ScrollWheelEvent, SharedString, Styled as _, Subscription, UTF16Selection,
-    Window, WrappedLine,
+    actions, anchored, deferred, div, point, px, relative, rems, AnyElement, App, AppContext,
+    Bounds, ClickEvent, ClipboardItem, Context, DefiniteLength, Entity, EntityInputHandler,
+    EventEmitter, FocusHandle, Focusable, InteractiveElement as _, IntoElement, KeyBinding,
+    KeyDownEvent, MouseButton, MouseDownEvent, MouseMoveEvent, MouseUpEvent, ParentElement as _,
+    Pixels, Point, Rems, Render, ScrollHandle, ScrollWheelEvent, SharedString, Styled as _,
+    Subscription, Task, UTF16Selection, Window, WrappedLine,
 };
+use rust_i18n::t;
 
 // TODO:
 // - Move cursor to skip line eof empty chars.
 
 use super::blink_cursor::BlinkCursor;
 use super::change::Change;
-use super::element::TextElement;
+use super::completion::{
+    self, CompletionItem, CompletionListDelegate, CompletionProvider, MenuAction, MenuKey,
+};
+use super::element::{LastLayout, TextElement};
+use super::find::{self, FindOptions};
 use super::number_input;
+use super::rope::Rope;
+use super::selection::{self, Selection};
 
+use crate::button::{Button, ButtonVariants as _};
+use crate::find::FindState;
+use crate::highlighter::{HighlightTheme, Highlighter};
 use crate::history::History;
 use crate::indicator::Indicator;
 use crate::input::clear_button;
+use crate::list::List;
 use crate::scroll::{Scrollbar, ScrollbarAxis, ScrollbarState};
 use crate::ActiveTheme;
 use crate::Size;
 use crate::StyledExt;
+use crate::{h_flex, v_flex, IconName, Selectable as _};
 use crate::{Sizable, StyleSized};
 
 actions!(
@@ -76,6 +87,14 @@ actions!(
         MoveToPreviousWord,
         MoveToNextWord,
         TextChanged,
+        SelectNextOccurrence,
+        Escape,
+        Indent,
+        Outdent,
+        Find,
+        FindAndReplace,
+        FindPrev,
+        ShowCompletions,
     ]
 );
 
@@ -88,6 +107,9 @@ pub enum InputEvent {
 }
 
 const CONTEXT: &str = "Input";
+const FIND_CONTEXT: &str = "InputFind";
+/// The indent of the code editor.
+const INDENT: &str = "    ";
 
 pub fn init(cx: &mut App) {
     cx.bind_keys([
@@ -184,15 +206,132 @@ pub fn init(cx: &mut App) {
         KeyBinding::new("ctrl-z", Undo, Some(CONTEXT)),
         #[cfg(not(target_os = "macos"))]
         KeyBinding::new("ctrl-y", Redo, Some(CONTEXT)),
+        #[cfg(target_os = "macos")]
+        KeyBinding::new("cmd-d", SelectNextOccurrence, Some(CONTEXT)),
+        #[cfg(not(target_os = "macos"))]
+        KeyBinding::new("ctrl-d", SelectNextOccurrence, Some(CONTEXT)),
+        KeyBinding::new("escape", Escape, Some(CONTEXT)),
+        KeyBinding::new("tab", Indent, Some(CONTEXT)),
+        KeyBinding::new("shift-tab", Outdent, Some(CONTEXT)),
+        #[cfg(target_os = "macos")]
+        KeyBinding::new("cmd-f", Find, Some(CONTEXT)),
+        #[cfg(not(target_os = "macos"))]
+        KeyBinding::new("ctrl-f", Find, Some(CONTEXT)),
+        #[cfg(target_os = "macos")]
+        KeyBinding::new("cmd-h", FindAndReplace, Some(CONTEXT)),
+        #[cfg(not(target_os = "macos"))]
+        KeyBinding::new("ctrl-h", FindAndReplace, Some(CONTEXT)),
+        KeyBinding::new("shift-enter", FindPrev, Some(FIND_CONTEXT)),
+        KeyBinding::new("ctrl-space", ShowCompletions, Some(CONTEXT)),
     ]);
 
     number_input::init(cx);
 }
 
+/// The find and replace bar of the multi-line `TextInput`, opened by `cmd-f` or `cmd-h`.
+struct FindBar {
+    query_input: Entity<TextInput>,
+    replace_input: Entity<TextInput>,
+    visible: bool,
+    /// Show the replace input.
+    replacing: bool,
+    options: FindOptions,
+    state: FindState<Range<usize>>,
+    /// True if the text is changed after the last search.
+    dirty: bool,
+    _subscriptions: Vec<Subscription>,
+}
+
+impl FindBar {
+    fn new(window: &mut Window, cx: &mut Context<TextInput>) -> Self {
+        let query_input = cx.new(|cx| {
+            TextInput::new(window, cx)
+                .xsmall()
+                .appearance(false)
+                .placeholder(t!("Input.Find"))
+        });
+        let replace_input = cx.new(|cx| {
+            TextInput::new(window, cx)
+                .xsmall()
+                .appearance(false)
+                .placeholder(t!("Input.Replace"))
+        });
+        let _subscriptions = vec![
+            cx.subscribe_in(
+                &query_input,
+                window,
+                |this, _, ev: &InputEvent, _, cx| match ev {
+                    InputEvent::Change(_) => this.search(true, cx),
+                    InputEvent::PressEnter => this.find_next(false, cx),
+                    _ => {}
+                },
+            ),
+            cx.subscribe_in(
+                &replace_input,
+                window,
+                |this, _, ev: &InputEvent, window, cx| {
+                    if let InputEvent::PressEnter = ev {
+                        this.replace_next(window, cx);
+                    }
+                },
+            ),
+        ];
+
+        Self {
+            query_input,
+            replace_input,
+            visible: false,
+            replacing: false,
+            options: FindOptions::default(),
+            state: FindState::default(),
+            dirty: false,
+            _subscriptions,
+        }
+    }
+}
+
+/// The completion menu of the `TextInput`, opened by typing or `ctrl-space` if there is a [`CompletionProvider`].
+struct CompletionMenu {
+    list: Entity<List<CompletionListDelegate>>,
+    visible: bool,
+    /// The range of the word before the cursor, that is replaced by the confirmed item.
+    word_range: Range<usize>,
+    /// The task to query the completions, drop it to cancel the query.
+    _query_task: Task<()>,
+}
+
+impl CompletionMenu {
+    fn new(window: &mut Window, cx: &mut Context<TextInput>) -> Self {
+        let input = cx.entity().downgrade();
+        let list = cx.new(|cx| {
+            List::new(CompletionListDelegate::new(input), window, cx)
+                .no_query()
+                .max_h(rems(15.))
+        });
+
+        Self {
+            list,
+            visible: false,
+            word_range: 0..0,
+            _query_task: Task::ready(()),
+        }
+    }
+}
+
 pub struct TextInput {
     pub(super) focus_handle: FocusHandle,
-    pub(super) text: SharedString,
+    pub(super) text: Rope,
+    /// The text as a `SharedString`, built on the first [`TextInput::text`] call after an edit.
+    text_cache: OnceCell<SharedString>,
+    /// The depth of the nested edits, see [`TextInput::start_edits`].
+    edit_depth: usize,
+    /// True if the text is changed in the edits, the `Change` event is emitted at the end of the edits.
+    text_changed: bool,
     multi_line: bool,
+    /// The syntax highlighter of the code editor, the input is a code editor if this is set.
+    pub(super) highlighter: Option<Highlighter>,
+    /// The theme of the syntax highlighting, defaults to the theme by the syntax colors of the current [`Theme`](crate::Theme).
+    pub(super) highlight_theme: Option<Rc<HighlightTheme>>,
     pub(super) history: History<Change>,
     pub(super) blink_cursor: Entity<BlinkCursor>,
     pub(super) prefix: Option<Box<dyn Fn(&mut Window, &mut Context<Self>) -> AnyElement + 'static>>,
@@ -207,8 +346,22 @@ pub struct TextInput {
     /// Range for save the selected word, use to keep word range when drag move.
     pub(super) selected_word_range: Option<Range<usize>>,
     pub(super) selection_reversed: bool,
+    /// The other selections of the multiple cursors (Multi-line only), sorted by the position.
+    ///
+    /// The `selected_range` is the primary selection, that is the last added one.
+    pub(super) other_selections: Vec<Selection>,
+    /// The mouse position (relative to the text origin) where the box selection started.
+    box_select_origin: Option<Point<Pixels>>,
+    /// The find and replace bar (Multi-line only), created when it is opened at the first time.
+    find_bar: Option<FindBar>,
+    completion_provider: Option<Rc<dyn CompletionProvider>>,
+    /// The completion menu, created when it is opened at the first time.
+    completion_menu: Option<CompletionMenu>,
     pub(super) marked_range: Option<Range<usize>>,
-    pub(super) last_layout: Option<SmallVec<[WrappedLine; 1]>>,
+    pub(super) last_layout: Option<LastLayout>,
+    /// The number of the wrapped rows of each line, the lines out of the viewport are not laid out,
+    /// so this is used to estimate their height.
+    pub(super) line_rows: Vec<usize>,
     pub(super) last_cursor_offset: Option<usize>,
     /// The line_height of text layout, this will change will InputElement painted.
     pub(super) last_line_height: Pixels,
@@ -266,14 +419,24 @@ impl TextInput {
 
         Self {
             focus_handle: focus_handle.clone(),
-            text: "".into(),
+            text: Rope::new(),
+            text_cache: OnceCell::new(),
+            edit_depth: 0,
+            text_changed: false,
             multi_line: false,
+            highlighter: None,
+            highlight_theme: None,
             blink_cursor,
             history,
             placeholder: "".into(),
             selected_range: 0..0,
             selected_word_range: None,
             selection_reversed: false,
+            other_selections: Vec::new(),
+            box_select_origin: None,
+            find_bar: None,
+            completion_provider: None,
+            completion_menu: None,
             marked_range: None,
             input_bounds: Bounds::default(),
             selecting: false,
@@ -291,6 +454,7 @@ impl TextInput {
             validate: None,
             rows: 2,
             last_layout: None,
+            line_rows: vec![1],
             last_bounds: None,
             last_selected_range: None,
             last_line_height: px(20.),
@@ -309,15 +473,52 @@ impl TextInput {
         self
     }
 
+    /// Use the text input field as a code editor, this implies `multi_line`.
+    ///
+    /// The text is highlighted with the syntax of the `language` (name or file extension, e.g.: "json", "sql"),
+    /// and there is a line number gutter and the current line highlight.
+    pub fn code_editor(mut self, language: impl Into<SharedString>) -> Self {
+        let language: SharedString = language.into();
+        self.multi_line = true;
+        self.highlighter = Some(Highlighter::new(
+            Some(language.as_ref()),
+            &HighlightTheme::default_light(),
+        ));
+        self
+    }
+
+    /// Set the theme of the syntax highlighting for the code editor.
+    pub fn highlight_theme(mut self, theme: Rc<HighlightTheme>) -> Self {
+        self.highlight_theme = Some(theme);
+        self
+    }
+
+    /// Set the provider of the completion menu, the menu is opened by typing a word or `ctrl-space`.
+    pub fn completion_provider(mut self, provider: Rc<dyn CompletionProvider>) -> Self {
+        self.completion_provider = Some(provider);
+        self
+    }
+
+    /// Set the provider of the completion menu, `None` to disable the completion.
+    pub fn set_completion_provider(
+        &mut self,
+        provider: Option<Rc<dyn CompletionProvider>>,
+        cx: &mut Context<Self>,
+    ) {
+        self.completion_provider = provider;
+        self.close_completions();
+        cx.notify();
+    }
+
     /// Called after moving the cursor. Updates preferred_x_offset if we know where the cursor now is.
     fn update_preferred_x_offset(&mut self, _cx: &mut Context<Self>) {
-        if let (Some(lines), Some(bounds)) = (&self.last_layout, &self.last_bounds) {
+        if let (Some(layout), Some(bounds)) = (&self.last_layout, &self.last_bounds) {
             let offset = self.cursor_offset();
             let line_height = self.last_line_height;
 
             // Find which line and sub-line the cursor is on and its position
             let (_line_index, _sub_line_index, cursor_pos) =
-                self.line_and_position_for_offset(offset, lines, line_height);
+                self.line_and_position_for_offset(offset, layout, line_height);
 
             if let Some(pos) = cursor_pos {
                 // Adjust by scroll offset
@@ -328,16 +529,22 @@ impl TextInput {
     }
 
     /// Find which line and sub-line the given offset belongs to, along with the position within that sub-line.
+    ///
+    /// The line index is the index in the laid out lines, returns no position if the line is not laid out.
     fn line_and_position_for_offset(
         &self,
         offset: usize,
-        lines: &[WrappedLine],
+        layout: &LastLayout,
         line_height: Pixels,
     ) -> (usize, usize, Option<Point<Pixels>>) {
-        let mut prev_lines_offset = 0;
-        let mut y_offset = px(0.);
-        for (line_index, line) in lines.iter().enumerate() {
-            let local_offset = offset.saturating_sub(prev_lines_offset);
+        let mut prev_lines_offset = layout.visible_offset;
+        let mut y_offset = layout.visible_top;
+        for (line_index, line) in layout.lines.iter().enumerate() {
+            if offset < prev_lines_offset {
+                break;
+            }
+
+            let local_offset = offset - prev_lines_offset;
             if let Some(pos) = line.position_for_index(local_offset, line_height) {
                 let sub_line_index = (pos.y.0 / line_height.0) as usize;
                 let adjusted_pos = point(pos.x, pos.y + y_offset);
@@ -357,18 +564,23 @@ impl TextInput {
             return;
         }
 
-        let (Some(lines), Some(bounds)) = (&self.last_layout, &self.last_bounds) else {
+        let (Some(layout), Some(bounds)) = (&self.last_layout, &self.last_bounds) else {
             return;
         };
 
         let offset = self.cursor_offset();
         let line_height = self.last_line_height;
         let (current_line_index, current_sub_line, current_pos) =
-            self.line_and_position_for_offset(offset, lines, line_height);
+            self.line_and_position_for_offset(offset, layout, line_height);
 
         let Some(current_pos) = current_pos else {
+            // The cursor line is out of the viewport.
+            self.move_vertical_by_line(direction, window, cx);
             return;
         };
+        let lines = &layout.lines;
+        let has_lines_above = layout.visible_range.start > 0;
+        let has_lines_below = layout.visible_range.end < self.text.lines_len();
 
         let current_x = self
             .preferred_x_offset
@@ -381,8 +593,12 @@ impl TextInput {
 
         // Handle moving above the first line
         if direction == -1 && new_line_index == 0 && new_sub_line < 0 {
-            // Move cursor to the beginning of the text
-            self.move_to(0, window, cx);
+            if has_lines_above {
+                self.move_vertical_by_line(direction, window, cx);
+            } else {
+                // Move cursor to the beginning of the text
+                self.move_to(0, window, cx);
+            }
             return;
         }
 
@@ -399,6 +615,9 @@ impl TextInput {
                 if new_line_index < lines.len() - 1 {
                     new_line_index += 1;
                     new_sub_line = 0;
+                } else if has_lines_below {
+                    self.move_vertical_by_line(direction, window, cx);
+                    return;
                 } else {
                     new_sub_line = max_sub_line;
                 }
@@ -422,7 +641,7 @@ impl TextInput {
             Err(i) => i,
         };
 
-        let mut prev_lines_offset = 0;
+        let mut prev_lines_offset = layout.visible_offset;
         for (i, l) in lines.iter().enumerate() {
             if i == new_line_index {
                 break;
@@ -436,6 +655,43 @@ impl TextInput {
         cx.notify();
     }
 
+    /// Move the cursor to the same column of the previous or next line (without the wrapped rows),
+    /// used when the target line has not been laid out.
+    fn move_vertical_by_line(
+        &mut self,
+        direction: i32,
+        window: &mut Window,
+        cx: &mut Context<Self>,
+    ) {
+        let offset = self.cursor_offset();
+        let row = self.text.offset_to_line(offset);
+        if direction < 0 && row == 0 {
+            self.move_to(0, window, cx);
+            return;
+        }
+        if direction > 0 && row + 1 >= self.text.lines_len() {
+            self.move_to(self.text.len(), window, cx);
+            return;
+        }
+
+        let line_start = self.text.line_start(row);
+        let column = self.text.slice(line_start..offset).chars().count();
+        let new_row = if direction < 0 { row - 1 } else { row + 1 };
+        let new_line_start = self.text.line_start(new_row);
+        let new_offset = new_line_start
+            + self
+                .text
+                .line(new_row)
+                .chars()
+                .take(column)
+                .map(char::len_utf8)
+                .sum::<usize>();
+
+        self.selected_range = new_offset..new_offset;
+        self.pause_blink_cursor(cx);
+        cx.notify();
+    }
+
     #[inline]
     pub(super) fn is_multi_line(&self) -> bool {
         self.multi_line
@@ -446,6 +702,11 @@ impl TextInput {
         !self.multi_line
     }
 
+    #[inline]
+    pub(super) fn is_code_editor(&self) -> bool {
+        self.highlighter.is_some()
+    }
+
     /// Set the number of rows for the multi-line Textarea.
     ///
     /// This is only used when `multi_line` is set to true.
@@ -470,6 +731,7 @@ impl TextInput {
         self.history.ignore = false;
         // Ensure cursor to start when set text
         self.selected_range = 0..0;
+        self.other_selections.clear();
 
         cx.notify();
     }
@@ -510,7 +772,7 @@ impl TextInput {
         cx: &mut Context<Self>,
     ) {
         let text: SharedString = text.into();
-        let range = 0..self.text.chars().map(|c| c.len_utf16()).sum();
+        let range = 0..self.text.len_utf16();
         self.replace_text_in_range(Some(range), &text, window, cx);
     }
 
@@ -647,8 +909,12 @@ impl TextInput {
     }
 
     /// Return the text of the input field.
+    ///
+    /// The text is copied out of the chunks once after an edit, and shared by the next calls.
     pub fn text(&self) -> SharedString {
-        self.text.clone()
+        self.text_cache
+            .get_or_init(|| self.text.to_string().into())
+            .clone()
     }
 
     pub fn disabled(&self) -> bool {
@@ -661,78 +927,105 @@ impl TextInput {
     }
 
     fn left(&mut self, _: &Left, window: &mut Window, cx: &mut Context<Self>) {
-        self.pause_blink_cursor(cx);
-        if self.selected_range.is_empty() {
-            self.move_to(self.previous_boundary(self.cursor_offset()), window, cx);
-        } else {
-            self.move_to(self.selected_range.start, window, cx)
-        }
+        self.for_each_selection(window, cx, |this, window, cx| {
+            this.pause_blink_cursor(cx);
+            if this.selected_range.is_empty() {
+                this.move_to(this.previous_boundary(this.cursor_offset()), window, cx);
+            } else {
+                this.move_to(this.selected_range.start, window, cx)
+            }
+        });
     }
 
     fn right(&mut self, _: &Right, window: &mut Window, cx: &mut Context<Self>) {
-        self.pause_blink_cursor(cx);
-        if self.selected_range.is_empty() {
-            self.move_to(self.next_boundary(self.selected_range.end), window, cx);
-        } else {
-            self.move_to(self.selected_range.end, window, cx)
-        }
+        self.for_each_selection(window, cx, |this, window, cx| {
+            this.pause_blink_cursor(cx);
+            if this.selected_range.is_empty() {
+                this.move_to(this.next_boundary(this.selected_range.end), window, cx);
+            } else {
+                this.move_to(this.selected_range.end, window, cx)
+            }
+        });
     }
 
     fn up(&mut self, _: &Up, window: &mut Window, cx: &mut Context<Self>) {
+        if self.completion_menu_key(MenuKey::Up, window, cx) {
+            return;
+        }
         if self.is_single_line() {
             return;
         }
-        self.pause_blink_cursor(cx);
-        self.move_vertical(-1, window, cx);
+        self.for_each_selection(window, cx, |this, window, cx| {
+            this.pause_blink_cursor(cx);
+            this.move_vertical(-1, window, cx);
+        });
     }
 
     fn down(&mut self, _: &Down, window: &mut Window, cx: &mut Context<Self>) {
+        if self.completion_menu_key(MenuKey::Down, window, cx) {
+            return;
+        }
         if self.is_single_line() {
             return;
         }
-        self.pause_blink_cursor(cx);
-        self.move_vertical(1, window, cx);
+        self.for_each_selection(window, cx, |this, window, cx| {
+            this.pause_blink_cursor(cx);
+            this.move_vertical(1, window, cx);
+        });
     }
 
     fn select_left(&mut self, _: &SelectLeft, window: &mut Window, cx: &mut Context<Self>) {
-        self.select_to(self.previous_boundary(self.cursor_offset()), window, cx);
+        self.for_each_selection(window, cx, |this, window, cx| {
+            this.select_to(this.previous_boundary(this.cursor_offset()), window, cx);
+        });
     }
 
     fn select_right(&mut self, _: &SelectRight, window: &mut Window, cx: &mut Context<Self>) {
-        self.select_to(self.next_boundary(self.cursor_offset()), window, cx);
+        self.for_each_selection(window, cx, |this, window, cx| {
+            this.select_to(this.next_boundary(this.cursor_offset()), window, cx);
+        });
     }
 
     fn select_up(&mut self, _: &SelectUp, window: &mut Window, cx: &mut Context<Self>) {
         if self.is_single_line() {
             return;
         }
-        let offset = self.start_of_line(window, cx).saturating_sub(1);
-        self.select_to(offset, window, cx);
+        self.for_each_selection(window, cx, |this, window, cx| {
+            let offset = this.start_of_line(window, cx).saturating_sub(1);
+            this.select_to(offset, window, cx);
+        });
     }
 
     fn select_down(&mut self, _: &SelectDown, window: &mut Window, cx: &mut Context<Self>) {
         if self.is_single_line() {
             return;
         }
-        let offset = (self.end_of_line(window, cx) + 1).min(self.text.len());
-        self.select_to(self.next_boundary(offset), window, cx);
+        self.for_each_selection(window, cx, |this, window, cx| {
+            let offset = (this.end_of_line(window, cx) + 1).min(this.text.len());
+            this.select_to(this.next_boundary(offset), window, cx);
+        });
     }
 
     fn select_all(&mut self, _: &SelectAll, window: &mut Window, cx: &mut Context<Self>) {
+        self.other_selections.clear();
         self.move_to(0, window, cx);
         self.select_to(self.text.len(), window, cx)
     }
 
     fn home(&mut self, _: &Home, window: &mut Window, cx: &mut Context<Self>) {
-        self.pause_blink_cursor(cx);
-        let offset = self.start_of_line(window, cx);
-        self.move_to(offset, window, cx);
+        self.for_each_selection(window, cx, |this, window, cx| {
+            this.pause_blink_cursor(cx);
+            let offset = this.start_of_line(window, cx);
+            this.move_to(offset, window, cx);
+        });
     }
 
     fn end(&mut self, _: &End, window: &mut Window, cx: &mut Context<Self>) {
-        self.pause_blink_cursor(cx);
-        let offset = self.end_of_line(window, cx);
-        self.move_to(offset, window, cx);
+        self.for_each_selection(window, cx, |this, window, cx| {
+            this.pause_blink_cursor(cx);
+            let offset = this.end_of_line(window, cx);
+            this.move_to(offset, window, cx);
+        });
     }
 
     fn move_to_start(&mut self, _: &MoveToStart, window: &mut Window, cx: &mut Context<Self>) {
@@ -750,8 +1043,10 @@ impl TextInput {
         window: &mut Window,
         cx: &mut Context<Self>,
     ) {
-        let offset = self.previous_start_of_word();
-        self.move_to(offset, window, cx);
+        self.for_each_selection(window, cx, |this, window, cx| {
+            let offset = this.previous_start_of_word();
+            this.move_to(offset, window, cx);
+        });
     }
 
     fn move_to_next_word(
@@ -760,8 +1055,10 @@ impl TextInput {
         window: &mut Window,
         cx: &mut Context<Self>,
     ) {
-        let offset = self.next_end_of_word();
-        self.move_to(offset, window, cx);
+        self.for_each_selection(window, cx, |this, window, cx| {
+            let offset = this.next_end_of_word();
+            this.move_to(offset, window, cx);
+        });
     }
 
     fn select_to_start(&mut self, _: &SelectToStart, window: &mut Window, cx: &mut Context<Self>) {
@@ -779,8 +1076,10 @@ impl TextInput {
         window: &mut Window,
         cx: &mut Context<Self>,
     ) {
-        let offset = self.start_of_line(window, cx);
-        self.select_to(self.previous_boundary(offset), window, cx);
+        self.for_each_selection(window, cx, |this, window, cx| {
+            let offset = this.start_of_line(window, cx);
+            this.select_to(this.previous_boundary(offset), window, cx);
+        });
     }
 
     fn select_to_end_of_line(
@@ -789,8 +1088,10 @@ impl TextInput {
         window: &mut Window,
         cx: &mut Context<Self>,
     ) {
-        let offset = self.end_of_line(window, cx);
-        self.select_to(self.next_boundary(offset), window, cx);
+        self.for_each_selection(window, cx, |this, window, cx| {
+            let offset = this.end_of_line(window, cx);
+            this.select_to(this.next_boundary(offset), window, cx);
+        });
     }
 
     fn select_to_previous_word(
@@ -799,8 +1100,10 @@ impl TextInput {
         window: &mut Window,
         cx: &mut Context<Self>,
     ) {
-        let offset = self.previous_start_of_word();
-        self.select_to(offset, window, cx);
+        self.for_each_selection(window, cx, |this, window, cx| {
+            let offset = this.previous_start_of_word();
+            this.select_to(offset, window, cx);
+        });
     }
 
     fn select_to_next_word(
@@ -809,96 +1112,85 @@ impl TextInput {
         window: &mut Window,
         cx: &mut Context<Self>,
     ) {
-        let offset = self.next_end_of_word();
-        self.select_to(offset, window, cx);
+        self.for_each_selection(window, cx, |this, window, cx| {
+            let offset = this.next_end_of_word();
+            this.select_to(offset, window, cx);
+        });
     }
 
     /// Return the start offset of the previous word.
+    ///
+    /// Only the current and the previous line are searched, to avoid to scan the whole text.
     fn previous_start_of_word(&mut self) -> usize {
         let offset = self.selected_range.start;
-        let prev_str = &self.text[..offset].to_string();
-        UnicodeSegmentation::split_word_bound_indices(prev_str as &str)
+        let row = self.text.offset_to_line(offset);
+        let start = self.text.line_start(row.saturating_sub(1));
+        let prev_str = self.text.slice(start..offset);
+        UnicodeSegmentation::split_word_bound_indices(prev_str.as_str())
             .filter(|(_, s)| !s.trim_start().is_empty())
             .next_back()
-            .map(|(i, _)| i)
-            .unwrap_or(0)
+            .map(|(i, _)| start + i)
+            .unwrap_or(start)
     }
 
     /// Return the next end offset of the next word.
+    ///
+    /// Only the current and the next line are searched, to avoid to scan the whole text.
     fn next_end_of_word(&mut self) -> usize {
         let offset = self.cursor_offset();
-        let next_str = &self.text[offset..].to_string();
-        UnicodeSegmentation::split_word_bound_indices(next_str as &str)
+        let row = self.text.offset_to_line(offset);
+        let end = self.text.line_end(row + 1);
+        let next_str = self.text.slice(offset..end);
+        UnicodeSegmentation::split_word_bound_indices(next_str.as_str())
             .find(|(_, s)| !s.trim_start().is_empty())
             .map(|(i, s)| offset + i + s.len())
-            .unwrap_or(self.text.len())
+            .unwrap_or(end)
     }
 
     /// Get start of line
-    fn start_of_line(&mut self, window: &mut Window, cx: &mut Context<Self>) -> usize {
+    fn start_of_line(&mut self, _: &mut Window, _: &mut Context<Self>) -> usize {
         if self.is_single_line() {
             return 0;
         }
 
-        let offset = self.previous_boundary(self.cursor_offset());
-        let line = self
-            .text_for_range(self.range_to_utf16(&(0..offset + 1)), &mut None, window, cx)
-            .unwrap_or_default()
-            .rfind('\n')
-            .map(|i| i + 1)
-            .unwrap_or(0);
-        line
+        self.text
+            .line_start(self.text.offset_to_line(self.cursor_offset()))
     }
 
     /// Get end of line
-    fn end_of_line(&mut self, window: &mut Window, cx: &mut Context<Self>) -> usize {
+    fn end_of_line(&mut self, _: &mut Window, _: &mut Context<Self>) -> usize {
         if self.is_single_line() {
             return self.text.len();
         }
 
         let offset = self.next_boundary(self.cursor_offset());
+        let row = self.text.offset_to_line(offset);
         // ignore if offset is "\n"
-        if self
-            .text_for_range(
-                self.range_to_utf16(&(offset - 1..offset)),
-                &mut None,
-                window,
-                cx,
-            )
-            .unwrap_or_default()
-            .eq("\n")
-        {
+        if offset > 0 && self.text.line_start(row) == offset {
             return offset;
         }
 
-        let line = self
-            .text_for_range(
-                self.range_to_utf16(&(offset..self.text.len())),
-                &mut None,
-                window,
-                cx,
-            )
-            .unwrap_or_default()
-            .find('\n')
-            .map(|i| i + offset)
-            .unwrap_or(self.text.len());
-        line
+        self.text.line_end(row)
     }
 
     fn backspace(&mut self, _: &Backspace, window: &mut Window, cx: &mut Context<Self>) {
-        if self.selected_range.is_empty() {
-            self.select_to(self.previous_boundary(self.cursor_offset()), window, cx)
-        }
-        self.replace_text_in_range(None, "", window, cx);
-        self.pause_blink_cursor(cx);
+        self.for_each_selection(window, cx, |this, window, cx| {
+            if this.selected_range.is_empty() {
+                this.select_to(this.previous_boundary(this.cursor_offset()), window, cx)
+            }
+            this.replace_text_in_range(None, "", window, cx);
+            this.pause_blink_cursor(cx);
+        });
     }
 
     fn delete(&mut self, _: &Delete, window: &mut Window, cx: &mut Context<Self>) {
-        if self.selected_range.is_empty() {
-            self.select_to(self.next_boundary(self.cursor_offset()), window, cx)
-        }
-        self.replace_text_in_range(None, "", window, cx);
-        self.pause_blink_cursor(cx);
+        self.for_each_selection(window, cx, |this, window, cx| {
+            if this.selected_range.is_empty() {
+                this.select_to(this.next_boundary(this.cursor_offset()), window, cx)
+            }
+            this.replace_text_in_range(None, "", window, cx);
+            this.pause_blink_cursor(cx);
+        });
     }
 
     fn delete_to_beginning_of_line(
@@ -907,14 +1199,16 @@ impl TextInput {
         window: &mut Window,
         cx: &mut Context<Self>,
     ) {
-        let offset = self.start_of_line(window, cx);
-        self.replace_text_in_range(
-            Some(self.range_to_utf16(&(offset..self.cursor_offset()))),
-            "",
-            window,
-            cx,
-        );
-        self.pause_blink_cursor(cx);
+        self.for_each_selection(window, cx, |this, window, cx| {
+            let offset = this.start_of_line(window, cx);
+            this.replace_text_in_range(
+                Some(this.range_to_utf16(&(offset..this.cursor_offset()))),
+                "",
+                window,
+                cx,
+            );
+            this.pause_blink_cursor(cx);
+        });
     }
 
     fn delete_to_end_of_line(
@@ -923,14 +1217,16 @@ impl TextInput {
         window: &mut Window,
         cx: &mut Context<Self>,
     ) {
-        let offset = self.end_of_line(window, cx);
-        self.replace_text_in_range(
-            Some(self.range_to_utf16(&(self.cursor_offset()..offset))),
-            "",
-            window,
-            cx,
-        );
-        self.pause_blink_cursor(cx);
+        self.for_each_selection(window, cx, |this, window, cx| {
+            let offset = this.end_of_line(window, cx);
+            this.replace_text_in_range(
+                Some(this.range_to_utf16(&(this.cursor_offset()..offset))),
+                "",
+                window,
+                cx,
+            );
+            this.pause_blink_cursor(cx);
+        });
     }
 
     fn delete_previous_word(
@@ -939,14 +1235,16 @@ impl TextInput {
         window: &mut Window,
         cx: &mut Context<Self>,
     ) {
-        let offset = self.previous_start_of_word();
-        self.replace_text_in_range(
-            Some(self.range_to_utf16(&(offset..self.cursor_offset()))),
-            "",
-            window,
-            cx,
-        );
-        self.pause_blink_cursor(cx);
+        self.for_each_selection(window, cx, |this, window, cx| {
+            let offset = this.previous_start_of_word();
+            this.replace_text_in_range(
+                Some(this.range_to_utf16(&(offset..this.cursor_offset()))),
+                "",
+                window,
+                cx,
+            );
+            this.pause_blink_cursor(cx);
+        });
     }
 
     fn delete_next_word(
@@ -955,32 +1253,129 @@ impl TextInput {
         window: &mut Window,
         cx: &mut Context<Self>,
     ) {
-        let offset = self.next_end_of_word();
-        self.replace_text_in_range(
-            Some(self.range_to_utf16(&(self.cursor_offset()..offset))),
-            "",
-            window,
-            cx,
-        );
-        self.pause_blink_cursor(cx);
+        self.for_each_selection(window, cx, |this, window, cx| {
+            let offset = this.next_end_of_word();
+            this.replace_text_in_range(
+                Some(this.range_to_utf16(&(this.cursor_offset()..offset))),
+                "",
+                window,
+                cx,
+            );
+            this.pause_blink_cursor(cx);
+        });
     }
 
     fn enter(&mut self, _: &Enter, window: &mut Window, cx: &mut Context<Self>) {
+        if self.completion_menu_key(MenuKey::Enter, window, cx) {
+            return;
+        }
+
         if self.is_multi_line() {
-            let is_eof = self.selected_range.end == self.text.len();
-            self.replace_text_in_range(None, "\n", window, cx);
+            self.for_each_selection(window, cx, |this, window, cx| {
+                let mut new_text = String::from("\n");
+                if this.is_code_editor() {
+                    new_text.push_str(&this.new_line_indent());
+                }
+                this.replace_text_in_range(None, &new_text, window, cx);
 
-            // Move cursor to the start of the next line
-            let mut new_offset = self.next_boundary(self.cursor_offset()) - 1;
-            if is_eof {
-                new_offset += 1;
-            }
-            self.move_to(new_offset, window, cx);
+                // Move cursor to the start of the next line
+                let new_offset = this.cursor_offset();
+                this.move_to(new_offset, window, cx);
+            });
         }
 
         cx.emit(InputEvent::PressEnter);
     }
 
+    /// Returns the indent of a new line inserted at the selection, that is the indent of the current line,
+    /// and one more level after an open bracket.
+    fn new_line_indent(&self) -> String {
+        let offset = self.selected_range.start;
+        let line_start = self.text.line_start(self.text.offset_to_line(offset));
+        let before = self.text.slice(line_start..offset);
+        let mut indent = before
+            .chars()
+            .take_while(|c| *c == ' ' || *c == '\t')
+            .collect::<String>();
+        if before.trim_end().ends_with(['{', '[', '(']) {
+            indent.push_str(INDENT);
+        }
+        indent
+    }
+
+    fn indent(&mut self, _: &Indent, window: &mut Window, cx: &mut Context<Self>) {
+        self.for_each_selection(window, cx, |this, window, cx| {
+            this.indent_lines(false, window, cx);
+        });
+    }
+
+    fn outdent(&mut self, _: &Outdent, window: &mut Window, cx: &mut Context<Self>) {
+        self.for_each_selection(window, cx, |this, window, cx| {
+            this.indent_lines(true, window, cx);
+        });
+    }
+
+    /// Indent or outdent the lines of the selection.
+    ///
+    /// The indent of a selection in one line is inserted at the selection, like typing a tab.
+    fn indent_lines(&mut self, outdent: bool, window: &mut Window, cx: &mut Context<Self>) {
+        let range = self.selected_range.clone();
+        let start_row = self.text.offset_to_line(range.start);
+        let mut end_row = self.text.offset_to_line(range.end);
+        if !outdent && start_row == end_row {
+            self.replace_text_in_range(None, INDENT, window, cx);
+            return;
+        }
+        // The last line is not selected if the selection ends at the start of it.
+        if end_row > start_row && self.text.line_start(end_row) == range.end {
+            end_row -= 1;
+        }
+
+        let mut start_delta = 0;
+        let mut end_delta = 0;
+        self.start_edits();
+        // Edit from the last line, so the offsets of the lines above are not changed.
+        for row in (start_row..=end_row).rev() {
+            let line_start = self.text.line_start(row);
+            let (edit_range, new_text) = if outdent {
+                let line = self.text.line(row);
+                let len = if line.starts_with('\t') {
+                    1
+                } else {
+                    line.bytes()
+                        .take(INDENT.len())
+                        .take_while(|b| *b == b' ')
+                        .count()
+                };
+                (line_start..line_start + len, "")
+            } else {
+                (line_start..line_start, INDENT)
+            };
+            if edit_range.is_empty() && new_text.is_empty() {
+                continue;
+            }
+
+            let delta = new_text.len() as isize - edit_range.len() as isize;
+            self.replace_text_in_range(
+                Some(self.range_to_utf16(&edit_range)),
+                new_text,
+                window,
+                cx,
+            );
+            end_delta += delta;
+            if row == start_row {
+                // The selection start is not moved before the line start.
+                start_delta = delta.max(line_start as isize - range.start as isize);
+            }
+        }
+        self.end_edits(cx);
+
+        let start = range.start.saturating_add_signed(start_delta);
+        let end = range.end.saturating_add_signed(end_delta).max(start);
+        self.selected_range = start..end;
+        cx.notify();
+    }
+
     fn clean(&mut self, _: &ClickEvent, window: &mut Window, cx: &mut Context<Self>) {
         self.replace_text("", window, cx);
     }
@@ -993,6 +1388,17 @@ impl TextInput {
     ) {
         self.selecting = true;
         let offset = self.index_for_mouse_position(event.position, window, cx);
+
+        // Alt + click to add a cursor, and alt + drag to select a box.
+        if self.is_multi_line() && event.modifiers.alt {
+            self.box_select_origin = self
+                .last_bounds
+                .map(|bounds| event.position - bounds.origin);
+            self.add_cursor(offset, cx);
+            return;
+        }
+
+        self.other_selections.clear();
         // Double click to select word
         if event.button == MouseButton::Left && event.click_count == 2 {
             self.select_word(offset, window, cx);
@@ -1009,6 +1415,7 @@ impl TextInput {
     fn on_mouse_up(&mut self, _: &MouseUpEvent, _window: &mut Window, _cx: &mut Context<Self>) {
         self.selecting = false;
         self.selected_word_range = None;
+        self.box_select_origin = None;
     }
 
     fn on_scroll_wheel(
@@ -1039,23 +1446,45 @@ impl TextInput {
         window.show_character_palette();
     }
 
+    /// Returns the selected text of all selections, joined by a newline.
+    fn selected_text(&self) -> String {
+        let mut ranges = self
+            .other_selections
+            .iter()
+            .map(|selection| selection.range.clone())
+            .chain(Some(self.selected_range.clone()))
+            .filter(|range| !range.is_empty())
+            .collect::<Vec<_>>();
+        ranges.sort_by_key(|range| range.start);
+
+        ranges
+            .into_iter()
+            .map(|range| self.text.slice(range))
+            .collect::<Vec<_>>()
+            .join("\n")
+    }
+
     fn copy(&mut self, _: &Copy, _: &mut Window, cx: &mut Context<Self>) {
-        if self.selected_range.is_empty() {
+        let selected_text = self.selected_text();
+        if selected_text.is_empty() {
             return;
         }
 
-        let selected_text = self.text[self.selected_range.clone()].to_string();
         cx.write_to_clipboard(ClipboardItem::new_string(selected_text));
     }
 
     fn cut(&mut self, _: &Cut, window: &mut Window, cx: &mut Context<Self>) {
-        if self.selected_range.is_empty() {
+        let selected_text = self.selected_text();
+        if selected_text.is_empty() {
             return;
         }
 
-        let selected_text = self.text[self.selected_range.clone()].to_string();
         cx.write_to_clipboard(ClipboardItem::new_string(selected_text));
-        self.replace_text_in_range(None, "", window, cx);
+        self.for_each_selection(window, cx, |this, window, cx| {
+            if !this.selected_range.is_empty() {
+                this.replace_text_in_range(None, "", window, cx);
+            }
+        });
     }
 
     fn paste(&mut self, _: &Paste, window: &mut Window, cx: &mut Context<Self>) {
@@ -1065,54 +1494,786 @@ impl TextInput {
                 new_text = new_text.replace('\n', "");
             }
 
-            self.replace_text_in_range(None, &new_text, window, cx);
+            self.for_each_selection(window, cx, |this, window, cx| {
+                this.replace_text_in_range(None, &new_text, window, cx);
+            });
+            // The completion menu is only opened by typing.
+            self.close_completions();
         }
     }
 
-    fn push_history(
-        &mut self,
-        range: &Range<usize>,
-        new_text: &str,
-        window: &mut Window,
-        cx: &mut Context<Self>,
-    ) {
-        if self.history.ignore {
+    fn escape(&mut self, _: &Escape, window: &mut Window, cx: &mut Context<Self>) {
+        if self.completion_menu_key(MenuKey::Escape, window, cx) {
             return;
         }
+        // Cancel the query of the menu that is not shown yet.
+        self.close_completions();
 
-        let old_text = self
-            .text_for_range(self.range_to_utf16(&range), &mut None, window, cx)
-            .unwrap_or("".to_string());
+        if self.find_bar.as_ref().is_some_and(|bar| bar.visible) {
+            self.close_find(window, cx);
+            return;
+        }
 
-        let new_range = range.start..range.start + new_text.len();
+        if self.other_selections.is_empty() {
+            // Propagate the event to the parent view, for example to the Modal to support ESC to close.
+            cx.propagate();
+            return;
+        }
 
-        self.history.push(Change::new(
-            range.clone(),
-            &old_text,
-            new_range.clone(),
-            new_text,
-        ));
+        self.other_selections.clear();
+        cx.notify();
     }
 
-    fn undo(&mut self, _: &Undo, window: &mut Window, cx: &mut Context<Self>) {
-        self.history.ignore = true;
-        if let Some(changes) = self.history.undo() {
-            for change in changes {
-                let range_utf16 = self.range_to_utf16(&change.new_range);
-                self.replace_text_in_range(Some(range_utf16), &change.old_text, window, cx);
-            }
-        }
-        self.history.ignore = false;
+    fn find(&mut self, _: &Find, window: &mut Window, cx: &mut Context<Self>) {
+        self.open_find(false, window, cx);
     }
 
-    fn redo(&mut self, _: &Redo, window: &mut Window, cx: &mut Context<Self>) {
-        self.history.ignore = true;
-        if let Some(changes) = self.history.redo() {
-            for change in changes {
-                let range_utf16 = self.range_to_utf16(&change.old_range);
-                self.replace_text_in_range(Some(range_utf16), &change.new_text, window, cx);
-            }
-        }
+    fn find_and_replace(
+        &mut self,
+        _: &FindAndReplace,
+        window: &mut Window,
+        cx: &mut Context<Self>,
+    ) {
+        self.open_find(true, window, cx);
+    }
+
+    fn find_prev(&mut self, _: &FindPrev, _: &mut Window, cx: &mut Context<Self>) {
+        self.find_next(true, cx);
+    }
+
+    /// Open the find bar, and show the replace input if `replacing` is true.
+    ///
+    /// The selected text in one line is used as the query.
+    fn open_find(&mut self, replacing: bool, window: &mut Window, cx: &mut Context<Self>) {
+        let selected_text = Some(self.text.slice(self.selected_range.clone()))
+            .filter(|text| !text.is_empty() && !text.contains('\n'));
+        let bar = self
+            .find_bar
+            .get_or_insert_with(|| FindBar::new(window, cx));
+        bar.visible = true;
+        bar.replacing = replacing;
+        bar.query_input.update(cx, |input, cx| {
+            if let Some(text) = selected_text {
+                input.set_text(text, window, cx);
+            }
+            input.select_all(&SelectAll, window, cx);
+            input.focus(window, cx);
+        });
+
+        self.search(true, cx);
+    }
+
+    /// Close the find bar and focus the input.
+    fn close_find(&mut self, window: &mut Window, cx: &mut Context<Self>) {
+        let Some(bar) = self.find_bar.as_mut() else {
+            return;
+        };
+
+        bar.visible = false;
+        bar.state.clear();
+        self.focus_handle.focus(window);
+        cx.notify();
+    }
+
+    /// Find the query of the find bar in the text.
+    ///
+    /// The current match is the first one at or after the selection, and it is selected if `select` is true.
+    fn search(&mut self, select: bool, cx: &mut Context<Self>) {
+        let Some(query) = self
+            .find_bar
+            .as_ref()
+            .filter(|bar| bar.visible)
+            .map(|bar| bar.query_input.read(cx).text())
+        else {
+            return;
+        };
+
+        let text = self.text();
+        let Some(bar) = self.find_bar.as_mut() else {
+            return;
+        };
+        let matches = bar
+            .options
+            .build(&query)
+            .map(|regex| find::find_matches(&text, &regex))
+            .unwrap_or_default();
+        bar.dirty = false;
+        let from = self.selected_range.start;
+        let current = bar.state.set_matches(matches, |range| range.start < from);
+        if let Some(range) = current.filter(|_| select) {
+            self.select_match(range, cx);
+        }
+        cx.notify();
+    }
+
+    /// Search again if the text is changed after the last search.
+    fn search_if_dirty(&mut self, cx: &mut Context<Self>) {
+        if self.find_bar.as_ref().is_some_and(|bar| bar.dirty) {
+            self.search(false, cx);
+        }
+    }
+
+    /// Select the next match, or the previous one if `reverse` is true.
+    fn find_next(&mut self, reverse: bool, cx: &mut Context<Self>) {
+        self.search_if_dirty(cx);
+        let Some(bar) = self.find_bar.as_mut() else {
+            return;
+        };
+
+        if let Some(range) = bar.state.next(reverse) {
+            self.select_match(range, cx);
+        }
+    }
+
+    fn select_match(&mut self, range: Range<usize>, cx: &mut Context<Self>) {
+        self.other_selections.clear();
+        self.selected_range = range;
+        self.selection_reversed = false;
+        self.pause_blink_cursor(cx);
+        cx.notify();
+    }
+
+    fn toggle_find_option(&mut self, toggle: fn(&mut FindOptions), cx: &mut Context<Self>) {
+        if let Some(bar) = self.find_bar.as_mut() {
+            toggle(&mut bar.options);
+            self.search(true, cx);
+        }
+    }
+
+    /// Returns the regex, the options and the replacement of the find bar.
+    fn find_replacement(&self, cx: &App) -> Option<(regex::Regex, FindOptions, SharedString)> {
+        let bar = self.find_bar.as_ref()?;
+        let regex = bar.options.build(&bar.query_input.read(cx).text())?;
+        Some((regex, bar.options, bar.replace_input.read(cx).text()))
+    }
+
+    /// Replace the current match and select the next one.
+    ///
+    /// If the current match is not selected (e.g.: the cursor is moved), select it first.
+    fn replace_next(&mut self, window: &mut Window, cx: &mut Context<Self>) {
+        self.search_if_dirty(cx);
+        let Some((regex, options, replacement)) = self.find_replacement(cx) else {
+            return;
+        };
+        let Some(range) = self.find_bar.as_ref().and_then(|bar| bar.state.current()) else {
+            return;
+        };
+
+        if self.selected_range != range {
+            self.select_match(range, cx);
+            return;
+        }
+
+        let new_text =
+            find::expand_replacement(&self.text(), &range, &regex, &replacement, options);
+        self.replace_text_in_range(Some(self.range_to_utf16(&range)), &new_text, window, cx);
+        self.search(true, cx);
+    }
+
+    /// Replace all the matches.
+    ///
+    /// The text from the first match to the last match is replaced in one edit, so it is one undo step,
+    /// and the text is not copied for each match.
+    fn replace_all(&mut self, window: &mut Window, cx: &mut Context<Self>) {
+        let Some((regex, options, replacement)) = self.find_replacement(cx) else {
+            return;
+        };
+
+        let text = self.text();
+        let matches = find::find_matches(&text, &regex);
+        let (Some(first), Some(last)) = (matches.first(), matches.last()) else {
+            return;
+        };
+
+        let range = first.start..last.end;
+        let mut new_text = String::with_capacity(range.len());
+        let mut offset = range.start;
+        for range in &matches {
+            new_text.push_str(&text[offset..range.start]);
+            new_text.push_str(&find::expand_replacement(
+                &text,
+                range,
+                &regex,
+                &replacement,
+                options,
+            ));
+            offset = range.end;
+        }
+
+        self.replace_text_in_range(Some(self.range_to_utf16(&range)), &new_text, window, cx);
+        self.search(false, cx);
+    }
+
+    /// Returns the matches and the current match of the find bar, if it is visible.
+    pub(super) fn find_matches(&self) -> Option<(&[Range<usize>], Option<Range<usize>>)> {
+        let bar = self.find_bar.as_ref().filter(|bar| bar.visible)?;
+        Some((bar.state.matches(), bar.state.current()))
+    }
+
+    /// Render the find bar over the top-right of the input.
+    fn render_find_bar(&self, cx: &mut Context<Self>) -> Option<impl IntoElement> {
+        let bar = self.find_bar.as_ref().filter(|bar| bar.visible)?;
+        let (index, count) = bar.state.position();
+        let query = bar.query_input.read(cx).text();
+        let status: SharedString = if query.is_empty() {
+            "".into()
+        } else if count == 0 {
+            t!("Input.No Results").into()
+        } else {
+            t!("Input.Match Position", index = index, count = count)
+                .to_string()
+                .into()
+        };
+
+        let options = bar.options;
+        let toggle_button = |id: &'static str,
+                             label: &'static str,
+                             tooltip: SharedString,
+                             selected: bool,
+                             toggle: fn(&mut FindOptions)| {
+            Button::new(id)
+                .ghost()
+                .xsmall()
+                .label(label)
+                .tooltip(tooltip)
+                .selected(selected)
+                .on_click(cx.listener(move |this, _, _, cx| this.toggle_find_option(toggle, cx)))
+        };
+
+        Some(
+            v_flex()
+                .key_context(FIND_CONTEXT)
+                .id("input-find-bar")
+                // The find bar is inside the input, stop the editing and moving actions that are not
+                // handled by the query or replace input, to not change the text of the input.
+                .on_action(|_: &Indent, _, cx| cx.stop_propagation())
+                .on_action(|_: &Outdent, _, cx| cx.stop_propagation())
+                .on_action(|_: &Up, _, cx| cx.stop_propagation())
+                .on_action(|_: &Down, _, cx| cx.stop_propagation())
+                .on_action(|_: &SelectUp, _, cx| cx.stop_propagation())
+                .on_action(|_: &SelectDown, _, cx| cx.stop_propagation())
+                .on_action(|_: &SelectNextOccurrence, _, cx| cx.stop_propagation())
+                .on_action(|_: &ShowCompletions, _, cx| cx.stop_propagation())
+                .occlude()
+                .absolute()
+                .top_1()
+                .right_3()
+                .w(px(380.))
+                .gap_1()
+                .p_1()
+                .cursor_default()
+                .bg(cx.theme().popover)
+                .border_1()
+                .border_color(cx.theme().border)
+                .rounded(cx.theme().radius)
+                .shadow_md()
+                .child(
+                    h_flex()
+                        .gap_1()
+                        .child(div().flex_1().child(bar.query_input.clone()))
+                        .child(toggle_button(
+                            "find-case-sensitive",
+                            "Aa",
+                            t!("Input.Match Case").into(),
+                            options.case_sensitive,
+                            |options| options.case_sensitive = !options.case_sensitive,
+                        ))
+                        .child(toggle_button(
+                            "find-whole-word",
+                            "ab",
+                            t!("Input.Match Whole Word").into(),
+                            options.whole_word,
+                            |options| options.whole_word = !options.whole_word,
+                        ))
+                        .child(toggle_button(
+                            "find-regex",
+                            ".*",
+                            t!("Input.Use Regular Expression").into(),
+                            options.regex,
+                            |options| options.regex = !options.regex,
+                        ))
+                        .child(
+                            div()
+                                .flex_shrink_0()
+                                .text_xs()
+                                .text_color(cx.theme().muted_foreground)
+                                .child(status),
+                        )
+                        .child(
+                            Button::new("find-prev")
+                                .ghost()
+                                .xsmall()
+                                .icon(IconName::ChevronUp)
+                                .disabled(count == 0)
+                                .on_click(cx.listener(|this, _, _, cx| this.find_next(true, cx))),
+                        )
+                        .child(
+                            Button::new("find-next")
+                                .ghost()
+                                .xsmall()
+                                .icon(IconName::ChevronDown)
+                                .disabled(count == 0)
+                                .on_click(cx.listener(|this, _, _, cx| this.find_next(false, cx))),
+                        )
+                        .child(
+                            Button::new("find-close")
+                                .ghost()
+                                .xsmall()
+                                .icon(IconName::Close)
+                                .on_click(
+                                    cx.listener(|this, _, window, cx| this.close_find(window, cx)),
+                                ),
+                        ),
+                )
+                .when(bar.replacing && !self.disabled, |this| {
+                    this.child(
+                        h_flex()
+                            .gap_1()
+                            .child(div().flex_1().child(bar.replace_input.clone()))
+                            .child(
+                                Button::new("replace-next")
+                                    .ghost()
+                                    .xsmall()
+                                    .label(t!("Input.Replace"))
+                                    .disabled(count == 0)
+                                    .on_click(cx.listener(|this, _, window, cx| {
+                                        this.replace_next(window, cx)
+                                    })),
+                            )
+                            .child(
+                                Button::new("replace-all")
+                                    .ghost()
+                                    .xsmall()
+                                    .label(t!("Input.Replace All"))
+                                    .disabled(count == 0)
+                                    .on_click(cx.listener(|this, _, window, cx| {
+                                        this.replace_all(window, cx)
+                                    })),
+                            ),
+                    )
+                }),
+        )
+    }
+
+    fn show_completions(
+        &mut self,
+        _: &ShowCompletions,
+        window: &mut Window,
+        cx: &mut Context<Self>,
+    ) {
+        self.query_completions(window, cx);
+    }
+
+    /// Update the completion menu after typing the `new_text`.
+    ///
+    /// Typing a word char or trigger char queries the completions, deleting updates the visible menu,
+    /// and the other chars close the menu.
+    fn update_completions(&mut self, new_text: &str, window: &mut Window, cx: &mut Context<Self>) {
+        let Some(provider) = self.completion_provider.clone() else {
+            return;
+        };
+
+        let menu_visible = self
+            .completion_menu
+            .as_ref()
+            .is_some_and(|menu| menu.visible);
+        if completion::should_query(
+            new_text,
+            menu_visible,
+            self.completion_word_range(provider.as_ref()).is_empty(),
+            |c| provider.is_word_char(c),
+            |c| provider.is_trigger_char(c),
+        ) {
+            self.query_completions(window, cx);
+        } else {
+            self.close_completions();
+        }
+    }
+
+    /// Returns the range of the word before the cursor.
+    fn completion_word_range(&self, provider: &dyn CompletionProvider) -> Range<usize> {
+        completion::word_range(&self.text, self.cursor_offset(), |c| {
+            provider.is_word_char(c)
+        })
+    }
+
+    /// Query the completions of the word before the cursor, the menu is shown when the items are ready.
+    fn query_completions(&mut self, window: &mut Window, cx: &mut Context<Self>) {
+        let Some(provider) = self.completion_provider.clone() else {
+            return;
+        };
+        if !self.selected_range.is_empty() || !self.other_selections.is_empty() {
+            self.close_completions();
+            return;
+        }
+
+        let word_range = self.completion_word_range(provider.as_ref());
+        let query = self.text.slice(word_range.clone());
+        let task = provider.completions(self, &query, word_range.end, window, cx);
+        let menu = self
+            .completion_menu
+            .get_or_insert_with(|| CompletionMenu::new(window, cx));
+        menu.word_range = word_range;
+        menu._query_task = cx.spawn_in(window, |this, mut cx| async move {
+            // Close the menu if failed to query the completions.
+            let items = task.await.unwrap_or_default();
+            _ = this.update_in(&mut cx, |this, window, cx| {
+                this.set_completions(items, window, cx);
+            });
+        });
+    }
+
+    /// Show the queried items in the completion menu, and select the first one.
+    fn set_completions(
+        &mut self,
+        items: Vec<CompletionItem>,
+        window: &mut Window,
+        cx: &mut Context<Self>,
+    ) {
+        // The cursor is moved out of the word before the items are ready.
+        if !self.is_completion_word_valid() {
+            self.close_completions();
+            cx.notify();
+            return;
+        }
+
+        let Some(menu) = self.completion_menu.as_mut() else {
+            return;
+        };
+        menu.visible = !items.is_empty();
+        let selected_index = menu.visible.then_some(0);
+        menu.list.update(cx, |list, cx| {
+            list.delegate_mut().items = items;
+            list.set_selected_index(selected_index, window, cx);
+            cx.notify();
+        });
+        cx.notify();
+    }
+
+    /// Returns true if the cursor is still at the end of the word of the completion menu.
+    fn is_completion_word_valid(&self) -> bool {
+        self.completion_menu.as_ref().is_some_and(|menu| {
+            self.selected_range.is_empty()
+                && self.other_selections.is_empty()
+                && self.cursor_offset() == menu.word_range.end
+        })
+    }
+
+    /// Close the completion menu and cancel the query, returns true if the menu was visible.
+    fn close_completions(&mut self) -> bool {
+        let Some(menu) = self.completion_menu.as_mut() else {
+            return false;
+        };
+        menu._query_task = Task::ready(());
+        std::mem::replace(&mut menu.visible, false)
+    }
+
+    /// Handle the `key` by the completion menu, see [`completion::menu_key_action`].
+    ///
+    /// Returns false if the menu is not visible.
+    fn completion_menu_key(
+        &mut self,
+        key: MenuKey,
+        window: &mut Window,
+        cx: &mut Context<Self>,
+    ) -> bool {
+        let Some(menu) = self.completion_menu.as_ref() else {
+            return false;
+        };
+        let list = menu.list.clone();
+        let (selected_index, items_count) = {
+            let list = list.read(cx);
+            (list.selected_index(), list.delegate().items.len())
+        };
+        let Some(action) =
+            completion::menu_key_action(key, menu.visible, selected_index, items_count)
+        else {
+            return false;
+        };
+
+        match action {
+            MenuAction::Select(ix) => list.update(cx, |list, cx| {
+                list.set_selected_index(Some(ix), window, cx);
+                cx.notify();
+            }),
+            MenuAction::Confirm(ix) => {
+                let item = list.read(cx).delegate().items[ix].clone();
+                self.accept_completion(item, window, cx);
+            }
+            MenuAction::Close => {
+                self.close_completions();
+                cx.notify();
+            }
+            MenuAction::None => {}
+        }
+        true
+    }
+
+    /// Replace the word of the completion menu with the `item`, this is undone as one step.
+    pub(super) fn accept_completion(
+        &mut self,
+        item: CompletionItem,
+        window: &mut Window,
+        cx: &mut Context<Self>,
+    ) {
+        let Some(menu) = self.completion_menu.as_ref() else {
+            return;
+        };
+        let change = completion::completion_change(&self.text, &menu.word_range, &item);
+        self.close_completions();
+
+        self.start_edits();
+        self.replace_text_in_range(
+            Some(self.range_to_utf16(&change.old_range)),
+            &change.new_text,
+            window,
+            cx,
+        );
+        self.end_edits(cx);
+    }
+
+    /// Render the completion menu under the word before the cursor.
+    fn render_completion_menu(&self, cx: &mut Context<Self>) -> Option<impl IntoElement> {
+        let menu = self.completion_menu.as_ref().filter(|menu| menu.visible)?;
+        let layout = self.last_layout.as_ref()?;
+        let bounds = self.last_bounds?;
+        let line_height = self.last_line_height;
+        let (_, _, word_origin) =
+            self.line_and_position_for_offset(menu.word_range.start, layout, line_height);
+        let position = bounds.origin + word_origin? + point(px(0.), line_height);
+
+        Some(
+            deferred(
+                anchored()
+                    .position(position)
+                    .snap_to_window_with_margin(px(8.))
+                    .child(
+                        div()
+                            .occlude()
+                            .w(rems(16.))
+                            .mt_1()
+                            .overflow_hidden()
+                            .bg(cx.theme().background)
+                            .border_1()
+                            .border_color(cx.theme().border)
+                            .rounded(cx.theme().radius)
+                            .shadow_md()
+                            .on_mouse_down_out(cx.listener(|this, _, _, cx| {
+                                this.close_completions();
+                                cx.notify();
+                            }))
+                            .child(menu.list.clone()),
+                    ),
+            )
+            .with_priority(1),
+        )
+    }
+
+    /// Add a cursor at the given offset as the primary selection, or remove the cursor if it is already there.
+    fn add_cursor(&mut self, offset: usize, cx: &mut Context<Self>) {
+        let cursor = Selection::cursor(offset);
+        if let Some(ix) = self
+            .other_selections
+            .iter()
+            .position(|selection| *selection == cursor)
+        {
+            self.other_selections.remove(ix);
+        } else if self.selected_range != (offset..offset) {
+            let primary = Selection::new(self.selected_range.clone(), self.selection_reversed);
+            let mut selections = self.other_selections.clone();
+            selections.extend([primary, cursor]);
+            self.set_selections(selections);
+        }
+
+        self.pause_blink_cursor(cx);
+        cx.notify();
+    }
+
+    /// Select the word at the cursor if the selection is empty,
+    /// otherwise add a selection at the next occurrence of the selected text.
+    fn select_next_occurrence(
+        &mut self,
+        _: &SelectNextOccurrence,
+        window: &mut Window,
+        cx: &mut Context<Self>,
+    ) {
+        if self.selected_range.is_empty() {
+            self.select_word(self.cursor_offset(), window, cx);
+            self.selected_word_range = None;
+            return;
+        }
+
+        let primary = Selection::new(self.selected_range.clone(), self.selection_reversed);
+        let query = self.text.slice(self.selected_range.clone());
+        let text = self.text();
+        let mut selections = self.other_selections.clone();
+        selections.push(primary);
+        let Some(range) =
+            selection::next_occurrence(&text, &query, self.selected_range.end, &selections)
+        else {
+            return;
+        };
+
+        selections.push(Selection::new(range, false));
+        self.set_selections(selections);
+        self.pause_blink_cursor(cx);
+        cx.notify();
+    }
+
+    /// Select a box (column selection) from the `box_select_origin` to the given position,
+    /// each line in the box has a selection.
+    fn box_select(&mut self, position: Point<Pixels>, cx: &mut Context<Self>) {
+        let (Some(origin), Some(bounds), Some(layout)) = (
+            self.box_select_origin,
+            self.last_bounds.as_ref(),
+            self.last_layout.as_ref(),
+        ) else {
+            return;
+        };
+
+        let line_height = self.last_line_height;
+        let position = position - bounds.origin;
+        // Ignore the small move of the alt + click.
+        if (position.x - origin.x).abs() < px(4.) && (position.y - origin.y).abs() < px(4.) {
+            return;
+        }
+        let (top, bottom) = (origin.y.min(position.y), origin.y.max(position.y));
+
+        let mut selections = vec![];
+        let mut line_offset = layout.visible_offset;
+        let mut y_offset = layout.visible_top;
+        for line in layout.lines.iter() {
+            let height = line.size(line_height).height;
+            if y_offset + height > top && y_offset <= bottom {
+                let layout = &line.unwrapped_layout;
+                let start = line_offset + layout.closest_index_for_x(origin.x);
+                let end = line_offset + layout.closest_index_for_x(position.x);
+                selections.push(Selection::new(start.min(end)..start.max(end), end < start));
+            }
+
+            y_offset += height;
+            line_offset += line.len() + 1;
+        }
+
+        // The line at the mouse position is the primary selection.
+        if position.y < origin.y {
+            selections.reverse();
+        }
+        if !selections.is_empty() {
+            self.set_selections(selections);
+            cx.notify();
+        }
+    }
+
+    /// Set the selections of the multiple cursors, the last one is the primary selection.
+    fn set_selections(&mut self, selections: impl IntoIterator<Item = Selection>) {
+        let mut selections = selections.into_iter().collect::<Vec<_>>();
+        let Some(primary) = selections.len().checked_sub(1) else {
+            return;
+        };
+
+        let primary = selection::merge_selections(&mut selections, primary);
+        let primary = selections.remove(primary);
+        self.selected_range = primary.range;
+        self.selection_reversed = primary.reversed;
+        self.other_selections = selections;
+    }
+
+    /// Run `f` for each selection of the multiple cursors, with the selection as the `selected_range`,
+    /// so the actions that work with a single cursor work with the multiple cursors.
+    ///
+    /// The selections are visited from the last to the first, so an edit only moves the visited selections,
+    /// and all the edits are grouped into one undo step.
+    fn for_each_selection(
+        &mut self,
+        window: &mut Window,
+        cx: &mut Context<Self>,
+        mut f: impl FnMut(&mut Self, &mut Window, &mut Context<Self>),
+    ) {
+        if self.other_selections.is_empty() {
+            f(self, window, cx);
+            return;
+        }
+
+        let mut selections = std::mem::take(&mut self.other_selections);
+        selections.push(Selection::new(
+            self.selected_range.clone(),
+            self.selection_reversed,
+        ));
+        let mut order = (0..selections.len()).collect::<Vec<_>>();
+        order.sort_by_key(|&ix| std::cmp::Reverse(selections[ix].range.start));
+
+        self.start_edits();
+        for (i, &ix) in order.iter().enumerate() {
+            self.selected_range = selections[ix].range.clone();
+            self.selection_reversed = selections[ix].reversed;
+            // The preferred x offset is of the primary cursor.
+            self.preferred_x_offset = None;
+
+            let len = self.text.len();
+            f(self, window, cx);
+            let delta = self.text.len() as isize - len as isize;
+
+            selections[ix] = Selection::new(self.selected_range.clone(), self.selection_reversed);
+            if delta != 0 {
+                for &visited in &order[..i] {
+                    selections[visited].shift(delta);
+                }
+            }
+        }
+        self.end_edits(cx);
+
+        self.set_selections(selections);
+        cx.notify();
+    }
+
+    fn push_history(
+        &mut self,
+        range: &Range<usize>,
+        new_text: &str,
+        window: &mut Window,
+        cx: &mut Context<Self>,
+    ) {
+        if self.history.ignore {
+            return;
+        }
+
+        let old_text = self
+            .text_for_range(self.range_to_utf16(&range), &mut None, window, cx)
+            .unwrap_or("".to_string());
+
+        let new_range = range.start..range.start + new_text.len();
+
+        self.history.push(Change::new(
+            range.clone(),
+            &old_text,
+            new_range.clone(),
+            new_text,
+        ));
+    }
+
+    fn undo(&mut self, _: &Undo, window: &mut Window, cx: &mut Context<Self>) {
+        self.other_selections.clear();
+        self.history.ignore = true;
+        if let Some(changes) = self.history.undo() {
+            for change in changes {
+                let range_utf16 = self.range_to_utf16(&change.new_range);
+                self.replace_text_in_range(Some(range_utf16), &change.old_text, window, cx);
+            }
+        }
+        self.history.ignore = false;
+    }
+
+    fn redo(&mut self, _: &Redo, window: &mut Window, cx: &mut Context<Self>) {
+        self.other_selections.clear();
+        self.history.ignore = true;
+        if let Some(changes) = self.history.redo() {
+            // Redo the changes in the order they were made.
+            for change in changes.into_iter().rev() {
+                let range_utf16 = self.range_to_utf16(&change.old_range);
+                self.replace_text_in_range(Some(range_utf16), &change.new_text, window, cx);
+            }
+        }
         self.history.ignore = false;
     }
 
@@ -1147,7 +2308,7 @@ impl TextInput {
             return 0;
         }
 
-        let (Some(bounds), Some(lines)) = (self.last_bounds.as_ref(), self.last_layout.as_ref())
+        let (Some(bounds), Some(layout)) = (self.last_bounds.as_ref(), self.last_layout.as_ref())
         else {
             return 0;
         };
@@ -1166,10 +2327,10 @@ impl TextInput {
         // - included the scroll offset.
         let inner_position = position - bounds.origin;
 
-        let mut index = 0;
-        let mut y_offset = px(0.);
+        let mut index = layout.visible_offset;
+        let mut y_offset = layout.visible_top;
 
-        for line in lines.iter() {
+        for line in layout.lines.iter() {
             let line_origin = self.line_origin_with_y_offset(&mut y_offset, &line, line_height);
             let pos = inner_position - line_origin;
             let closest_index = line.unwrapped_layout.closest_index_for_x(pos.x);
@@ -1269,40 +2430,32 @@ impl TextInput {
     /// Select the word at the given offset.
     ///
     /// The offset is the UTF-8 offset.
-    fn select_word(&mut self, offset: usize, window: &mut Window, cx: &mut Context<Self>) {
+    fn select_word(&mut self, offset: usize, _: &mut Window, cx: &mut Context<Self>) {
         fn is_word(c: char) -> bool {
             c.is_alphanumeric() || matches!(c, '_')
         }
 
-        let mut start = self.offset_to_utf16(offset);
-        let mut end = start;
-        let prev_text = self
-            .text_for_range(0..start, &mut None, window, cx)
-            .unwrap_or_default();
-        let next_text = self
-            .text_for_range(end..self.text.len(), &mut None, window, cx)
-            .unwrap_or_default();
-
-        let prev_chars = prev_text.chars().rev().peekable();
-        let next_chars = next_text.chars().peekable();
-
-        for (_, c) in prev_chars.enumerate() {
-            if !is_word(c) {
-                break;
-            }
-
-            start -= c.len_utf16();
-        }
-
-        for (_, c) in next_chars.enumerate() {
-            if !is_word(c) {
-                break;
-            }
-
-            end += c.len_utf16();
-        }
-
-        self.selected_range = self.range_from_utf16(&(start..end));
+        // The word never contains the `\n`, so only the line of the offset is needed.
+        let row = self.text.offset_to_line(offset);
+        let line_start = self.text.line_start(row);
+        let line = self.text.line(row);
+        let (prev_text, next_text) = line.split_at(offset - line_start);
+
+        let start = offset
+            - prev_text
+                .chars()
+                .rev()
+                .take_while(|c| is_word(*c))
+                .map(char::len_utf8)
+                .sum::<usize>();
+        let end = offset
+            + next_text
+                .chars()
+                .take_while(|c| is_word(*c))
+                .map(char::len_utf8)
+                .sum::<usize>();
+
+        self.selected_range = start..end;
         self.selected_word_range = Some(self.selected_range.clone());
         cx.notify()
     }
@@ -1314,33 +2467,11 @@ impl TextInput {
     }
 
     fn offset_from_utf16(&self, offset: usize) -> usize {
-        let mut utf8_offset = 0;
-        let mut utf16_count = 0;
-
-        for ch in self.text.chars() {
-            if utf16_count >= offset {
-                break;
-            }
-            utf16_count += ch.len_utf16();
-            utf8_offset += ch.len_utf8();
-        }
-
-        utf8_offset
+        self.text.offset_from_utf16(offset)
     }
 
     fn offset_to_utf16(&self, offset: usize) -> usize {
-        let mut utf16_offset = 0;
-        let mut utf8_count = 0;
-
-        for ch in self.text.chars() {
-            if utf8_count >= offset {
-                break;
-            }
-            utf8_count += ch.len_utf8();
-            utf16_offset += ch.len_utf16();
-        }
-
-        utf16_offset
+        self.text.offset_to_utf16(offset)
     }
 
     fn range_to_utf16(&self, range: &Range<usize>) -> Range<usize> {
@@ -1352,18 +2483,28 @@ impl TextInput {
     }
 
     fn previous_boundary(&self, offset: usize) -> usize {
+        if offset == 0 {
+            return 0;
+        }
+
+        // The grapheme never crosses the line, except the `\r\n`, so search from the line of the previous char.
+        let start = self.text.line_start(self.text.offset_to_line(offset - 1));
         self.text
+            .slice(start..offset)
             .grapheme_indices(true)
-            .rev()
-            .find_map(|(idx, _)| (idx < offset).then_some(idx))
+            .next_back()
+            .map(|(idx, _)| start + idx)
             .unwrap_or(0)
     }
 
     fn next_boundary(&self, offset: usize) -> usize {
+        // Include the `\n` of the line, to move to the next line at the end of the line.
+        let end = (self.text.line_end(self.text.offset_to_line(offset)) + 1).min(self.text.len());
         self.text
+            .slice(offset..end)
             .grapheme_indices(true)
-            .find_map(|(idx, _)| (idx > offset).then_some(idx))
-            .unwrap_or(self.text.len())
+            .find_map(|(idx, _)| (idx > 0).then_some(offset + idx))
+            .unwrap_or(end)
     }
 
     /// Returns the true to let InputElement to render cursor, when Input is focused and current BlinkCursor is visible.
@@ -1380,6 +2521,7 @@ impl TextInput {
 
     fn on_blur(&mut self, window: &mut Window, cx: &mut Context<Self>) {
         self.unselect(window, cx);
+        self.close_completions();
         self.blink_cursor.update(cx, |cursor, cx| {
             cursor.stop(cx);
         });
@@ -1423,10 +2565,71 @@ impl TextInput {
             return;
         }
 
+        if self.box_select_origin.is_some() {
+            self.box_select(event.position, cx);
+            return;
+        }
+
         let offset = self.index_for_mouse_position(event.position, window, cx);
         self.select_to(offset, window, cx);
     }
 
+    /// Returns true if the text is valid after replacing the range with the `new_text`.
+    ///
+    /// The full text is only built when there is a `validate` or `pattern`.
+    fn is_valid_replacement(&self, range: &Range<usize>, new_text: &str) -> bool {
+        if self.validate.is_none() && self.pattern.is_none() {
+            return true;
+        }
+
+        let pending_text = self.text.slice(0..range.start)
+            + new_text
+            + &self.text.slice(range.end..self.text.len());
+        self.is_valid_input(&pending_text)
+    }
+
+    /// Replace the text in the range, and update the `line_rows` of the changed lines.
+    fn replace_text_unchecked(&mut self, range: Range<usize>, new_text: &str) {
+        let start_row = self.text.offset_to_line(range.start);
+        let end_row = self.text.offset_to_line(range.end);
+        let new_rows = new_text.matches('\n').count() + 1;
+        self.line_rows
+            .splice(start_row..=end_row, std::iter::repeat(1).take(new_rows));
+        if let Some(highlighter) = self.highlighter.as_mut() {
+            highlighter.edit(start_row, end_row - start_row, new_rows - 1);
+        }
+        if let Some(bar) = self.find_bar.as_mut().filter(|bar| bar.visible) {
+            bar.dirty = true;
+        }
+        self.text.replace(range, new_text);
+        self.text_cache.take();
+    }
+
+    /// Start a group of edits, the edits are one undo step, and the `Change` event is emitted once
+    /// by [`Self::end_edits`] instead of once per edit, e.g.: typing with the multiple cursors.
+    fn start_edits(&mut self) {
+        self.history.start_grouping();
+        self.edit_depth += 1;
+    }
+
+    /// End the group of edits started by [`Self::start_edits`].
+    fn end_edits(&mut self, cx: &mut Context<Self>) {
+        self.history.end_grouping();
+        self.edit_depth = self.edit_depth.saturating_sub(1);
+        if self.edit_depth == 0 && std::mem::take(&mut self.text_changed) {
+            cx.emit(InputEvent::Change(self.text()));
+        }
+    }
+
+    /// Emit the `Change` event, or defer it to the end of the edits.
+    fn emit_change(&mut self, cx: &mut Context<Self>) {
+        if self.edit_depth > 0 {
+            self.text_changed = true;
+        } else {
+            cx.emit(InputEvent::Change(self.text()));
+        }
+    }
+
     fn is_valid_input(&self, new_text: &str) -> bool {
         if new_text.is_empty() {
             return true;
@@ -1462,7 +2665,7 @@ impl EntityInputHandler for TextInput {
     ) -> Option<String> {
         let range = self.range_from_utf16(&range_utf16);
         adjusted_range.replace(self.range_to_utf16(&range));
-        Some(self.text[range].to_string())
+        Some(self.text.slice(range))
     }
 
     fn selected_text_range(
@@ -1502,24 +2705,36 @@ impl EntityInputHandler for TextInput {
             return;
         }
 
+        // Typing with the multiple cursors.
+        if range_utf16.is_none() && self.marked_range.is_none() && !self.other_selections.is_empty()
+        {
+            self.for_each_selection(window, cx, |this, window, cx| {
+                this.replace_text_in_range(None, new_text, window, cx);
+            });
+            return;
+        }
+        // The other edits (e.g.: undo) only apply to the primary selection.
+        self.other_selections.clear();
+
         let range = range_utf16
             .as_ref()
             .map(|range_utf16| self.range_from_utf16(range_utf16))
             .or(self.marked_range.clone())
             .unwrap_or(self.selected_range.clone());
 
-        let pending_text: SharedString =
-            (self.text[0..range.start].to_owned() + new_text + &self.text[range.end..]).into();
-        if !self.is_valid_input(&pending_text) {
+        if !self.is_valid_replacement(&range, new_text) {
             return;
         }
 
         self.push_history(&range, new_text, window, cx);
-        self.text = pending_text;
+        self.replace_text_unchecked(range.clone(), new_text);
         self.selected_range = range.start + new_text.len()..range.start + new_text.len();
         self.marked_range.take();
         self.update_preferred_x_offset(cx);
-        cx.emit(InputEvent::Change(self.text.clone()));
+        self.emit_change(cx);
+        if range_utf16.is_none() {
+            self.update_completions(new_text, window, cx);
+        }
         cx.notify();
     }
 
@@ -1535,26 +2750,26 @@ impl EntityInputHandler for TextInput {
             return;
         }
 
+        // The IME composition only applies to the primary selection.
+        self.other_selections.clear();
         let range = range_utf16
             .as_ref()
             .map(|range_utf16| self.range_from_utf16(range_utf16))
             .or(self.marked_range.clone())
             .unwrap_or(self.selected_range.clone());
-        let pending_text: SharedString =
-            (self.text[0..range.start].to_owned() + new_text + &self.text[range.end..]).into();
-        if !self.is_valid_input(&pending_text) {
+        if !self.is_valid_replacement(&range, new_text) {
             return;
         }
 
         self.push_history(&range, new_text, window, cx);
-        self.text = pending_text;
+        self.replace_text_unchecked(range.clone(), new_text);
         self.marked_range = Some(range.start..range.start + new_text.len());
         self.selected_range = new_selected_range_utf16
             .as_ref()
             .map(|range_utf16| self.range_from_utf16(range_utf16))
             .map(|new_range| new_range.start + range.start..new_range.end + range.end)
             .unwrap_or_else(|| range.start + new_text.len()..range.start + new_text.len());
-        cx.emit(InputEvent::Change(self.text.clone()));
+        self.emit_change(cx);
         cx.notify();
     }
 
@@ -1568,15 +2783,15 @@ impl EntityInputHandler for TextInput {
         _cx: &mut Context<Self>,
     ) -> Option<Bounds<Pixels>> {
         let line_height = self.last_line_height;
-        let lines = self.last_layout.as_ref()?;
+        let layout = self.last_layout.as_ref()?;
         let range = self.range_from_utf16(&range_utf16);
 
         let mut start_origin = None;
         let mut end_origin = None;
-        let mut y_offset = px(0.);
-        let mut index_offset = 0;
+        let mut y_offset = layout.visible_top;
+        let mut index_offset = layout.visible_offset;
 
-        for line in lines.iter() {
+        for line in layout.lines.iter() {
             if let Some(p) =
                 line.position_for_index(range.start.saturating_sub(index_offset), line_height)
             {
@@ -1614,9 +2829,9 @@ impl EntityInputHandler for TextInput {
     ) -> Option<usize> {
         let line_height = self.last_line_height;
         let line_point = self.last_bounds?.localize(&point)?;
-        let lines = self.last_layout.as_ref()?;
+        let layout = self.last_layout.as_ref()?;
 
-        for line in lines.iter() {
+        for line in layout.lines.iter() {
             if let Ok(utf8_index) = line.index_for_position(line_point, line_height) {
                 return Some(self.offset_to_utf16(utf8_index));
             }
@@ -1647,6 +2862,14 @@ impl Render for TextInput {
 
         let prefix = self.prefix.as_ref().map(|build| build(window, cx));
         let suffix = self.suffix.as_ref().map(|build| build(window, cx));
+        // Update the matches of the find bar after the text is changed.
+        self.search_if_dirty(cx);
+        let find_bar = self.render_find_bar(cx);
+        // Close the completion menu if the cursor is moved out of the word.
+        if !self.is_completion_word_valid() {
+            self.close_completions();
+        }
+        let completion_menu = self.render_completion_menu(cx);
 
         div()
             .flex()
@@ -1661,16 +2884,34 @@ impl Render for TextInput {
                     .on_action(cx.listener(Self::delete_previous_word))
                     .on_action(cx.listener(Self::delete_next_word))
                     .on_action(cx.listener(Self::enter))
+                    .when(self.is_code_editor(), |this| {
+                        this.on_action(cx.listener(Self::indent))
+                            .on_action(cx.listener(Self::outdent))
+                    })
+                    .when(self.completion_provider.is_some(), |this| {
+                        this.on_action(cx.listener(Self::show_completions))
+                    })
             })
             .on_action(cx.listener(Self::left))
             .on_action(cx.listener(Self::right))
             .on_action(cx.listener(Self::select_left))
             .on_action(cx.listener(Self::select_right))
+            // The single line input handles the keys only to select and close the completion menu.
+            .when(self.is_single_line() && completion_menu.is_some(), |this| {
+                this.on_action(cx.listener(Self::up))
+                    .on_action(cx.listener(Self::down))
+                    .on_action(cx.listener(Self::escape))
+            })
             .when(self.multi_line, |this| {
                 this.on_action(cx.listener(Self::up))
                     .on_action(cx.listener(Self::down))
                     .on_action(cx.listener(Self::select_up))
                     .on_action(cx.listener(Self::select_down))
+                    .on_action(cx.listener(Self::select_next_occurrence))
+                    .on_action(cx.listener(Self::escape))
+                    .on_action(cx.listener(Self::find))
+                    .on_action(cx.listener(Self::find_and_replace))
+                    .on_action(cx.listener(Self::find_prev))
             })
             .on_action(cx.listener(Self::select_all))
             .on_action(cx.listener(Self::select_to_start_of_line))
@@ -1765,5 +3006,64 @@ impl Render for TextInput {
                     this
                 }
             })
+            .when_some(find_bar, |this, find_bar| this.relative().child(find_bar))
+            .children(completion_menu)
+    }
+}
+
+#[cfg(test)]
+mod tests {
+    use gpui::TestAppContext;
+
+    use super::*;
+
+    /// Counts the queries of the completions.
+    struct TestCompletionProvider(Rc<Cell<usize>>);
+
+    impl CompletionProvider for TestCompletionProvider {
+        fn completions(
+            &self,
+            _: &TextInput,
+            _: &str,
+            _: usize,
+            _: &mut Window,
+            _: &mut App,
+        ) -> Task<anyhow::Result<Vec<CompletionItem>>> {
+            self.0.set(self.0.get() + 1);
+            Task::ready(Ok(vec![CompletionItem::new("main")]))
+        }
+    }
+
+    #[gpui::test]
+    fn test_find_bar_stops_editor_actions(cx: &mut TestAppContext) {
+        cx.update(crate::init);
+        let text = "fn main() {\n    let a = 1;\n}";
+        let queries = Rc::new(Cell::new(0));
+        let (input, cx) = cx.add_window_view(|window, cx| {
+            let mut input = TextInput::new(window, cx)
+                .code_editor("rust")
+                .completion_provider(Rc::new(TestCompletionProvider(queries.clone())));
+            input.set_text(text, window, cx);
+            input
+        });
+        cx.update(|window, cx| {
+            input.update(cx, |input, cx| {
+                input.selected_range = 12..12;
+                input.open_find(false, window, cx);
+            })
+        });
+        cx.run_until_parked();
+
+        // The keys in the query input do not change the text or move the cursor of the editor.
+        cx.simulate_keystrokes("tab shift-tab up down shift-up shift-down ctrl-space");
+        cx.run_until_parked();
+        cx.update(|_, cx| {
+            let input = input.read(cx);
+            assert_eq!(input.text().as_ref(), text);
+            assert_eq!(input.selected_range, 12..12);
+            // The completion menu of the editor is not opened.
+            assert!(input.completion_menu.is_none());
+        });
+        assert_eq!(queries.get(), 0);
     }
 }
@@ -1,12 +1,17 @@
 mod blink_cursor;
 mod change;
 mod clear_button;
+mod completion;
 mod element;
+mod find;
 mod input;
 mod number_input;
 mod otp_input;
+mod rope;
+mod selection;
 
 pub(crate) use clear_button::*;
+pub use completion::{CompletionItem, CompletionProvider};
 pub use input::*;
 pub use number_input::{NumberInput, NumberInputEvent, StepAction};
 pub use otp_input::*;
@@ -0,0 +1,431 @@
+use std::{fmt, ops::Range};
+
+/// The max size in bytes of a chunk, the chunks are split at the char boundary,
+/// so a chunk may be a little larger.
+const CHUNK_SIZE: usize = 1024;
+/// The min size in bytes of a chunk, except the only chunk of a short text,
+/// a chunk may be a little smaller for the same reason.
+const MIN_CHUNK_SIZE: usize = CHUNK_SIZE / 2;
+
+/// The summary of a text, used to find the chunk by the offset, UTF-16 offset or line.
+#[derive(Debug, Clone, Copy, Default, PartialEq, Eq)]
+struct Summary {
+    /// The length in UTF-8 bytes.
+    len: usize,
+    /// The length in UTF-16 code units.
+    len_utf16: usize,
+    /// The number of `\n`.
+    newlines: usize,
+}
+
+impl Summary {
+    fn new(text: &str) -> Self {
+        Self {
+            len: text.len(),
+            len_utf16: text.chars().map(char::len_utf16).sum(),
+            newlines: text.bytes().filter(|b| *b == b'\n').count(),
+        }
+    }
+
+    fn add(self, other: Self) -> Self {
+        Self {
+            len: self.len + other.len,
+            len_utf16: self.len_utf16 + other.len_utf16,
+            newlines: self.newlines + other.newlines,
+        }
+    }
+}
+
+#[derive(Debug, Clone)]
+struct Chunk {
+    text: String,
+    summary: Summary,
+}
+
+impl Chunk {
+    fn new(text: String) -> Self {
+        Self {
+            summary: Summary::new(&text),
+            text,
+        }
+    }
+}
+
+/// The text storage of the `TextInput`, the text is stored in chunks of about `MIN_CHUNK_SIZE..=CHUNK_SIZE` bytes,
+/// and the lines and UTF-16 offsets are indexed by the prefix summaries of the chunks.
+///
+/// This is a flat list of chunks, not a balanced tree, for a text of `n` bytes:
+///
+/// - Finding the chunk by the offset, UTF-16 offset or line is `O(log(n / CHUNK_SIZE))`,
+///   then the chunk is scanned in `O(CHUNK_SIZE)`.
+/// - An edit copies only the chunks it touches, but splicing the chunks and updating the summaries
+///   after the edit is `O(n / CHUNK_SIZE)`, e.g.: about 10k small summaries for a 10MB text.
+///
+/// All offsets are in UTF-8 bytes, and must be at the char boundary.
+#[derive(Debug, Clone, Default)]
+pub(crate) struct Rope {
+    chunks: Vec<Chunk>,
+    /// The summary of the chunks before the chunk at the same index.
+    starts: Vec<Summary>,
+    summary: Summary,
+}
+
+impl Rope {
+    pub(crate) fn new() -> Self {
+        Self::default()
+    }
+
+    /// Returns the length in UTF-8 bytes.
+    pub(crate) fn len(&self) -> usize {
+        self.summary.len
+    }
+
+    /// Returns the length in UTF-16 code units.
+    pub(crate) fn len_utf16(&self) -> usize {
+        self.summary.len_utf16
+    }
+
+    pub(crate) fn is_empty(&self) -> bool {
+        self.summary.len == 0
+    }
+
+    /// Returns the number of lines, an empty text has 1 line.
+    pub(crate) fn lines_len(&self) -> usize {
+        self.summary.newlines + 1
+    }
+
+    /// Replace the text in the range with the new text.
+    ///
+    /// The chunks in the range are rebuilt, and merged with a neighbor chunk if they are undersized.
+    pub(crate) fn replace(&mut self, range: Range<usize>, new_text: &str) {
+        let range = range.start.min(self.len())..range.end.min(self.len());
+        let (mut start_ix, start_offset) = self.chunk_at(range.start);
+        let (end_ix, end_offset) = self.chunk_at(range.end);
+
+        let mut text = String::new();
+        if let Some(chunk) = self.chunks.get(start_ix) {
+            text.push_str(&chunk.text[..start_offset]);
+        }
+        text.push_str(new_text);
+        if let Some(chunk) = self.chunks.get(end_ix) {
+            text.push_str(&chunk.text[end_offset..]);
+        }
+
+        let mut end_ix = (end_ix + 1).min(self.chunks.len());
+        if text.len() < MIN_CHUNK_SIZE {
+            if let Some(next) = self.chunks.get(end_ix) {
+                text.push_str(&next.text);
+                end_ix += 1;
+            } else if start_ix > 0 {
+                start_ix -= 1;
+                text.insert_str(0, &self.chunks[start_ix].text);
+            }
+        }
+
+        self.chunks
+            .splice(start_ix..end_ix, split_chunks(&text).map(Chunk::new));
+        self.update_starts(start_ix);
+    }
+
+    /// Returns the text in the range.
+    pub(crate) fn slice(&self, range: Range<usize>) -> String {
+        let mut text = String::with_capacity(range.len());
+        for chunk in self.chunks_in_range(range) {
+            text.push_str(chunk);
+        }
+        text
+    }
+
+    /// Returns the chunks of the text in the range.
+    pub(crate) fn chunks_in_range(&self, range: Range<usize>) -> impl Iterator<Item = &str> {
+        let range = range.start.min(self.len())..range.end.min(self.len());
+        let (start_ix, _) = self.chunk_at(range.start);
+        self.chunks[start_ix..]
+            .iter()
+            .zip(&self.starts[start_ix..])
+            .take_while(move |(_, start)| start.len < range.end)
+            .map(move |(chunk, start)| {
+                let from = range.start.saturating_sub(start.len);
+                let to = (range.end - start.len).min(chunk.text.len());
+                &chunk.text[from..to]
+            })
+            .filter(|text| !text.is_empty())
+    }
+
+    /// Returns the line index of the offset.
+    pub(crate) fn offset_to_line(&self, offset: usize) -> usize {
+        let offset = offset.min(self.len());
+        let (ix, local) = self.chunk_at(offset);
+        match self.chunks.get(ix) {
+            Some(chunk) => {
+                self.starts[ix].newlines
+                    + chunk.text.as_bytes()[..local]
+                        .iter()
+                        .filter(|b| **b == b'\n')
+                        .count()
+            }
+            None => self.summary.newlines,
+        }
+    }
+
+    /// Returns the start offset of the line.
+    pub(crate) fn line_start(&self, line: usize) -> usize {
+        if line == 0 {
+            return 0;
+        }
+        if line > self.summary.newlines {
+            return self.len();
+        }
+
+        // The chunk contains the `line`-th newline.
+        let ix = self
+            .starts
+            .partition_point(|start| start.newlines < line)
+            .saturating_sub(1);
+        let n = line - self.starts[ix].newlines;
+        let local = self.chunks[ix]
+            .text
+            .match_indices('\n')
+            .nth(n - 1)
+            .map_or(self.chunks[ix].text.len(), |(i, _)| i + 1);
+        self.starts[ix].len + local
+    }
+
+    /// Returns the end offset of the line, without the `\n`.
+    pub(crate) fn line_end(&self, line: usize) -> usize {
+        if line >= self.summary.newlines {
+            self.len()
+        } else {
+            self.line_start(line + 1) - 1
+        }
+    }
+
+    /// Returns the text of the line, without the `\n`.
+    pub(crate) fn line(&self, line: usize) -> String {
+        self.slice(self.line_start(line)..self.line_end(line))
+    }
+
+    /// Convert the UTF-8 offset to the UTF-16 offset.
+    pub(crate) fn offset_to_utf16(&self, offset: usize) -> usize {
+        let offset = offset.min(self.len());
+        let (ix, local) = self.chunk_at(offset);
+        match self.chunks.get(ix) {
+            Some(chunk) => {
+                self.starts[ix].len_utf16
+                    + chunk.text[..local]
+                        .chars()
+                        .map(char::len_utf16)
+                        .sum::<usize>()
+            }
+            None => self.summary.len_utf16,
+        }
+    }
+
+    /// Convert the UTF-16 offset to the UTF-8 offset.
+    pub(crate) fn offset_from_utf16(&self, offset_utf16: usize) -> usize {
+        if offset_utf16 >= self.summary.len_utf16 {
+            return self.len();
+        }
+
+        let ix = self
+            .starts
+            .partition_point(|start| start.len_utf16 <= offset_utf16)
+            .saturating_sub(1);
+        let mut utf16_count = self.starts[ix].len_utf16;
+        let mut offset = self.starts[ix].len;
+        for ch in self.chunks[ix].text.chars() {
+            if utf16_count >= offset_utf16 {
+                break;
+            }
+            utf16_count += ch.len_utf16();
+            offset += ch.len_utf8();
+        }
+        offset
+    }
+
+    /// Returns the (index, local offset) of the chunk that contains the offset,
+    /// the index is the chunks length if the text is empty.
+    fn chunk_at(&self, offset: usize) -> (usize, usize) {
+        if self.chunks.is_empty() {
+            return (0, 0);
+        }
+
+        let ix = self
+            .starts
+            .partition_point(|start| start.len <= offset)
+            .saturating_sub(1);
+        (ix, offset - self.starts[ix].len)
+    }
+
+    /// Update the summaries of the chunks from the given index.
+    fn update_starts(&mut self, from: usize) {
+        self.starts.truncate(from);
+        let mut start = self
+            .starts
+            .last()
+            .copied()
+            .map_or(Summary::default(), |last| {
+                last.add(self.chunks[from - 1].summary)
+            });
+        for chunk in &self.chunks[from..] {
+            self.starts.push(start);
+            start = start.add(chunk.summary);
+        }
+        self.summary = start;
+    }
+}
+
+/// Split the text into the fewest chunks of the even size at the char boundary,
+/// so the chunks are not undersized if the text is longer than `MIN_CHUNK_SIZE`.
+fn split_chunks(text: &str) -> impl Iterator<Item = String> + '_ {
+    let mut rest = text;
+    std::iter::from_fn(move || {
+        if rest.is_empty() {
+            return None;
+        }
+
+        let count = rest.len().div_ceil(CHUNK_SIZE);
+        let mut end = rest.len().div_ceil(count);
+        while !rest.is_char_boundary(end) {
+            end += 1;
+        }
+        let (chunk, next) = rest.split_at(end);
+        rest = next;
+        Some(chunk.to_string())
+    })
+}
+
+impl From<&str> for Rope {
+    fn from(text: &str) -> Self {
+        let mut rope = Self::new();
+        rope.replace(0..0, text);
+        rope
+    }
+}
+
+impl fmt::Display for Rope {
+    fn fmt(&self, f: &mut fmt::Formatter<'_>) -> fmt::Result {
+        for chunk in &self.chunks {
+            f.write_str(&chunk.text)?;
+        }
+        Ok(())
+    }
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+
+    fn sample() -> String {
+        (0..500)
+            .map(|i| format!("Line {} 你好 🎉", i))
+            .collect::<Vec<_>>()
+            .join("\n")
+    }
+
+    #[test]
+    fn test_replace() {
+        let mut text = sample();
+        let mut rope = Rope::from(text.as_str());
+        assert!(rope.chunks.len() > 1);
+        assert_eq!(rope.to_string(), text);
+
+        for (range, new_text) in [
+            (0..0, "Hello\n"),
+            (100..3000, ""),
+            (50..60, "世界\n\n"),
+            (text.len() - 20..text.len() - 20, "x"),
+        ] {
+            let range = range.start.min(text.len())..range.end.min(text.len());
+            let range =
+                floor_char_boundary(&text, range.start)..floor_char_boundary(&text, range.end);
+            text.replace_range(range.clone(), new_text);
+            rope.replace(range, new_text);
+            assert_eq!(rope.to_string(), text);
+            assert_eq!(rope.len(), text.len());
+            assert_eq!(rope.len_utf16(), text.encode_utf16().count());
+        }
+
+        rope.replace(0..rope.len(), "");
+        assert!(rope.is_empty());
+        assert_eq!(rope.lines_len(), 1);
+        assert_eq!(rope.line(0), "");
+    }
+
+    #[test]
+    fn test_lines() {
+        let text = sample();
+        let rope = Rope::from(text.as_str());
+        let lines = text.split('\n').collect::<Vec<_>>();
+
+        assert_eq!(rope.lines_len(), lines.len());
+        let mut offset = 0;
+        for (ix, line) in lines.iter().enumerate() {
+            assert_eq!(rope.line_start(ix), offset);
+            assert_eq!(rope.line_end(ix), offset + line.len());
+            assert_eq!(rope.line(ix), *line);
+            assert_eq!(rope.offset_to_line(offset), ix);
+            assert_eq!(rope.offset_to_line(offset + line.len()), ix);
+            offset += line.len() + 1;
+        }
+        assert_eq!(rope.line_start(lines.len()), text.len());
+    }
+
+    #[test]
+    fn test_slice_and_utf16() {
+        let text = sample();
+        let rope = Rope::from(text.as_str());
+
+        let range = floor_char_boundary(&text, 1000)..floor_char_boundary(&text, 3000);
+        assert_eq!(rope.slice(range.clone()), text[range]);
+        assert_eq!(rope.slice(0..text.len()), text);
+        assert_eq!(rope.slice(5..5), "");
+
+        for (offset, _) in text.char_indices().step_by(7) {
+            let offset_utf16 = text[..offset].encode_utf16().count();
+            assert_eq!(rope.offset_to_utf16(offset), offset_utf16);
+            assert_eq!(rope.offset_from_utf16(offset_utf16), offset);
+        }
+        assert_eq!(rope.offset_from_utf16(usize::MAX), text.len());
+    }
+
+    #[test]
+    fn test_chunk_sizes() {
+        let assert_chunk_sizes = |rope: &Rope| {
+            if rope.chunks.len() > 1 {
+                for chunk in &rope.chunks {
+                    assert!(
+                        chunk.text.len() >= MIN_CHUNK_SIZE - 3,
+                        "{}",
+                        chunk.text.len()
+                    );
+                    assert!(chunk.text.len() <= CHUNK_SIZE + 3, "{}", chunk.text.len());
+                }
+            }
+        };
+
+        let mut text = sample();
+        let mut rope = Rope::from(text.as_str());
+        assert_chunk_sizes(&rope);
+
+        // Delete the text in many small steps, and insert some text between them.
+        let mut offset = 0;
+        while text.len() > 100 {
+            offset = floor_char_boundary(&text, (offset + 997) % (text.len() - 50));
+            let end = floor_char_boundary(&text, offset + 50);
+            let new_text = if offset % 3 == 0 { "中文\n" } else { "" };
+            text.replace_range(offset..end, new_text);
+            rope.replace(offset..end, new_text);
+            assert_eq!(rope.to_string(), text);
+            assert_chunk_sizes(&rope);
+        }
+        assert_eq!(rope.chunks.len(), 1);
+    }
+
+    fn floor_char_boundary(text: &str, mut ix: usize) -> usize {
+        while !text.is_char_boundary(ix) {
+            ix -= 1;
+        }
+        ix
+    }
+}
@@ -0,0 +1,149 @@
+use std::ops::Range;
+
+/// A selection of the multiple cursors in the `TextInput`, the range is in UTF-8 offset.
+#[derive(Debug, Clone, Default, PartialEq, Eq)]
+pub(super) struct Selection {
+    pub(super) range: Range<usize>,
+    /// True if the cursor is at the start of the range.
+    pub(super) reversed: bool,
+}
+
+impl Selection {
+    pub(super) fn new(range: Range<usize>, reversed: bool) -> Self {
+        Self { range, reversed }
+    }
+
+    /// Create an empty selection, that is a cursor at the given offset.
+    pub(super) fn cursor(offset: usize) -> Self {
+        Self::new(offset..offset, false)
+    }
+
+    /// Returns the offset of the cursor.
+    pub(super) fn head(&self) -> usize {
+        if self.reversed {
+            self.range.start
+        } else {
+            self.range.end
+        }
+    }
+
+    /// Move the selection by the length changed of an edit before it.
+    pub(super) fn shift(&mut self, delta: isize) {
+        let shift = |offset: usize| offset.saturating_add_signed(delta);
+        self.range = shift(self.range.start)..shift(self.range.end);
+    }
+}
+
+/// Sort the selections by the position, and merge the overlapping ones into one.
+///
+/// Returns the new index of the `primary` selection.
+pub(super) fn merge_selections(selections: &mut Vec<Selection>, primary: usize) -> usize {
+    let mut items = selections
+        .drain(..)
+        .enumerate()
+        .map(|(ix, selection)| (selection, ix == primary))
+        .collect::<Vec<_>>();
+    items.sort_by_key(|(selection, _)| (selection.range.start, selection.range.end));
+
+    let mut primary = 0;
+    for (selection, is_primary) in items {
+        if let Some(last) = selections.last_mut() {
+            if selection.range.start < last.range.end || selection.range == last.range {
+                last.range.end = last.range.end.max(selection.range.end);
+                if is_primary {
+                    last.reversed = selection.reversed;
+                    primary = selections.len() - 1;
+                }
+                continue;
+            }
+        }
+
+        if is_primary {
+            primary = selections.len();
+        }
+        selections.push(selection);
+    }
+
+    primary
+}
+
+/// Returns the range of the next occurrence of the `query` after the `offset`, wraps around to the start.
+///
+/// The occurrences that are already selected are skipped.
+pub(super) fn next_occurrence(
+    text: &str,
+    query: &str,
+    offset: usize,
+    selections: &[Selection],
+) -> Option<Range<usize>> {
+    if query.is_empty() {
+        return None;
+    }
+
+    let after = text[offset..]
+        .match_indices(query)
+        .map(|(ix, _)| offset + ix);
+    let before = text.match_indices(query).map(|(ix, _)| ix);
+    after
+        .chain(before.take_while(|&ix| ix < offset))
+        .map(|ix| ix..ix + query.len())
+        .find(|range| !selections.iter().any(|selection| selection.range == *range))
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+
+    #[test]
+    fn test_merge_selections() {
+        let mut selections = vec![
+            Selection::cursor(10),
+            Selection::new(2..6, false),
+            Selection::new(4..8, true),
+            Selection::cursor(10),
+            Selection::cursor(0),
+        ];
+
+        assert_eq!(merge_selections(&mut selections, 2), 1);
+        assert_eq!(
+            selections,
+            vec![
+                Selection::cursor(0),
+                Selection::new(2..8, true),
+                Selection::cursor(10),
+            ]
+        );
+    }
+
+    #[test]
+    fn test_next_occurrence() {
+        let text = "foo bar foo baz foo";
+        let selected = [Selection::new(8..11, false)];
+
+        assert_eq!(next_occurrence(text, "foo", 11, &selected), Some(16..19));
+        // Wraps around, and skips the selected one.
+        assert_eq!(next_occurrence(text, "foo", 19, &selected), Some(0..3));
+        assert_eq!(
+            next_occurrence(
+                text,
+                "foo",
+                3,
+                &[Selection::new(0..3, false), selected[0].clone()]
+            ),
+            Some(16..19)
+        );
+        assert_eq!(next_occurrence(text, "qux", 0, &[]), None);
+        assert_eq!(next_occurrence(text, "", 0, &[]), None);
+    }
+
+    #[test]
+    fn test_selection() {
+        let mut selection = Selection::new(4..8, true);
+        assert_eq!(selection.head(), 4);
+        selection.shift(-2);
+        assert_eq!(selection.range, 2..6);
+        selection.shift(3);
+        assert_eq!(selection.range, 5..9);
+        assert_eq!(Selection::cursor(3).head(), 3);
+    }
+}
@@ -1,5 +1,6 @@
 mod colors;
 mod event;
+mod find;
 mod focusable;
 mod icon;
 mod kbd;
@@ -20,6 +21,7 @@ pub mod checkbox;
 pub mod clipboard;
 pub mod color_picker;
 pub mod context_menu;
+pub mod data_source;
 pub mod description_list;
 pub mod divider;
 pub mod dock;
@@ -1,5 +1,5 @@
 use std::time::Duration;
-use std::{cell::Cell, rc::Rc};
+use std::{cell::Cell, ops::Range, rc::Rc};
 
 use crate::Icon;
 use crate::{
@@ -140,6 +140,19 @@ pub trait ListDelegate: Sized + 'static {
     /// This is always called when the table is near the bottom,
     /// so you must check if there is more data to load or lock the loading state.
     fn load_more(&mut self, window: &mut Window, cx: &mut Context<List<Self>>) {}
+
+    /// Called when the visible range of the items changed.
+    ///
+    /// NOTE: Make sure this method is fast, because it will be called frequently.
+    ///
+    /// This can be used to fetch the visible items, see also [`crate::data_source::PagedRows`].
+    fn visible_items_changed(
+        &mut self,
+        visible_range: Range<usize>,
+        window: &mut Window,
+        cx: &mut Context<List<Self>>,
+    ) {
+    }
 }
 
 pub struct List<D: ListDelegate> {
@@ -156,6 +169,7 @@ pub struct List<D: ListDelegate> {
     pub(crate) size: Size,
     selected_index: Option<usize>,
     right_clicked_index: Option<usize>,
+    visible_range: Range<usize>,
     reset_on_cancel: bool,
     _search_task: Task<()>,
     _load_more_task: Task<()>,
@@ -185,6 +199,7 @@ where
             last_query: None,
             selected_index: None,
             right_clicked_index: None,
+            visible_range: 0..0,
             vertical_scroll_handle: UniformListScrollHandle::new(),
             scrollbar_state: Rc::new(Cell::new(ScrollbarState::new())),
             max_height: None,
@@ -383,6 +398,21 @@ where
         }
     }
 
+    fn update_visible_range_if_need(
+        &mut self,
+        visible_range: Range<usize>,
+        window: &mut Window,
+        cx: &mut Context<Self>,
+    ) {
+        if self.visible_range == visible_range {
+            return;
+        }
+
+        self.delegate
+            .visible_items_changed(visible_range.clone(), window, cx);
+        self.visible_range = visible_range;
+    }
+
     pub(crate) fn reset_on_cancel(mut self, reset: bool) -> Self {
         self.reset_on_cancel = reset;
         self
@@ -601,6 +631,11 @@ where
                                                         window,
                                                         cx,
                                                     );
+                                                    list.update_visible_range_if_need(
+                                                        visible_range.clone(),
+                                                        window,
+                                                        cx,
+                                                    );
 
                                                     visible_range
                                                         .map(|ix| {
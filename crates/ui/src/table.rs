@@ -1,22 +1,69 @@
-use std::{cell::Cell, ops::Range, rc::Rc, time::Duration};
+use std::{
+    cell::Cell,
+    collections::HashSet,
+    io,
+    ops::Range,
+    rc::Rc,
+    sync::Arc,
+    time::{Duration, Instant},
+};
 
 use crate::{
+    button::{Button, ButtonVariants as _},
     context_menu::ContextMenuExt,
+    find::FindState,
     h_flex,
+    input::{InputEvent, TextInput},
+    popover::Popover,
     popup_menu::PopupMenu,
     scroll::{self, ScrollableMask, Scrollbar, ScrollbarState},
-    v_flex, ActiveTheme, Icon, IconName, Sizable, Size, StyleSized as _,
+    v_flex, ActiveTheme, ColorName, Icon, IconName, Sizable, Size, StyleSized as _,
 };
 use gpui::{
-    actions, canvas, div, prelude::FluentBuilder, px, uniform_list, App, AppContext, Axis, Bounds,
-    Context, Div, DragMoveEvent, Edges, Empty, EntityId, EventEmitter, FocusHandle, Focusable,
-    InteractiveElement, IntoElement, KeyBinding, ListSizingBehavior, MouseButton, MouseDownEvent,
-    ParentElement, Pixels, Point, Render, ScrollHandle, ScrollStrategy, ScrollWheelEvent,
-    SharedString, Stateful, StatefulInteractiveElement as _, Styled, Task, UniformListScrollHandle,
-    Window,
+    actions, canvas, div, impl_internal_actions, prelude::FluentBuilder, px, relative,
+    uniform_list, AnyElement, App, AppContext, Axis, Bounds, ClickEvent, ClipboardItem, Context,
+    Corner, Div, DragMoveEvent, Edges, Empty, Entity, EntityId, EventEmitter, FocusHandle,
+    Focusable, InteractiveElement, IntoElement, KeyBinding, KeyDownEvent, ListSizingBehavior,
+    MouseButton, MouseDownEvent, MouseUpEvent, ParentElement, Pixels, Point, Render, ScrollHandle,
+    ScrollStrategy, ScrollWheelEvent, SharedString, Stateful, StatefulInteractiveElement as _,
+    Styled, Subscription, Task, TextRun, TextStyle, Timer, UniformListScrollHandle, Window,
 };
-
+use rust_i18n::t;
+use serde::{Deserialize, Serialize};
+
+mod aggregate;
+mod clipboard;
+mod export;
+mod filter;
+mod find;
+mod format;
+mod group;
 mod loading;
+mod selection;
+mod state;
+mod tree;
+
+use aggregate::format_aggregate;
+use filter::FilterPanel;
+use format::{CellFormat, ColFormat};
+use group::{ListItem, RowGroups};
+use tree::TreeKeyAction;
+
+pub use aggregate::Aggregate;
+pub use export::{CellValue, ExportFormat, ExportRows};
+pub use filter::{ColFilter, ColFilterKind, TextFilterOp};
+pub use format::{CellStyle, FormatRule};
+pub use selection::*;
+pub use state::{ColLayoutState, TableLayoutState};
+
+/// The indent of each depth of the tree rows.
+const TREE_INDENT: Pixels = px(16.);
+/// The key context of the search bar.
+const FIND_CONTEXT: &str = "TableFind";
+/// The delay to search after the typing in the search bar is stopped.
+const FIND_DEBOUNCE: Duration = Duration::from_millis(150);
+/// The number of rows to read the cell values for the search at a time, to not block the main thread.
+const FIND_CHUNK_ROWS: usize = 500;
 
 actions!(
     table,
@@ -25,10 +72,56 @@ actions!(
         SelectPrev,
         SelectNext,
         SelectPrevColumn,
-        SelectNextColumn
+        SelectNextColumn,
+        EditCell,
+        SelectPrevCell,
+        SelectNextCell,
+        SelectAll,
+        ExtendSelectionUp,
+        ExtendSelectionDown,
+        ExtendSelectionLeft,
+        ExtendSelectionRight,
+        Copy,
+        Paste,
+        SelectPageUp,
+        SelectPageDown,
+        SelectRowStart,
+        SelectRowEnd,
+        SelectFirstCell,
+        SelectLastCell,
+        SelectEdgeUp,
+        SelectEdgeDown,
+        SelectEdgeLeft,
+        SelectEdgeRight,
+        Find,
+        FindPrev,
+        CloseFind
     ]
 );
 
+/// Toggle the visibility of the column at the given index, used by the header context menu.
+#[derive(Clone, PartialEq, Eq, Deserialize)]
+struct ToggleColVisible(usize);
+
+/// Sort the table by the column at the given index.
+#[derive(Clone, PartialEq, Eq, Deserialize)]
+struct SortCol(usize, ColSort);
+
+/// Pin the column at the given index to a side, None to unpin.
+#[derive(Clone, PartialEq, Eq, Deserialize)]
+struct FixCol(usize, Option<ColFixed>);
+
+#[derive(Clone, PartialEq, Eq, Deserialize)]
+struct AutoFitCol(usize);
+
+#[derive(Clone, PartialEq, Eq, Deserialize)]
+struct ResetColWidth(usize);
+
+impl_internal_actions!(
+    table,
+    [ToggleColVisible, SortCol, FixCol, AutoFitCol, ResetColWidth]
+);
+
 pub fn init(cx: &mut App) {
     let context = Some("Table");
     cx.bind_keys([
@@ -37,12 +130,71 @@ pub fn init(cx: &mut App) {
         KeyBinding::new("down", SelectNext, context),
         KeyBinding::new("left", SelectPrevColumn, context),
         KeyBinding::new("right", SelectNextColumn, context),
+        KeyBinding::new("enter", EditCell, context),
+        KeyBinding::new("f2", EditCell, context),
+        KeyBinding::new("tab", SelectNextCell, context),
+        KeyBinding::new("shift-tab", SelectPrevCell, context),
+        KeyBinding::new("shift-up", ExtendSelectionUp, context),
+        KeyBinding::new("shift-down", ExtendSelectionDown, context),
+        KeyBinding::new("shift-left", ExtendSelectionLeft, context),
+        KeyBinding::new("shift-right", ExtendSelectionRight, context),
+        KeyBinding::new("pageup", SelectPageUp, context),
+        KeyBinding::new("pagedown", SelectPageDown, context),
+        KeyBinding::new("home", SelectRowStart, context),
+        KeyBinding::new("end", SelectRowEnd, context),
+        #[cfg(target_os = "macos")]
+        KeyBinding::new("cmd-home", SelectFirstCell, context),
+        #[cfg(not(target_os = "macos"))]
+        KeyBinding::new("ctrl-home", SelectFirstCell, context),
+        #[cfg(target_os = "macos")]
+        KeyBinding::new("cmd-end", SelectLastCell, context),
+        #[cfg(not(target_os = "macos"))]
+        KeyBinding::new("ctrl-end", SelectLastCell, context),
+        #[cfg(target_os = "macos")]
+        KeyBinding::new("cmd-up", SelectEdgeUp, context),
+        #[cfg(not(target_os = "macos"))]
+        KeyBinding::new("ctrl-up", SelectEdgeUp, context),
+        #[cfg(target_os = "macos")]
+        KeyBinding::new("cmd-down", SelectEdgeDown, context),
+        #[cfg(not(target_os = "macos"))]
+        KeyBinding::new("ctrl-down", SelectEdgeDown, context),
+        #[cfg(target_os = "macos")]
+        KeyBinding::new("cmd-left", SelectEdgeLeft, context),
+        #[cfg(not(target_os = "macos"))]
+        KeyBinding::new("ctrl-left", SelectEdgeLeft, context),
+        #[cfg(target_os = "macos")]
+        KeyBinding::new("cmd-right", SelectEdgeRight, context),
+        #[cfg(not(target_os = "macos"))]
+        KeyBinding::new("ctrl-right", SelectEdgeRight, context),
+        #[cfg(target_os = "macos")]
+        KeyBinding::new("cmd-a", SelectAll, context),
+        #[cfg(not(target_os = "macos"))]
+        KeyBinding::new("ctrl-a", SelectAll, context),
+        #[cfg(target_os = "macos")]
+        KeyBinding::new("cmd-c", Copy, context),
+        #[cfg(not(target_os = "macos"))]
+        KeyBinding::new("ctrl-c", Copy, context),
+        #[cfg(target_os = "macos")]
+        KeyBinding::new("cmd-v", Paste, context),
+        #[cfg(not(target_os = "macos"))]
+        KeyBinding::new("ctrl-v", Paste, context),
+        #[cfg(target_os = "macos")]
+        KeyBinding::new("cmd-f", Find, context),
+        #[cfg(not(target_os = "macos"))]
+        KeyBinding::new("ctrl-f", Find, context),
+        // The enter in the search bar is handled by the `InputEvent::PressEnter`.
+        KeyBinding::new("shift-enter", FindPrev, Some(FIND_CONTEXT)),
+        KeyBinding::new("escape", CloseFind, Some(FIND_CONTEXT)),
     ]);
 }
 
-#[derive(Debug, Clone, Copy, PartialEq, Eq)]
+/// The fixed side of the column.
+///
+/// The left fixed columns must be the first columns, and the right fixed columns must be the last columns.
+#[derive(Debug, Clone, Copy, PartialEq, Eq, Serialize, Deserialize)]
 pub enum ColFixed {
     Left,
+    Right,
 }
 
 #[derive(Debug, Clone, Copy)]
@@ -52,6 +204,8 @@ pub(crate) struct ColGroup {
     pub(crate) sort: Option<ColSort>,
     pub(crate) fixed: Option<ColFixed>,
     pub(crate) padding: Option<Edges<Pixels>>,
+    /// Whether the column is hidden by the user, the hidden column is rendered with zero width.
+    pub(crate) hidden: bool,
 }
 
 #[derive(Clone)]
@@ -62,7 +216,7 @@ pub(crate) struct DragCol {
     pub(crate) col_ix: usize,
 }
 
-#[derive(Copy, Clone, Debug, PartialEq, Eq)]
+#[derive(Copy, Clone, Debug, PartialEq, Eq, Serialize, Deserialize)]
 pub enum ColSort {
     /// No sorting.
     Default,
@@ -98,10 +252,20 @@ impl Render for ResizeCol {
     }
 }
 
+/// The drag payload for selecting a range of cells by mouse.
+#[derive(Clone)]
+pub struct SelectCells(pub EntityId);
+impl Render for SelectCells {
+    fn render(&mut self, _window: &mut Window, _: &mut Context<Self>) -> impl IntoElement {
+        Empty
+    }
+}
+
 #[derive(Copy, Clone, Debug, PartialEq, Eq)]
 enum SelectionState {
     Column,
     Row,
+    Cell,
 }
 
 #[derive(Clone)]
@@ -113,11 +277,24 @@ pub enum TableEvent {
     SelectCol(usize),
     ColWidthsChanged(Vec<Pixels>),
     MoveCol(usize, usize),
+    /// The cell at the given (row_ix, col_ix) has been edited and committed.
+    CellEdited(usize, usize),
+    /// The cell selection has been changed.
+    SelectionChanged(TableSelection),
+    /// The column filters have been changed, with the active filters of (col_ix, filter).
+    FilterChanged(Vec<(usize, ColFilter)>),
+    /// The row group with the key has been collapsed (true) or expanded (false).
+    GroupToggled(SharedString, bool),
+    /// The tree row at the given index has been expanded or collapsed.
+    RowExpandToggled(usize),
+    /// The column at the given index has been hidden (true) or shown (false).
+    ColHiddenChanged(usize, bool),
 }
 
 #[derive(Clone, Copy, Default)]
 struct FixedCols {
     left: usize,
+    right: usize,
 }
 
 /// The visible range of the rows and columns.
@@ -148,11 +325,39 @@ pub struct Table<D: TableDelegate> {
     bounds: Bounds<Pixels>,
     /// The bounds of the fixed head cols.
     fixed_head_cols_bounds: Bounds<Pixels>,
+    /// The bounds of the right fixed head cols.
+    fixed_right_head_cols_bounds: Bounds<Pixels>,
     /// The bounds of the table head content.
     head_content_bounds: Bounds<Pixels>,
 
     col_groups: Vec<ColGroup>,
     fixed_cols: FixedCols,
+    /// The column indexes of the active sort keys, in priority order.
+    sort_keys: Vec<usize>,
+    /// The filter of each column.
+    col_filters: Vec<Option<ColFilter>>,
+    /// The row groups and the items of the body list, rebuilt when the rows are changed or a group is toggled.
+    row_groups: RowGroups,
+    /// The subtotal of each column (by `group_aggregate`) of each row group.
+    group_values: Vec<Vec<Option<f64>>>,
+    /// The keys of the collapsed row groups.
+    collapsed_groups: HashSet<SharedString>,
+    /// The heights of the body list items, None if all rows use the default row height.
+    list_item_heights: Option<Rc<Vec<gpui::Size<Pixels>>>>,
+    /// The top of each body list item and the bottom of the last one, only for the `list_item_heights`.
+    list_item_tops: Vec<Pixels>,
+    /// The text style of the table, used to measure the text width of the cells.
+    text_style: TextStyle,
+    /// The conditional formatting of each column, rebuilt when the rows are changed.
+    col_formats: Vec<Option<ColFormat>>,
+    /// The footer aggregate value of each column.
+    footer_values: Vec<Option<f64>>,
+    /// Whether the rows data has been changed, the caches of the rows are rebuilt on the next render.
+    rows_dirty: bool,
+    /// The rows count when the caches of the rows were built.
+    cached_rows_count: usize,
+    /// Increased when the caches of the rows are rebuilt.
+    rows_version: usize,
 
     pub vertical_scroll_handle: UniformListScrollHandle,
     pub vertical_scrollbar_state: Rc<Cell<ScrollbarState>>,
@@ -164,9 +369,29 @@ pub struct Table<D: TableDelegate> {
     selection_state: SelectionState,
     right_clicked_row: Option<usize>,
     selected_col: Option<usize>,
+    /// The index of the selected group header, the `selected_row` is None when a group is selected.
+    selected_group: Option<usize>,
+    /// The cell range selection, the focus cell is always in sync with the `selected_row`.
+    selection: TableSelection,
+    /// The mouse position while selecting cells by dragging.
+    drag_select_position: Option<Point<Pixels>>,
 
     /// The column index that is being resized.
     resizing_col: Option<usize>,
+    /// The (row_ix, col_ix) of the cell that is being edited.
+    editing_cell: Option<(usize, usize)>,
+    /// The typed text to search the rows by the focused column, and the time of the last typing.
+    type_search: String,
+    type_search_at: Option<Instant>,
+    /// The search bar to find the cells, opened by `cmd-f` or `ctrl-f`.
+    find_input: Entity<TextInput>,
+    find_visible: bool,
+    find: FindState<CellPos>,
+    /// True if the search is running in the background.
+    find_searching: bool,
+    /// The values of the visible cells to search, cached until the rows are changed.
+    find_cells: Option<Arc<Vec<(CellPos, CellValue)>>>,
+    _find_task: Task<()>,
 
     /// Set stripe style of the table.
     stripe: bool,
@@ -179,6 +404,8 @@ pub struct Table<D: TableDelegate> {
 
     _measure: Vec<Duration>,
     _load_more_task: Task<()>,
+    _autoscroll_task: Task<()>,
+    _subscriptions: Vec<Subscription>,
 }
 
 #[allow(unused)]
@@ -191,6 +418,13 @@ pub trait TableDelegate: Sized + 'static {
     /// Returns the name of the column at the given index.
     fn col_name(&self, col_ix: usize, cx: &App) -> SharedString;
 
+    /// Returns the unique key of the column at the given index, used to persist the columns layout.
+    ///
+    /// Default: the column name
+    fn col_key(&self, col_ix: usize, cx: &App) -> SharedString {
+        self.col_name(col_ix, cx)
+    }
+
     /// Returns whether the column at the given index can be resized. Default: true
     fn can_resize_col(&self, col_ix: usize, cx: &App) -> bool {
         true
@@ -211,6 +445,20 @@ pub trait TableDelegate: Sized + 'static {
         px(100.)
     }
 
+    /// Returns the minimum width of the column at the given index, used to resize or auto-fit the column.
+    ///
+    /// Default: 10px
+    fn col_min_width(&self, col_ix: usize, cx: &App) -> Pixels {
+        px(10.)
+    }
+
+    /// Returns the maximum width of the column at the given index, used to resize or auto-fit the column.
+    ///
+    /// Default: 1200px
+    fn col_max_width(&self, col_ix: usize, cx: &App) -> Pixels {
+        px(1200.)
+    }
+
     /// Return the sort state of the column at the given index.
     ///
     /// This is only called when the table initializes.
@@ -223,6 +471,34 @@ pub trait TableDelegate: Sized + 'static {
         None
     }
 
+    /// Return the number of the frozen rows at the top of the table, the frozen rows
+    /// are always visible while scrolling vertically, e.g.: a summary row.
+    ///
+    /// Default: 0
+    fn frozen_rows_count(&self, cx: &App) -> usize {
+        0
+    }
+
+    /// Return the height of the row at the given index to override the default row height of the table size.
+    ///
+    /// If any row has a custom height, the table body will be rendered by a variable height list,
+    /// and the cells of these rows can wrap the text into multiple lines.
+    ///
+    /// This is only called if `has_row_heights` returns true, for every row when the rows are changed
+    /// (see [`Table::refresh_rows`]), and for the frozen rows when they are rendered.
+    ///
+    /// Default: None
+    fn row_height(&self, row_ix: usize, cx: &App) -> Option<Pixels> {
+        None
+    }
+
+    /// Return true to use the custom row heights by `row_height`.
+    ///
+    /// Default: false
+    fn has_row_heights(&self, cx: &App) -> bool {
+        false
+    }
+
     /// Return the padding of the column at the given index to override the default padding.
     ///
     /// Return None, use the default padding.
@@ -240,6 +516,50 @@ pub trait TableDelegate: Sized + 'static {
     ) {
     }
 
+    /// Perform sort by multiple columns, this is called when the user shift+click on the headers.
+    ///
+    /// The `sorts` is the ordered sort keys of (col_ix, sort), the first one is the primary key.
+    /// The columns not in the `sorts` should be sorted as [`ColSort::Default`].
+    ///
+    /// The `sorts` is never empty, when the last sort key is removed, `perform_sort` is called
+    /// with [`ColSort::Default`] instead.
+    ///
+    /// Default: perform sort by the primary key with `perform_sort`.
+    fn perform_multi_sort(
+        &mut self,
+        sorts: &[(usize, ColSort)],
+        window: &mut Window,
+        cx: &mut Context<Table<Self>>,
+    ) {
+        if let Some(&(col_ix, sort)) = sorts.first() {
+            self.perform_sort(col_ix, sort, window, cx);
+        }
+    }
+
+    /// Return the filter kind of the column at the given index to show the filter button
+    /// in the header, return None to disable the filter of this column.
+    fn col_filter_kind(&self, col_ix: usize, cx: &App) -> Option<ColFilterKind> {
+        None
+    }
+
+    /// Return the distinct values of the column at the given index for the [`ColFilterKind::Values`] filter.
+    ///
+    /// This is called when the filter popover is opened.
+    fn col_filter_values(&self, col_ix: usize, cx: &App) -> Vec<SharedString> {
+        vec![]
+    }
+
+    /// Perform filter by the active column filters of (col_ix, filter).
+    ///
+    /// The rows should be filtered by all the filters, an empty `filters` means no filter.
+    fn perform_filter(
+        &mut self,
+        filters: &[(usize, ColFilter)],
+        window: &mut Window,
+        cx: &mut Context<Table<Self>>,
+    ) {
+    }
+
     /// Render the header cell at the given column index, default to the column name.
     fn render_th(
         &self,
@@ -265,6 +585,20 @@ pub trait TableDelegate: Sized + 'static {
         menu
     }
 
+    /// Render the context menu for the column header at the given column index.
+    ///
+    /// The `menu` has the built-in items of the column, such as sort, pin, auto-fit, hide and reset width,
+    /// the delegate can add more items to it.
+    fn header_context_menu(
+        &self,
+        col_ix: usize,
+        menu: PopupMenu,
+        window: &Window,
+        cx: &App,
+    ) -> PopupMenu {
+        menu
+    }
+
     /// Render cell at the given row and column.
     fn render_td(
         &self,
@@ -274,6 +608,215 @@ pub trait TableDelegate: Sized + 'static {
         cx: &mut Context<Table<Self>>,
     ) -> impl IntoElement;
 
+    /// Returns whether the cell at the given row and column can be edited. Default: false
+    fn can_edit_cell(&self, row_ix: usize, col_ix: usize, cx: &App) -> bool {
+        false
+    }
+
+    /// Called when the cell at the given row and column enters edit mode.
+    ///
+    /// Use this to prepare the editor state (e.g. create a `TextInput` with the current value),
+    /// and focus the editor.
+    fn start_cell_edit(
+        &mut self,
+        row_ix: usize,
+        col_ix: usize,
+        window: &mut Window,
+        cx: &mut Context<Table<Self>>,
+    ) {
+    }
+
+    /// Render the editor of the cell at the given row and column, default to `render_td`.
+    ///
+    /// This is only called for the cell in edit mode.
+    fn render_cell_editor(
+        &self,
+        row_ix: usize,
+        col_ix: usize,
+        window: &mut Window,
+        cx: &mut Context<Table<Self>>,
+    ) -> impl IntoElement {
+        self.render_td(row_ix, col_ix, window, cx)
+    }
+
+    /// Commit the edit of the cell at the given row and column.
+    ///
+    /// Return false to reject the value and keep the cell in edit mode.
+    fn commit_cell_edit(
+        &mut self,
+        row_ix: usize,
+        col_ix: usize,
+        window: &mut Window,
+        cx: &mut Context<Table<Self>>,
+    ) -> bool {
+        true
+    }
+
+    /// Called when the edit of the cell at the given row and column has been canceled.
+    fn cancel_cell_edit(
+        &mut self,
+        row_ix: usize,
+        col_ix: usize,
+        window: &mut Window,
+        cx: &mut Context<Table<Self>>,
+    ) {
+    }
+
+    /// Returns the plain data value of the cell at the given row and column, used to export the table data.
+    ///
+    /// Default: [`CellValue::Empty`]
+    fn cell_value(&self, row_ix: usize, col_ix: usize, cx: &App) -> CellValue {
+        CellValue::Empty
+    }
+
+    /// Returns the plain text of the cell at the given row and column, used to copy the cells.
+    ///
+    /// The cells are copied as TSV text, with a HTML table flavour for the spreadsheets.
+    ///
+    /// Default: the text of `cell_value`
+    fn cell_text(&self, row_ix: usize, col_ix: usize, cx: &App) -> SharedString {
+        match self.cell_value(row_ix, col_ix, cx) {
+            CellValue::Empty => SharedString::default(),
+            CellValue::Text(text) => text,
+            value => value.to_string().into(),
+        }
+    }
+
+    /// Paste the grid of cells (rows of cells) parsed from the clipboard TSV or CSV text,
+    /// the `origin` is the top-left cell of the current selection.
+    fn paste_cells(
+        &mut self,
+        origin: CellPos,
+        grid: Vec<Vec<String>>,
+        window: &mut Window,
+        cx: &mut Context<Table<Self>>,
+    ) {
+    }
+
+    /// Return true to render a sticky footer row below the table body, e.g.: the totals row.
+    ///
+    /// Default: false
+    fn has_footer(&self, cx: &App) -> bool {
+        false
+    }
+
+    /// Return the aggregate function of the column at the given index, the aggregated value
+    /// over all rows (see [`Table::aggregate`]) will be passed to `render_tfoot`.
+    ///
+    /// The values are cached until the rows are changed, see [`Table::refresh_rows`].
+    fn footer_aggregate(&self, col_ix: usize, cx: &App) -> Option<Aggregate> {
+        None
+    }
+
+    /// Returns the numeric value of the cell at the given row and column,
+    /// used to compute the aggregate and the conditional formatting.
+    ///
+    /// Return None to skip the cell, e.g.: the cell is empty or not a number.
+    ///
+    /// Default: the number of `cell_value`
+    fn cell_number(&self, row_ix: usize, col_ix: usize, cx: &App) -> Option<f64> {
+        self.cell_value(row_ix, col_ix, cx).as_number()
+    }
+
+    /// Returns the conditional formatting rules of the column at the given index,
+    /// the rules are applied to the value of `cell_number` in order.
+    ///
+    /// The rules and the range of the values are cached until the rows are changed, see [`Table::refresh_rows`].
+    ///
+    /// Default: no rules
+    fn col_format_rules(&self, col_ix: usize, cx: &App) -> Vec<FormatRule> {
+        vec![]
+    }
+
+    /// Render the footer cell at the given column index.
+    ///
+    /// The `value` is the aggregated value of the column by `footer_aggregate`,
+    /// default to render the value rounded to 2 decimal places.
+    fn render_tfoot(
+        &self,
+        col_ix: usize,
+        value: Option<f64>,
+        window: &mut Window,
+        cx: &mut Context<Table<Self>>,
+    ) -> impl IntoElement {
+        div().size_full().children(value.map(format_aggregate))
+    }
+
+    /// Return the group key of the row at the given index, the rows with the same key are
+    /// grouped under a collapsible group header.
+    ///
+    /// The rows of the same group must be adjacent, e.g.: sort the rows by the group key first.
+    /// The frozen rows are not grouped.
+    ///
+    /// This is only called if `has_row_groups` returns true, for every row when the rows are changed,
+    /// see [`Table::refresh_rows`].
+    ///
+    /// Default: None, the rows are not grouped.
+    fn row_group(&self, row_ix: usize, cx: &App) -> Option<SharedString> {
+        None
+    }
+
+    /// Return true to group the rows by `row_group`.
+    ///
+    /// Default: false
+    fn has_row_groups(&self, cx: &App) -> bool {
+        false
+    }
+
+    /// Return the aggregate function of the column at the given index to show the subtotal
+    /// of the column in the group headers, the values are from `cell_number`.
+    fn group_aggregate(&self, col_ix: usize, cx: &App) -> Option<Aggregate> {
+        None
+    }
+
+    /// Render the label of the group header, it is rendered in the first column.
+    ///
+    /// Default: the group key with the rows count.
+    fn render_group_header(
+        &self,
+        key: &SharedString,
+        rows_count: usize,
+        window: &mut Window,
+        cx: &mut Context<Table<Self>>,
+    ) -> impl IntoElement {
+        h_flex().gap_1().child(key.clone()).child(
+            div()
+                .text_color(cx.theme().muted_foreground)
+                .child(format!("({})", rows_count)),
+        )
+    }
+
+    /// Return true to render the table as a tree, the first visible column renders the indentation
+    /// and the disclosure chevron of each row.
+    ///
+    /// The rows are the visible (flattened) nodes of the tree, the delegate should update
+    /// the rows in `toggle_expand`.
+    ///
+    /// Default: false
+    fn is_tree(&self, cx: &App) -> bool {
+        false
+    }
+
+    /// Returns the depth of the tree row at the given index, the root rows are 0.
+    fn row_depth(&self, row_ix: usize, cx: &App) -> usize {
+        0
+    }
+
+    /// Returns true if the tree row at the given index has children.
+    fn row_has_children(&self, row_ix: usize, cx: &App) -> bool {
+        false
+    }
+
+    /// Returns true if the tree row at the given index is expanded.
+    fn is_row_expanded(&self, row_ix: usize, cx: &App) -> bool {
+        false
+    }
+
+    /// Expand or collapse the tree row at the given index,
+    /// the children rows should be inserted after or removed from the row.
+    fn toggle_expand(&mut self, row_ix: usize, window: &mut Window, cx: &mut Context<Table<Self>>) {
+    }
+
     /// Return true to enable loop selection on the table.
     ///
     /// When the prev/next selection is out of the table bounds, the selection will loop to the other side.
@@ -361,6 +904,8 @@ pub trait TableDelegate: Sized + 'static {
     ///
     /// This can used to handle some data update, to only update the visible rows.
     /// Please ensure that the data is updated in the background task.
+    ///
+    /// See also [`crate::data_source::PagedRows`] to fetch the visible rows by pages.
     fn visible_rows_changed(
         &mut self,
         visible_range: Range<usize>,
@@ -388,12 +933,41 @@ impl<D> Table<D>
 where
     D: TableDelegate,
 {
-    pub fn new(delegate: D, _: &mut Window, cx: &mut Context<Self>) -> Self {
+    pub fn new(delegate: D, window: &mut Window, cx: &mut Context<Self>) -> Self {
+        let find_input = cx.new(|cx| {
+            TextInput::new(window, cx)
+                .xsmall()
+                .appearance(false)
+                .placeholder(t!("Table.Find"))
+        });
+        let _subscriptions = vec![cx.subscribe_in(
+            &find_input,
+            window,
+            |this, _, ev: &InputEvent, _, cx| match ev {
+                InputEvent::Change(text) => this.search(text.clone(), cx),
+                InputEvent::PressEnter => this.find_next(false, cx),
+                _ => {}
+            },
+        )];
+
         let mut this = Self {
             focus_handle: cx.focus_handle(),
             delegate,
             col_groups: Vec::new(),
             fixed_cols: FixedCols::default(),
+            sort_keys: Vec::new(),
+            col_filters: Vec::new(),
+            row_groups: RowGroups::default(),
+            group_values: Vec::new(),
+            collapsed_groups: HashSet::new(),
+            list_item_heights: None,
+            list_item_tops: Vec::new(),
+            text_style: TextStyle::default(),
+            col_formats: Vec::new(),
+            footer_values: Vec::new(),
+            rows_dirty: true,
+            cached_rows_count: 0,
+            rows_version: 0,
             horizontal_scroll_handle: ScrollHandle::new(),
             vertical_scroll_handle: UniformListScrollHandle::new(),
             vertical_scrollbar_state: Rc::new(Cell::new(ScrollbarState::new())),
@@ -402,9 +976,22 @@ where
             selected_row: None,
             right_clicked_row: None,
             selected_col: None,
+            selected_group: None,
+            selection: TableSelection::default(),
+            drag_select_position: None,
             resizing_col: None,
+            editing_cell: None,
+            type_search: String::new(),
+            type_search_at: None,
+            find_input,
+            find_visible: false,
+            find: FindState::default(),
+            find_searching: false,
+            find_cells: None,
+            _find_task: Task::ready(()),
             bounds: Bounds::default(),
             fixed_head_cols_bounds: Bounds::default(),
+            fixed_right_head_cols_bounds: Bounds::default(),
             head_content_bounds: Bounds::default(),
             stripe: false,
             border: true,
@@ -412,7 +999,9 @@ where
             scrollbar_visible: Edges::all(true),
             visible_range: VisibleRangeState::default(),
             _load_more_task: Task::ready(()),
+            _autoscroll_task: Task::ready(()),
             _measure: Vec::new(),
+            _subscriptions,
         };
 
         this.prepare_col_groups(cx);
@@ -447,6 +1036,8 @@ where
     /// Set the size to the table.
     pub fn set_size(&mut self, size: Size, cx: &mut Context<Self>) {
         self.size = size;
+        // The default row height is changed.
+        self.rows_dirty = true;
         cx.notify();
     }
 
@@ -466,111 +1057,1373 @@ where
     }
 
     /// When we update columns or rows, we need to refresh the table.
+    ///
+    /// The column widths, fixed columns, filters, hidden columns and sort state are kept if the number
+    /// of columns is unchanged, otherwise they are reset and a [`TableEvent::FilterChanged`] is emitted
+    /// if there were active filters.
     pub fn refresh(&mut self, cx: &mut Context<Self>) {
+        self.rows_dirty = true;
         self.prepare_col_groups(cx);
     }
 
+    /// When the rows data (values, order or count) is changed by the delegate, we need to refresh the rows.
+    ///
+    /// The row groups, row heights, cell formats and footer values are cached until the rows are refreshed,
+    /// the cache is also rebuilt if the rows count is changed.
+    pub fn refresh_rows(&mut self, cx: &mut Context<Self>) {
+        self.rows_dirty = true;
+        cx.notify();
+    }
+
+    /// Rebuild the caches of the rows if the rows have been changed.
+    fn prepare_rows(&mut self, cx: &mut Context<Self>) {
+        let rows_count = self.delegate.rows_count(cx);
+        if !self.rows_dirty && rows_count == self.cached_rows_count {
+            return;
+        }
+
+        self.rows_dirty = false;
+        self.cached_rows_count = rows_count;
+        self.rows_version += 1;
+        self.find_cells = None;
+        self.rebuild_row_groups(cx);
+        self.update_list_item_heights(cx);
+        self.group_values = self.compute_group_values(cx);
+        self.footer_values = self.compute_footer_values(cx);
+        self.prepare_col_formats(cx);
+    }
+
     fn prepare_col_groups(&mut self, cx: &mut Context<Self>) {
-        self.col_groups = (0..self.delegate.cols_count(cx))
-            .map(|col_ix| ColGroup {
-                width: self.delegate.col_width(col_ix, cx),
-                padding: self.delegate.col_padding(col_ix, cx),
-                bounds: Bounds::default(),
-                sort: self.delegate.col_sort(col_ix, cx),
-                fixed: self.delegate.col_fixed(col_ix, cx),
+        let cols_count = self.delegate.cols_count(cx);
+        let keep_state = cols_count == self.col_groups.len();
+
+        let col_groups = (0..cols_count)
+            .map(|col_ix| {
+                let old_col = self.col_groups.get(col_ix).filter(|_| keep_state);
+                // Keep the sort of the column unless it is not sortable now.
+                let sort = self
+                    .delegate
+                    .col_sort(col_ix, cx)
+                    .map(|sort| old_col.and_then(|col| col.sort).unwrap_or(sort));
+                ColGroup {
+                    width: old_col
+                        .map_or_else(|| self.delegate.col_width(col_ix, cx), |col| col.width),
+                    padding: self.delegate.col_padding(col_ix, cx),
+                    bounds: Bounds::default(),
+                    sort,
+                    fixed: old_col
+                        .map_or_else(|| self.delegate.col_fixed(col_ix, cx), |col| col.fixed),
+                    hidden: old_col.map_or(false, |col| col.hidden),
+                }
             })
             .collect();
+        self.col_groups = col_groups;
+        self.update_fixed_cols();
+
+        if keep_state {
+            let col_groups = &self.col_groups;
+            self.sort_keys.retain(|&col_ix| {
+                matches!(
+                    col_groups[col_ix].sort,
+                    Some(ColSort::Ascending | ColSort::Descending)
+                )
+            });
+        } else {
+            let had_filters = self.col_filters.iter().any(Option::is_some);
+            self.col_filters = vec![None; cols_count];
+            if had_filters {
+                cx.emit(TableEvent::FilterChanged(vec![]));
+            }
+            self.sort_keys = self
+                .col_groups
+                .iter()
+                .enumerate()
+                .filter(|(_, col)| {
+                    matches!(col.sort, Some(ColSort::Ascending | ColSort::Descending))
+                })
+                .map(|(ix, _)| ix)
+                .collect();
+        }
+        cx.notify();
+    }
+
+    /// Update the number of the left and right fixed columns from the columns.
+    fn update_fixed_cols(&mut self) {
         self.fixed_cols.left = self
             .col_groups
             .iter()
             .filter(|col| col.fixed == Some(ColFixed::Left))
             .count();
-        cx.notify();
-    }
-
-    /// Scroll to the row at the given index.
-    pub fn scroll_to_row(&mut self, row_ix: usize, cx: &mut Context<Self>) {
-        self.vertical_scroll_handle
-            .scroll_to_item(row_ix, ScrollStrategy::Top);
-        cx.notify();
+        self.fixed_cols.right = self
+            .col_groups
+            .iter()
+            .filter(|col| col.fixed == Some(ColFixed::Right))
+            .count();
     }
 
-    // Scroll to the column at the given index.
-    // TODO: Fix scroll to selected col, this was not working after fixed col.
-    // pub fn scroll_to_col(&mut self, col_ix: usize, window: &mut Window, cx: &mut Context<Self>) {
-    //     self.horizontal_scroll_handle.scroll_to_item(col_ix);
-    //     cx.notify();
-    // }
+    /// Move the left fixed columns to the start and the right fixed columns to the end,
+    /// the fixed column that can not be moved (see `TableDelegate::can_move_col`) is unpinned.
+    fn move_fixed_cols_to_edges(&mut self, window: &mut Window, cx: &mut Context<Self>) {
+        let cols_count = self.col_groups.len();
 
-    /// Returns the selected row index.
-    pub fn selected_row(&self) -> Option<usize> {
-        self.selected_row
-    }
+        let mut left_end = 0;
+        for col_ix in 0..cols_count {
+            if self.col_groups[col_ix].fixed != Some(ColFixed::Left) {
+                continue;
+            }
+            if col_ix != left_end {
+                if !self.delegate.can_move_col(col_ix, cx) {
+                    self.col_groups[col_ix].fixed = None;
+                    continue;
+                }
+                self.move_col(col_ix, left_end, window, cx);
+            }
+            left_end += 1;
+        }
 
-    /// Sets the selected row to the given index.
-    pub fn set_selected_row(&mut self, row_ix: usize, cx: &mut Context<Self>) {
-        self.selection_state = SelectionState::Row;
-        self.right_clicked_row = None;
-        self.selected_row = Some(row_ix);
-        if let Some(row_ix) = self.selected_row {
-            self.vertical_scroll_handle
-                .scroll_to_item(row_ix, ScrollStrategy::Top);
+        let mut right_start = cols_count;
+        for col_ix in (left_end..cols_count).rev() {
+            if self.col_groups[col_ix].fixed != Some(ColFixed::Right) {
+                continue;
+            }
+            if col_ix != right_start - 1 {
+                if !self.delegate.can_move_col(col_ix, cx) {
+                    self.col_groups[col_ix].fixed = None;
+                    continue;
+                }
+                self.move_col(col_ix, right_start - 1, window, cx);
+            }
+            right_start -= 1;
         }
-        cx.emit(TableEvent::SelectRow(row_ix));
-        cx.notify();
+
+        self.update_fixed_cols();
     }
 
-    /// Returns the selected column index.
-    pub fn selected_col(&self) -> Option<usize> {
-        self.selected_col
+    /// Returns true if the column at the given index is hidden.
+    pub fn is_col_hidden(&self, col_ix: usize) -> bool {
+        self.col_groups.get(col_ix).map_or(false, |col| col.hidden)
     }
 
-    /// Sets the selected col to the given index.
-    pub fn set_selected_col(&mut self, col_ix: usize, cx: &mut Context<Self>) {
-        self.selection_state = SelectionState::Column;
-        self.selected_col = Some(col_ix);
-        if let Some(_col_ix) = self.selected_col {
-            // TODO: Fix scroll to selected col, this was not working after fixed col.
-            // if self.col_groups[col_ix].fixed.is_none() {
-            //     self.horizontal_scroll_handle.scroll_to_item(col_ix);
-            // }
+    /// Hide or show the column at the given index.
+    ///
+    /// The last visible column can not be hidden.
+    pub fn set_col_hidden(&mut self, col_ix: usize, hidden: bool, cx: &mut Context<Self>) {
+        let Some(col_group) = self.col_groups.get(col_ix) else {
+            return;
+        };
+        if col_group.hidden == hidden {
+            return;
         }
-        cx.emit(TableEvent::SelectCol(col_ix));
+        if hidden && self.col_groups.iter().filter(|col| !col.hidden).count() <= 1 {
+            return;
+        }
+
+        self.col_groups[col_ix].hidden = hidden;
+        if hidden {
+            self.col_groups[col_ix].bounds.size.width = px(0.);
+        }
+        // The formats of the hidden columns are not built.
+        self.rows_dirty = true;
+        cx.emit(TableEvent::ColHiddenChanged(col_ix, hidden));
         cx.notify();
     }
 
-    /// Clear the selection of the table.
+    fn action_toggle_col_visible(
+        &mut self,
+        action: &ToggleColVisible,
+        _: &mut Window,
+        cx: &mut Context<Self>,
+    ) {
+        let col_ix = action.0;
+        self.set_col_hidden(col_ix, !self.is_col_hidden(col_ix), cx);
+    }
+
+    /// Returns the visible column whose header contains the given position.
+    fn head_col_at(&self, position: Point<Pixels>) -> Option<usize> {
+        self.col_groups
+            .iter()
+            .position(|col| !col.hidden && col.bounds.contains(&position))
+    }
+
+    /// Pin the column at the given index to the left or right side, None to unpin.
+    ///
+    /// The column is moved to the end of the left fixed columns or the start of the right fixed columns,
+    /// so it requires `TableDelegate::can_move_col` if the column is not there.
+    pub fn set_col_fixed(
+        &mut self,
+        col_ix: usize,
+        fixed: Option<ColFixed>,
+        window: &mut Window,
+        cx: &mut Context<Self>,
+    ) {
+        let cols_count = self.col_groups.len();
+        let Some(col_group) = self.col_groups.get(col_ix) else {
+            return;
+        };
+        if col_group.fixed == fixed {
+            return;
+        }
+
+        let to_ix = match (col_group.fixed, fixed) {
+            (_, Some(ColFixed::Left)) => self.fixed_cols.left,
+            (_, Some(ColFixed::Right)) => cols_count - self.fixed_cols.right - 1,
+            (Some(ColFixed::Left), None) => self.fixed_cols.left - 1,
+            (Some(ColFixed::Right), None) => cols_count - self.fixed_cols.right,
+            (None, None) => col_ix,
+        };
+        if to_ix != col_ix {
+            if !self.delegate.can_move_col(col_ix, cx) {
+                return;
+            }
+            self.move_col(col_ix, to_ix, window, cx);
+        }
+
+        self.col_groups[to_ix].fixed = fixed;
+        self.update_fixed_cols();
+        cx.notify();
+    }
+
+    /// Reset the width of the column at the given index to `TableDelegate::col_width`.
+    pub fn reset_col_width(&mut self, col_ix: usize, cx: &mut Context<Self>) {
+        if col_ix >= self.col_groups.len() {
+            return;
+        }
+        let width = self.delegate.col_width(col_ix, cx);
+        if self.col_groups[col_ix].width == width {
+            return;
+        }
+
+        self.col_groups[col_ix].width = width;
+        let new_widths = self.col_groups.iter().map(|g| g.width).collect();
+        cx.emit(TableEvent::ColWidthsChanged(new_widths));
+        cx.notify();
+    }
+
+    fn action_sort_col(&mut self, action: &SortCol, window: &mut Window, cx: &mut Context<Self>) {
+        self.set_col_sort(action.0, action.1, window, cx);
+    }
+
+    fn action_fix_col(&mut self, action: &FixCol, window: &mut Window, cx: &mut Context<Self>) {
+        self.set_col_fixed(action.0, action.1, window, cx);
+    }
+
+    fn action_auto_fit_col(
+        &mut self,
+        action: &AutoFitCol,
+        window: &mut Window,
+        cx: &mut Context<Self>,
+    ) {
+        self.auto_fit_col(action.0, window, cx);
+    }
+
+    fn action_reset_col_width(
+        &mut self,
+        action: &ResetColWidth,
+        _: &mut Window,
+        cx: &mut Context<Self>,
+    ) {
+        self.reset_col_width(action.0, cx);
+    }
+
+    /// Build the context menu of the column header, with the built-in items of the column
+    /// and the items from `TableDelegate::header_context_menu`.
+    fn header_menu(
+        view: &Entity<Self>,
+        col_ix: usize,
+        menu: PopupMenu,
+        window: &mut Window,
+        cx: &mut Context<PopupMenu>,
+    ) -> PopupMenu {
+        let table = view.read(cx);
+        let Some(col_group) = table.col_groups.get(col_ix).copied() else {
+            return menu;
+        };
+        let can_resize = table.delegate.can_resize_col(col_ix, cx);
+        let can_move = table.delegate.can_move_col(col_ix, cx);
+        let can_hide = table.visible_cols().count() > 1;
+
+        let mut menu = menu;
+        if let Some(sort) = col_group.sort {
+            menu = menu
+                .menu_with_check(
+                    t!("Table.Sort Ascending"),
+                    sort == ColSort::Ascending,
+                    Box::new(SortCol(col_ix, ColSort::Ascending)),
+                )
+                .menu_with_check(
+                    t!("Table.Sort Descending"),
+                    sort == ColSort::Descending,
+                    Box::new(SortCol(col_ix, ColSort::Descending)),
+                );
+            if sort != ColSort::Default {
+                menu = menu.menu(
+                    t!("Table.Clear Sort"),
+                    Box::new(SortCol(col_ix, ColSort::Default)),
+                );
+            }
+            menu = menu.separator();
+        }
+        if can_move {
+            // Click the checked side to unpin the column.
+            let pin = |side: ColFixed| {
+                let fixed = (col_group.fixed != Some(side)).then_some(side);
+                Box::new(FixCol(col_ix, fixed))
+            };
+            menu = menu
+                .menu_with_check(
+                    t!("Table.Pin Left"),
+                    col_group.fixed == Some(ColFixed::Left),
+                    pin(ColFixed::Left),
+                )
+                .menu_with_check(
+                    t!("Table.Pin Right"),
+                    col_group.fixed == Some(ColFixed::Right),
+                    pin(ColFixed::Right),
+                )
+                .separator();
+        }
+        if can_resize {
+            menu = menu
+                .menu(t!("Table.Auto Fit Width"), Box::new(AutoFitCol(col_ix)))
+                .menu(t!("Table.Reset Width"), Box::new(ResetColWidth(col_ix)));
+        }
+        if can_hide {
+            menu = menu.menu(t!("Table.Hide Column"), Box::new(ToggleColVisible(col_ix)));
+        }
+        let menu = menu.separator().submenu(t!("Table.Columns"), window, cx, {
+            let view = view.clone();
+            move |menu, _, cx| view.read(cx).col_visibility_menu(menu, cx)
+        });
+
+        view.read(cx)
+            .delegate
+            .header_context_menu(col_ix, menu, window, cx)
+    }
+
+    /// Build the menu to hide or show the columns.
+    fn col_visibility_menu(&self, menu: PopupMenu, cx: &App) -> PopupMenu {
+        (0..self.col_groups.len()).fold(menu.scrollable().max_h(px(360.)), |menu, col_ix| {
+            menu.menu_with_check(
+                self.delegate.col_name(col_ix, cx),
+                !self.col_groups[col_ix].hidden,
+                Box::new(ToggleColVisible(col_ix)),
+            )
+        })
+    }
+
+    /// Dump the columns layout, that is the order, widths, hidden columns, sort and fixed columns.
+    ///
+    /// See also [`Table::load_layout`].
+    pub fn dump_layout(&self, cx: &App) -> TableLayoutState {
+        let cols = self
+            .col_groups
+            .iter()
+            .enumerate()
+            .map(|(col_ix, col)| ColLayoutState {
+                key: self.delegate.col_key(col_ix, cx).to_string(),
+                width: col.width,
+                hidden: col.hidden,
+                sort: col.sort,
+                fixed: col.fixed,
+            })
+            .collect();
+        let sort_keys = self
+            .sort_keys()
+            .into_iter()
+            .map(|(col_ix, _)| self.delegate.col_key(col_ix, cx).to_string())
+            .collect();
+
+        TableLayoutState { cols, sort_keys }
+    }
+
+    /// Load the columns layout from the state, the columns are matched by `TableDelegate::col_key`,
+    /// the columns not in the state keep their order after the loaded columns.
+    ///
+    /// The columns will be moved by `TableDelegate::move_col` if `TableDelegate::can_move_col` allows,
+    /// the left fixed columns are moved to the start and the right fixed columns to the end, a fixed
+    /// column that can not be moved there is unpinned.
+    ///
+    /// The sort will be performed by `TableDelegate::perform_multi_sort` if there is any sorted column,
+    /// or reset by `TableDelegate::perform_sort` if the table was sorted before.
+    ///
+    /// See also [`Table::dump_layout`].
+    pub fn load_layout(
+        &mut self,
+        state: TableLayoutState,
+        window: &mut Window,
+        cx: &mut Context<Self>,
+    ) {
+        let cols_count = self.col_groups.len();
+
+        let mut target_ix = 0;
+        for col in state.cols.iter() {
+            let Some(col_ix) = (target_ix..cols_count)
+                .find(|&ix| self.delegate.col_key(ix, cx).as_ref() == col.key)
+            else {
+                continue;
+            };
+            // The column that can not be moved keeps its position.
+            let col_ix = if self.delegate.can_move_col(col_ix, cx) {
+                self.move_col(col_ix, target_ix, window, cx);
+                target_ix
+            } else {
+                col_ix
+            };
+            if col_ix == target_ix {
+                target_ix += 1;
+            }
+
+            let min_width = self.delegate.col_min_width(col_ix, cx);
+            let max_width = self.delegate.col_max_width(col_ix, cx).max(min_width);
+            let col_group = &mut self.col_groups[col_ix];
+            col_group.width = col.width.clamp(min_width, max_width);
+            col_group.hidden = col.hidden;
+            col_group.fixed = col.fixed;
+            // Only the sortable column can be sorted.
+            if col_group.sort.is_some() {
+                col_group.sort = Some(col.sort.unwrap_or(ColSort::Default));
+            }
+        }
+
+        // Keep at least one column visible.
+        if self.col_groups.iter().all(|col| col.hidden) {
+            if let Some(col) = self.col_groups.first_mut() {
+                col.hidden = false;
+            }
+        }
+        self.move_fixed_cols_to_edges(window, cx);
+
+        let prev_primary_col_ix = self.sort_keys.first().copied();
+        self.sort_keys = state
+            .sort_keys
+            .iter()
+            .filter_map(|key| {
+                (0..cols_count).find(|&ix| self.delegate.col_key(ix, cx).as_ref() == key)
+            })
+            .filter(|&ix| {
+                matches!(
+                    self.col_groups[ix].sort,
+                    Some(ColSort::Ascending | ColSort::Descending)
+                )
+            })
+            .collect();
+        if let Some(col_ix) = self.sort_keys.first().copied().or(prev_primary_col_ix) {
+            self.perform_sort_keys(col_ix, window, cx);
+        }
+
+        let new_widths = self.col_groups.iter().map(|g| g.width).collect();
+        cx.emit(TableEvent::ColWidthsChanged(new_widths));
+        cx.notify();
+    }
+
+    /// Returns the active filters of (col_ix, filter).
+    pub fn filters(&self) -> Vec<(usize, ColFilter)> {
+        self.col_filters
+            .iter()
+            .enumerate()
+            .filter_map(|(col_ix, filter)| filter.clone().map(|filter| (col_ix, filter)))
+            .collect()
+    }
+
+    /// Set the filter of the column at the given index, None to clear the filter.
+    pub fn set_col_filter(
+        &mut self,
+        col_ix: usize,
+        filter: Option<ColFilter>,
+        window: &mut Window,
+        cx: &mut Context<Self>,
+    ) {
+        let Some(col_filter) = self.col_filters.get_mut(col_ix) else {
+            return;
+        };
+        if *col_filter == filter {
+            return;
+        }
+
+        *col_filter = filter;
+        self.perform_filter(window, cx);
+    }
+
+    /// Clear the filters of all columns.
+    pub fn clear_filters(&mut self, window: &mut Window, cx: &mut Context<Self>) {
+        if self.col_filters.iter().all(|filter| filter.is_none()) {
+            return;
+        }
+
+        self.col_filters
+            .iter_mut()
+            .for_each(|filter| *filter = None);
+        self.perform_filter(window, cx);
+    }
+
+    fn perform_filter(&mut self, window: &mut Window, cx: &mut Context<Self>) {
+        let filters = self.filters();
+        self.delegate.perform_filter(&filters, window, cx);
+        self.rows_dirty = true;
+        cx.emit(TableEvent::FilterChanged(filters));
+        cx.notify();
+    }
+
+    /// Returns the active sort keys of (col_ix, sort), the first one is the primary key.
+    pub fn sort_keys(&self) -> Vec<(usize, ColSort)> {
+        self.sort_keys
+            .iter()
+            .filter_map(|&col_ix| {
+                self.col_groups
+                    .get(col_ix)
+                    .and_then(|col| col.sort)
+                    .map(|sort| (col_ix, sort))
+            })
+            .collect()
+    }
+
+    /// Scroll to the row at the given index.
+    pub fn scroll_to_row(&mut self, row_ix: usize, cx: &mut Context<Self>) {
+        self.scroll_to_item(row_ix, cx);
+        cx.notify();
+    }
+
+    /// Scroll the body list to the row at the given index, the frozen rows are always visible.
+    fn scroll_to_item(&self, row_ix: usize, cx: &App) {
+        let frozen_rows_count = self.delegate.frozen_rows_count(cx);
+        if row_ix < frozen_rows_count {
+            return;
+        }
+
+        let list_ix = if self.row_groups.is_grouped() {
+            let Some(ix) = self.row_groups.position_of_row(row_ix) else {
+                return;
+            };
+            ix
+        } else {
+            row_ix - frozen_rows_count
+        };
+        self.scroll_to_list_item(list_ix);
+    }
+
+    /// Scroll the body list to the item at the given index.
+    fn scroll_to_list_item(&self, ix: usize) {
+        if self.list_item_heights.is_none() {
+            self.vertical_scroll_handle
+                .scroll_to_item(ix, ScrollStrategy::Top);
+            return;
+        }
+
+        // The variable height list is scrolled by the base scroll handle directly,
+        // only scroll when the item is out of the viewport.
+        let (top, height) = self.list_item_bounds(ix);
+        let state = self.vertical_scroll_handle.0.borrow();
+        let viewport_height = state.base_handle.bounds().size.height;
+        let mut offset = state.base_handle.offset();
+        if top < -offset.y {
+            offset.y = -top;
+        } else if top + height > -offset.y + viewport_height {
+            offset.y = -(top + height - viewport_height);
+        }
+        state.base_handle.set_offset(offset);
+    }
+
+    /// Returns the height of the row at the given index.
+    ///
+    /// The height of the rows in the body list is read from the cached list item heights,
+    /// the delegate is only asked for the other rows (e.g.: the frozen rows).
+    fn row_height_of(&self, row_ix: usize, cx: &App) -> Pixels {
+        let row_height = self.size.table_row_height();
+        if !self.delegate.has_row_heights(cx) {
+            return row_height;
+        }
+
+        match self.row_groups.position_of_row(row_ix) {
+            Some(ix) if self.list_item_heights.is_some() => self.list_item_bounds(ix).1,
+            // All the rows in the body list have the default height.
+            Some(_) => row_height,
+            None => self.delegate.row_height(row_ix, cx).unwrap_or(row_height),
+        }
+    }
+
+    /// Update the heights of the body list items and their tops, the heights are None if all rows use the default row height.
+    fn update_list_item_heights(&mut self, cx: &App) {
+        self.list_item_heights = None;
+        self.list_item_tops.clear();
+        if !self.delegate.has_row_heights(cx) {
+            return;
+        }
+
+        let row_height = self.size.table_row_height();
+        let heights = (0..self.row_groups.len())
+            .map(|ix| match self.row_groups.get(ix) {
+                Some(ListItem::Row(row_ix)) => {
+                    self.delegate.row_height(row_ix, cx).unwrap_or(row_height)
+                }
+                _ => row_height,
+            })
+            .collect::<Vec<_>>();
+        if heights.iter().all(|height| *height == row_height) {
+            return;
+        }
+
+        let mut top = px(0.);
+        self.list_item_tops = std::iter::once(top)
+            .chain(heights.iter().map(|height| {
+                top += *height;
+                top
+            }))
+            .collect();
+        self.list_item_heights = Some(Rc::new(
+            heights
+                .into_iter()
+                .map(|height| gpui::size(px(0.), height))
+                .collect(),
+        ));
+    }
+
+    /// Returns the top (relative to the list content) and the height of the body list item at the given index.
+    fn list_item_bounds(&self, ix: usize) -> (Pixels, Pixels) {
+        let row_height = self.size.table_row_height();
+        match self.list_item_tops.len().checked_sub(1) {
+            Some(len) if self.list_item_heights.is_some() => {
+                let top = self.list_item_tops[ix.min(len)];
+                let height = if ix < len {
+                    self.list_item_tops[ix + 1] - top
+                } else {
+                    row_height
+                };
+                (top, height)
+            }
+            _ => (row_height * ix as f32, row_height),
+        }
+    }
+
+    /// Returns the index of the body list item at the given offset relative to the list content.
+    ///
+    /// The index may be out of the list, the caller should clamp it.
+    fn list_item_at(&self, y: Pixels) -> usize {
+        match &self.list_item_heights {
+            Some(_) => self
+                .list_item_tops
+                .partition_point(|top| *top <= y)
+                .saturating_sub(1),
+            None => (y / self.size.table_row_height()).floor().max(0.) as usize,
+        }
+    }
+
+    /// Returns the total height of the body list items.
+    fn list_content_height(&self, cx: &App) -> Pixels {
+        match &self.list_item_heights {
+            Some(_) => self.list_item_tops.last().copied().unwrap_or(px(0.)),
+            None => self.size.table_row_height() * self.list_items_count(cx) as f32,
+        }
+    }
+
+    /// Returns the total height of the frozen rows.
+    fn frozen_rows_height(&self, cx: &App) -> Pixels {
+        let rows_count = self.delegate.rows_count(cx);
+        (0..self.delegate.frozen_rows_count(cx).min(rows_count)).fold(px(0.), |height, row_ix| {
+            height + self.row_height_of(row_ix, cx)
+        })
+    }
+
+    /// Rebuild the row groups by the group key of each row.
+    fn rebuild_row_groups(&mut self, cx: &App) {
+        let rows_count = self.delegate.rows_count(cx);
+        let frozen_rows_count = self.delegate.frozen_rows_count(cx).min(rows_count);
+        let rows = frozen_rows_count..rows_count;
+        self.row_groups = if self.delegate.has_row_groups(cx) {
+            RowGroups::build(
+                rows,
+                |row_ix| self.delegate.row_group(row_ix, cx),
+                &self.collapsed_groups,
+            )
+        } else {
+            RowGroups::new(rows)
+        };
+    }
+
+    /// Compute the subtotal of each column of each row group.
+    fn compute_group_values(&self, cx: &App) -> Vec<Vec<Option<f64>>> {
+        if !self.row_groups.is_grouped() {
+            return vec![];
+        }
+
+        let aggregates = (0..self.delegate.cols_count(cx))
+            .map(|col_ix| self.delegate.group_aggregate(col_ix, cx))
+            .collect::<Vec<_>>();
+        self.row_groups
+            .groups()
+            .iter()
+            .map(|group| {
+                aggregates
+                    .iter()
+                    .enumerate()
+                    .map(|(col_ix, aggregate)| {
+                        aggregate.and_then(|aggregate| {
+                            aggregate.compute(
+                                group.rows.clone().filter_map(|row_ix| {
+                                    self.delegate.cell_number(row_ix, col_ix, cx)
+                                }),
+                            )
+                        })
+                    })
+                    .collect()
+            })
+            .collect()
+    }
+
+    /// Returns the index of the first visible column, the group header label and the tree toggle are rendered in it.
+    fn first_visible_col(&self) -> usize {
+        self.col_groups
+            .iter()
+            .position(|col| !col.hidden)
+            .unwrap_or(0)
+    }
+
+    /// Returns the number of the items in the body list, that is the rows and group headers except the frozen rows.
+    fn list_items_count(&self, cx: &App) -> usize {
+        if self.row_groups.is_grouped() {
+            self.row_groups.len()
+        } else {
+            let rows_count = self.delegate.rows_count(cx);
+            rows_count - self.delegate.frozen_rows_count(cx).min(rows_count)
+        }
+    }
+
+    /// Returns true if the row group with the given key is collapsed.
+    pub fn is_group_collapsed(&self, key: &SharedString) -> bool {
+        self.collapsed_groups.contains(key)
+    }
+
+    /// Collapse or expand the row group with the given key.
+    pub fn set_group_collapsed(
+        &mut self,
+        key: impl Into<SharedString>,
+        collapsed: bool,
+        cx: &mut Context<Self>,
+    ) {
+        let key = key.into();
+        let changed = if collapsed {
+            self.collapsed_groups.insert(key.clone())
+        } else {
+            self.collapsed_groups.remove(&key)
+        };
+        if !changed {
+            return;
+        }
+
+        // Keep the selected group after rebuild, the group index is not changed.
+        self.rebuild_row_groups(cx);
+        self.update_list_item_heights(cx);
+        cx.emit(TableEvent::GroupToggled(key, collapsed));
+        cx.notify();
+    }
+
+    /// Toggle the row group with the given key.
+    pub fn toggle_group(&mut self, key: impl Into<SharedString>, cx: &mut Context<Self>) {
+        let key = key.into();
+        let collapsed = !self.is_group_collapsed(&key);
+        self.set_group_collapsed(key, collapsed, cx);
+    }
+
+    /// Expand or collapse the tree row at the given index.
+    ///
+    /// The selected row is kept, or moved to the collapsed row if it was in the collapsed children.
+    pub fn toggle_expand(&mut self, row_ix: usize, window: &mut Window, cx: &mut Context<Self>) {
+        if !self.delegate.row_has_children(row_ix, cx) {
+            return;
+        }
+        if !self.commit_edit(window, cx) {
+            return;
+        }
+
+        let old_rows_count = self.delegate.rows_count(cx);
+        self.delegate.toggle_expand(row_ix, window, cx);
+        self.rows_dirty = true;
+        let new_rows_count = self.delegate.rows_count(cx);
+
+        if let Some(selected_row) = self.selected_row.filter(|&ix| ix > row_ix) {
+            let selected_row = tree::selected_row_after_toggle(
+                selected_row,
+                row_ix,
+                old_rows_count,
+                new_rows_count,
+            );
+            self.set_selected_row(selected_row, cx);
+        }
+
+        cx.emit(TableEvent::RowExpandToggled(row_ix));
+        cx.notify();
+    }
+
+    /// Handle the left and right keys of the tree like a file explorer, returns false if not handled.
+    ///
+    /// - Left: collapse the expanded row, or select the parent row.
+    /// - Right: expand the collapsed row, or select the first child row.
+    fn tree_select_by_key(
+        &mut self,
+        expand: bool,
+        window: &mut Window,
+        cx: &mut Context<Self>,
+    ) -> bool {
+        if !self.delegate.is_tree(cx) || self.selection_state == SelectionState::Column {
+            return false;
+        }
+        let Some(row_ix) = self.selected_row else {
+            return false;
+        };
+
+        let action = tree::tree_key_action(
+            row_ix,
+            expand,
+            self.delegate.rows_count(cx),
+            self.delegate.row_has_children(row_ix, cx),
+            self.delegate.is_row_expanded(row_ix, cx),
+            |ix| self.delegate.row_depth(ix, cx),
+        );
+        match action {
+            TreeKeyAction::Toggle => self.toggle_expand(row_ix, window, cx),
+            TreeKeyAction::Select(ix) => self.set_selected_row(ix, cx),
+            TreeKeyAction::None => {}
+        }
+
+        true
+    }
+
+    /// Select the group header at the given group index.
+    fn select_group(&mut self, group_ix: usize, cx: &mut Context<Self>) {
+        self.selection_state = SelectionState::Row;
+        self.right_clicked_row = None;
+        self.selected_row = None;
+        self.selected_group = Some(group_ix);
+        if let Some(ix) = self.row_groups.position_of_group(group_ix) {
+            self.scroll_to_list_item(ix);
+        }
+        self.update_selection(|selection| selection.clear(), cx);
+        cx.notify();
+    }
+
+    /// Collapse or expand the selected group, returns false if there is no selected group.
+    fn set_selected_group_collapsed(&mut self, collapsed: bool, cx: &mut Context<Self>) -> bool {
+        let Some(key) = self
+            .selected_group
+            .and_then(|group_ix| self.row_groups.group(group_ix))
+            .map(|group| group.key.clone())
+        else {
+            return false;
+        };
+
+        self.set_group_collapsed(key, collapsed, cx);
+        true
+    }
+
+    /// Move the selection by the given delta of the rows and group headers in display order,
+    /// the rows in the collapsed groups are skipped.
+    fn select_list_item_by(&mut self, delta: isize, cx: &mut Context<Self>) {
+        let rows_count = self.delegate.rows_count(cx);
+        let frozen_rows_count = self.delegate.frozen_rows_count(cx).min(rows_count);
+        let total = frozen_rows_count + self.row_groups.len();
+        if total == 0 {
+            return;
+        }
+
+        let current = if let Some(group_ix) = self.selected_group {
+            self.row_groups.position_of_group(group_ix)
+        } else {
+            self.selected_row.and_then(|row_ix| {
+                if row_ix < frozen_rows_count {
+                    return Some(row_ix);
+                }
+
+                // The row in a collapsed group is at the position of the group header.
+                self.row_groups
+                    .position_of_row(row_ix)
+                    .or_else(|| {
+                        self.row_groups
+                            .group_of_row(row_ix)
+                            .and_then(|group_ix| self.row_groups.position_of_group(group_ix))
+                    })
+                    .map(|ix| ix + frozen_rows_count)
+            })
+        };
+        let can_loop = self.delegate.can_loop_select(cx);
+        let next = match current {
+            None if delta < 0 => total - 1,
+            None => 0,
+            Some(ix) => {
+                let next = ix as isize + delta;
+                if next < 0 {
+                    if can_loop {
+                        total - 1
+                    } else {
+                        0
+                    }
+                } else if next as usize >= total {
+                    if can_loop {
+                        0
+                    } else {
+                        total - 1
+                    }
+                } else {
+                    next as usize
+                }
+            }
+        };
+
+        if next < frozen_rows_count {
+            self.set_selected_row(next, cx);
+            return;
+        }
+
+        match self.row_groups.get(next - frozen_rows_count) {
+            Some(ListItem::Row(row_ix)) => self.set_selected_row(row_ix, cx),
+            Some(ListItem::Group(group_ix)) => self.select_group(group_ix, cx),
+            None => {}
+        }
+    }
+
+    /// Scroll horizontally to make the column at the given index visible.
+    ///
+    /// The fixed columns are always visible, so there is nothing to scroll.
+    pub fn scroll_to_col(&mut self, col_ix: usize, cx: &mut Context<Self>) {
+        let Some(col_group) = self.col_groups.get(col_ix) else {
+            return;
+        };
+        if col_group.fixed.is_some() || col_group.hidden {
+            return;
+        }
+
+        // The left of the column relative to the scrollable columns.
+        let left = self.col_groups[self.fixed_cols.left.min(col_ix)..col_ix]
+            .iter()
+            .filter(|col| !col.hidden)
+            .fold(px(0.), |left, col| left + col.width);
+        let right = left + col_group.width;
+        let viewport_width = self.horizontal_scroll_handle.bounds().size.width;
+
+        let mut offset = self.horizontal_scroll_handle.offset();
+        if left < -offset.x {
+            offset.x = -left;
+        } else if right > -offset.x + viewport_width {
+            offset.x = -(right - viewport_width).min(left);
+        } else {
+            return;
+        }
+        self.horizontal_scroll_handle.set_offset(offset);
+        cx.notify();
+    }
+
+    /// Returns the selected row index.
+    pub fn selected_row(&self) -> Option<usize> {
+        self.selected_row
+    }
+
+    /// Sets the selected row to the given index.
+    pub fn set_selected_row(&mut self, row_ix: usize, cx: &mut Context<Self>) {
+        self.selection_state = SelectionState::Row;
+        self.right_clicked_row = None;
+        self.selected_row = Some(row_ix);
+        self.selected_group = None;
+        if let Some(row_ix) = self.selected_row {
+            self.scroll_to_item(row_ix, cx);
+        }
+        let col_ix = self.selected_col.unwrap_or(0);
+        self.update_selection(
+            |selection| selection.select(CellPos::new(row_ix, col_ix)),
+            cx,
+        );
+        cx.emit(TableEvent::SelectRow(row_ix));
+        cx.notify();
+    }
+
+    /// Returns the selected column index.
+    pub fn selected_col(&self) -> Option<usize> {
+        self.selected_col
+    }
+
+    /// Sets the selected col to the given index.
+    pub fn set_selected_col(&mut self, col_ix: usize, cx: &mut Context<Self>) {
+        self.selection_state = SelectionState::Column;
+        self.selected_col = Some(col_ix);
+        self.scroll_to_col(col_ix, cx);
+        let row_ix = self.selected_row.unwrap_or(0);
+        self.update_selection(
+            |selection| selection.select(CellPos::new(row_ix, col_ix)),
+            cx,
+        );
+        cx.emit(TableEvent::SelectCol(col_ix));
+        cx.notify();
+    }
+
+    /// Clear the selection of the table.
     pub fn clear_selection(&mut self, cx: &mut Context<Self>) {
         self.selection_state = SelectionState::Row;
         self.selected_row = None;
         self.selected_col = None;
+        self.selected_group = None;
+        self.update_selection(|selection| selection.clear(), cx);
+        cx.notify();
+    }
+
+    /// Returns the cell range selection of the table.
+    pub fn selection(&self) -> &TableSelection {
+        &self.selection
+    }
+
+    /// Select the given range of cells.
+    pub fn select_range(&mut self, range: CellRange, cx: &mut Context<Self>) {
+        self.selection_state = SelectionState::Cell;
+        self.selected_row = Some(range.focus.row);
+        self.selected_col = Some(range.focus.col);
+        self.selected_group = None;
+        self.update_selection(|selection| selection.select_range(range), cx);
+        cx.notify();
+    }
+
+    /// Update the selection by the given function, emits `SelectionChanged` if it has been changed.
+    fn update_selection(&mut self, f: impl FnOnce(&mut TableSelection), cx: &mut Context<Self>) {
+        let old_selection = self.selection.clone();
+        f(&mut self.selection);
+        if self.selection != old_selection {
+            cx.emit(TableEvent::SelectionChanged(self.selection.clone()));
+        }
+    }
+
+    /// Extend the active selection range to the given cell.
+    fn extend_selection_to(&mut self, pos: CellPos, cx: &mut Context<Self>) {
+        self.selection_state = SelectionState::Cell;
+        self.right_clicked_row = None;
+        self.selected_row = Some(pos.row);
+        self.selected_col = Some(pos.col);
+        self.selected_group = None;
+        self.scroll_to_item(pos.row, cx);
+        self.update_selection(|selection| selection.extend_to(pos), cx);
         cx.notify();
     }
 
+    /// Add the given cell as a new disjoint selection range.
+    fn add_selection(&mut self, pos: CellPos, cx: &mut Context<Self>) {
+        self.selection_state = SelectionState::Cell;
+        self.right_clicked_row = None;
+        self.selected_row = Some(pos.row);
+        self.selected_col = Some(pos.col);
+        self.selected_group = None;
+        self.update_selection(|selection| selection.add(pos), cx);
+        cx.notify();
+    }
+
+    /// Returns the cell at the given window position, the position out of the table
+    /// will be clamped to the nearest cell.
+    fn cell_at_position(&self, position: Point<Pixels>, cx: &App) -> Option<CellPos> {
+        let rows_count = self.delegate.rows_count(cx);
+        if rows_count == 0 || self.col_groups.is_empty() {
+            return None;
+        }
+
+        let (body_bounds, offset) = {
+            let state = self.vertical_scroll_handle.0.borrow();
+            (state.base_handle.bounds(), state.base_handle.offset())
+        };
+        let frozen_rows_count = self.delegate.frozen_rows_count(cx).min(rows_count);
+        let row_ix = if frozen_rows_count > 0 && position.y < body_bounds.top() {
+            // The frozen rows are above the body list.
+            let mut bottom = body_bounds.top() - self.frozen_rows_height(cx);
+            (0..frozen_rows_count)
+                .find(|&row_ix| {
+                    bottom += self.row_height_of(row_ix, cx);
+                    position.y < bottom
+                })
+                .unwrap_or(frozen_rows_count - 1)
+        } else {
+            let list_ix = self.list_item_at(position.y - body_bounds.top() - offset.y);
+            if self.row_groups.is_grouped() {
+                // The group header is mapped to the first row of the group.
+                let list_ix = list_ix.min(self.row_groups.len().saturating_sub(1));
+                self.row_groups
+                    .get(list_ix)
+                    .map_or(rows_count - 1, |item| self.row_groups.row_of_item(item))
+            } else {
+                (frozen_rows_count + list_ix).min(rows_count - 1)
+            }
+        };
+
+        let left_cols_count = self.fixed_cols.left;
+        let right_cols_count = self.fixed_cols.right;
+        let cols_count = self.col_groups.len();
+        let cols = if left_cols_count > 0 && position.x < self.fixed_head_cols_bounds.right() {
+            0..left_cols_count
+        } else if right_cols_count > 0 && position.x >= self.fixed_right_head_cols_bounds.left() {
+            cols_count - right_cols_count..cols_count
+        } else {
+            left_cols_count..cols_count - right_cols_count
+        };
+        let col_ix = cols
+            .clone()
+            .filter(|&ix| !self.col_groups[ix].hidden)
+            .find(|&ix| position.x < self.col_groups[ix].bounds.right())
+            .unwrap_or(cols.end.saturating_sub(1));
+
+        Some(CellPos::new(row_ix, col_ix))
+    }
+
+    /// Build the conditional formatting of the columns by `TableDelegate::col_format_rules`.
+    fn prepare_col_formats(&mut self, cx: &App) {
+        let rows_count = self.delegate.rows_count(cx);
+        self.col_formats = (0..self.col_groups.len())
+            .map(|col_ix| {
+                let rules = self.delegate.col_format_rules(col_ix, cx);
+                if rules.is_empty() || self.is_col_hidden(col_ix) {
+                    return None;
+                }
+
+                let values = if ColFormat::needs_values(&rules) {
+                    (0..rows_count)
+                        .filter_map(|row_ix| self.delegate.cell_number(row_ix, col_ix, cx))
+                        .collect()
+                } else {
+                    vec![]
+                };
+                Some(ColFormat::new(rules, values))
+            })
+            .collect();
+    }
+
+    /// Returns the conditional format of the cell, None if there is no rule or the cell is not a number.
+    fn cell_format(&self, row_ix: usize, col_ix: usize, cx: &App) -> Option<CellFormat> {
+        let col_format = self.col_formats.get(col_ix)?.as_ref()?;
+        let value = self.delegate.cell_number(row_ix, col_ix, cx)?;
+        Some(col_format.format(value))
+    }
+
+    /// Compute the aggregate of the column at the given index over all rows,
+    /// the values are from `TableDelegate::cell_number`.
+    pub fn aggregate(&self, col_ix: usize, aggregate: Aggregate, cx: &App) -> Option<f64> {
+        let rows_count = self.delegate.rows_count(cx);
+        aggregate.compute(
+            (0..rows_count).filter_map(|row_ix| self.delegate.cell_number(row_ix, col_ix, cx)),
+        )
+    }
+
+    /// Compute the footer aggregate value of each column.
+    fn compute_footer_values(&self, cx: &App) -> Vec<Option<f64>> {
+        if !self.delegate.has_footer(cx) {
+            return vec![];
+        }
+
+        (0..self.delegate.cols_count(cx))
+            .map(|col_ix| {
+                self.delegate
+                    .footer_aggregate(col_ix, cx)
+                    .and_then(|aggregate| self.aggregate(col_ix, aggregate, cx))
+            })
+            .collect()
+    }
+
     /// Returns the visible range of the rows and columns.
     pub fn visible_range(&self) -> &VisibleRangeState {
         &self.visible_range
     }
 
+    /// Returns the (row_ix, col_ix) of the cell that is being edited.
+    pub fn editing_cell(&self) -> Option<(usize, usize)> {
+        self.editing_cell
+    }
+
+    /// Start editing the cell at the given row and column.
+    ///
+    /// If there is another cell in edit mode, it will be committed first.
+    /// Returns false if the cell can not be edited.
+    pub fn edit_cell(
+        &mut self,
+        row_ix: usize,
+        col_ix: usize,
+        window: &mut Window,
+        cx: &mut Context<Self>,
+    ) -> bool {
+        if !self.delegate.can_edit_cell(row_ix, col_ix, cx) {
+            return false;
+        }
+        if self.editing_cell == Some((row_ix, col_ix)) {
+            return true;
+        }
+        if !self.commit_edit(window, cx) {
+            return false;
+        }
+
+        self.select_cell(row_ix, col_ix, cx);
+        self.editing_cell = Some((row_ix, col_ix));
+        self.delegate.start_cell_edit(row_ix, col_ix, window, cx);
+        cx.notify();
+        true
+    }
+
+    /// Commit the cell in edit mode.
+    ///
+    /// Returns false if the delegate rejected the value, the cell will keep in edit mode.
+    pub fn commit_edit(&mut self, window: &mut Window, cx: &mut Context<Self>) -> bool {
+        let Some((row_ix, col_ix)) = self.editing_cell else {
+            return true;
+        };
+
+        if !self.delegate.commit_cell_edit(row_ix, col_ix, window, cx) {
+            return false;
+        }
+
+        self.editing_cell = None;
+        self.rows_dirty = true;
+        self.focus_handle.focus(window);
+        cx.emit(TableEvent::CellEdited(row_ix, col_ix));
+        cx.notify();
+        true
+    }
+
+    /// Cancel the cell in edit mode, the edited value will be discarded.
+    pub fn cancel_edit(&mut self, window: &mut Window, cx: &mut Context<Self>) {
+        let Some((row_ix, col_ix)) = self.editing_cell.take() else {
+            return;
+        };
+
+        self.delegate.cancel_cell_edit(row_ix, col_ix, window, cx);
+        self.focus_handle.focus(window);
+        cx.notify();
+    }
+
+    /// Select the row and column of the given cell.
+    fn select_cell(&mut self, row_ix: usize, col_ix: usize, cx: &mut Context<Self>) {
+        self.selected_col = Some(col_ix);
+        self.set_selected_row(row_ix, cx);
+        self.scroll_to_col(col_ix, cx);
+    }
+
     fn on_row_click(
         &mut self,
         ev: &MouseDownEvent,
         row_ix: usize,
-        _: &mut Window,
+        window: &mut Window,
         cx: &mut Context<Self>,
     ) {
         if ev.button == MouseButton::Right {
             self.right_clicked_row = Some(row_ix);
         } else {
-            self.set_selected_row(row_ix, cx);
+            if !self.commit_edit(window, cx) {
+                return;
+            }
+
+            let col_ix = self
+                .cell_at_position(ev.position, cx)
+                .map_or(self.selected_col.unwrap_or(0), |pos| pos.col);
+            let pos = CellPos::new(row_ix, col_ix);
+
+            if ev.modifiers.shift {
+                self.extend_selection_to(pos, cx);
+            } else if is_multi_select_modifier(ev) {
+                self.add_selection(pos, cx);
+            } else {
+                self.selected_col = Some(col_ix);
+                self.set_selected_row(row_ix, cx);
+
+                if ev.click_count == 2 {
+                    cx.emit(TableEvent::DoubleClickedRow(row_ix));
+                }
+            }
+        }
+    }
+
+    fn on_drag_select_move(
+        &mut self,
+        e: &DragMoveEvent<SelectCells>,
+        window: &mut Window,
+        cx: &mut Context<Self>,
+    ) {
+        if e.drag(cx).0 != cx.entity_id() {
+            return;
+        }
+
+        let is_started = self.drag_select_position.is_some();
+        self.drag_select_position = Some(e.event.position);
+        if let Some(pos) = self.cell_at_position(e.event.position, cx) {
+            if self.selection.focus() != Some(pos) {
+                self.extend_selection_to(pos, cx);
+            }
+        }
 
-            if ev.click_count == 2 {
-                cx.emit(TableEvent::DoubleClickedRow(row_ix));
+        if !is_started {
+            self._autoscroll_task = cx.spawn_in(window, |view, mut cx| async move {
+                loop {
+                    cx.background_executor()
+                        .timer(Duration::from_millis(16))
+                        .await;
+                    let keep_scrolling = view
+                        .update(&mut cx, |view, cx| view.autoscroll_for_drag_select(cx))
+                        .unwrap_or(false);
+                    if !keep_scrolling {
+                        break;
+                    }
+                }
+            });
+        }
+    }
+
+    fn on_drag_select_end(&mut self, _: &MouseUpEvent, _: &mut Window, cx: &mut Context<Self>) {
+        if self.drag_select_position.take().is_some() {
+            self._autoscroll_task = Task::ready(());
+            cx.notify();
+        }
+    }
+
+    /// Scroll the table when selecting cells by dragging out of the table bounds.
+    ///
+    /// Returns false if the drag selection has been ended.
+    fn autoscroll_for_drag_select(&mut self, cx: &mut Context<Self>) -> bool {
+        let Some(position) = self.drag_select_position else {
+            return false;
+        };
+
+        let row_height = self.size.table_row_height();
+        {
+            let state = self.vertical_scroll_handle.0.borrow();
+            let body_bounds = state.base_handle.bounds();
+            let mut offset = state.base_handle.offset();
+            let max_offset_y = (self.list_content_height(cx) - body_bounds.size.height).max(px(0.));
+
+            // Only scroll up when the mouse is above the frozen rows.
+            if position.y < body_bounds.top() - self.frozen_rows_height(cx) {
+                offset.y = (offset.y + row_height).min(px(0.));
+            } else if position.y > body_bounds.bottom() {
+                offset.y = (offset.y - row_height).max(-max_offset_y);
+            }
+            state.base_handle.set_offset(offset);
+        }
+
+        let mut offset = self.horizontal_scroll_handle.offset();
+        let max_offset_x =
+            (self.head_content_bounds().size.width - self.bounds.size.width).max(px(0.));
+        if position.x < self.bounds.left() {
+            offset.x = (offset.x + px(20.)).min(px(0.));
+        } else if position.x > self.bounds.right() {
+            offset.x = (offset.x - px(20.)).max(-max_offset_x);
+        }
+        self.horizontal_scroll_handle.set_offset(offset);
+
+        if let Some(pos) = self.cell_at_position(position, cx) {
+            if self.selection.focus() != Some(pos) {
+                self.extend_selection_to(pos, cx);
             }
         }
+        cx.notify();
+        true
     }
 
-    fn on_col_head_click(&mut self, col_ix: usize, _: &mut Window, cx: &mut Context<Self>) {
+    fn on_col_head_click(
+        &mut self,
+        ev: &MouseDownEvent,
+        col_ix: usize,
+        window: &mut Window,
+        cx: &mut Context<Self>,
+    ) {
+        // Shift+click to add the column to the sort keys.
+        if ev.modifiers.shift && self.col_groups.get(col_ix).and_then(|g| g.sort).is_some() {
+            self.perform_multi_sort(col_ix, window, cx);
+            return;
+        }
+
         if !self.delegate.can_select_col(col_ix, cx) {
             return;
         }
@@ -578,11 +2431,320 @@ where
         self.set_selected_col(col_ix, cx)
     }
 
-    fn action_cancel(&mut self, _: &Cancel, _: &mut Window, cx: &mut Context<Self>) {
+    fn action_cancel(&mut self, _: &Cancel, window: &mut Window, cx: &mut Context<Self>) {
+        if self.editing_cell.is_some() {
+            self.cancel_edit(window, cx);
+            return;
+        }
+
         self.clear_selection(cx);
     }
 
+    fn action_select_all(&mut self, _: &SelectAll, _: &mut Window, cx: &mut Context<Self>) {
+        let rows_count = self.delegate.rows_count(cx);
+        let cols_count = self.delegate.cols_count(cx);
+        if rows_count == 0 || cols_count == 0 {
+            return;
+        }
+
+        self.selection_state = SelectionState::Cell;
+        self.update_selection(|selection| selection.select_all(rows_count, cols_count), cx);
+        cx.notify();
+    }
+
+    /// Extend the selection by the given rows and columns delta.
+    fn extend_selection_by(&mut self, rows: isize, cols: isize, cx: &mut Context<Self>) {
+        let rows_count = self.delegate.rows_count(cx);
+        let cols_count = self.delegate.cols_count(cx);
+        if rows_count == 0 || cols_count == 0 {
+            return;
+        }
+
+        let focus = self.selection.focus().unwrap_or_default();
+        let row_ix = focus.row.saturating_add_signed(rows).min(rows_count - 1);
+        let col_ix = match cols {
+            0 => focus.col,
+            _ => self
+                .adjacent_visible_col(focus.col, cols > 0)
+                .unwrap_or(focus.col),
+        };
+        self.extend_selection_to(CellPos::new(row_ix, col_ix), cx);
+    }
+
+    fn action_extend_selection_up(
+        &mut self,
+        _: &ExtendSelectionUp,
+        _: &mut Window,
+        cx: &mut Context<Self>,
+    ) {
+        self.extend_selection_by(-1, 0, cx);
+    }
+
+    fn action_extend_selection_down(
+        &mut self,
+        _: &ExtendSelectionDown,
+        _: &mut Window,
+        cx: &mut Context<Self>,
+    ) {
+        self.extend_selection_by(1, 0, cx);
+    }
+
+    fn action_extend_selection_left(
+        &mut self,
+        _: &ExtendSelectionLeft,
+        _: &mut Window,
+        cx: &mut Context<Self>,
+    ) {
+        self.extend_selection_by(0, -1, cx);
+    }
+
+    fn action_extend_selection_right(
+        &mut self,
+        _: &ExtendSelectionRight,
+        _: &mut Window,
+        cx: &mut Context<Self>,
+    ) {
+        self.extend_selection_by(0, 1, cx);
+    }
+
+    /// Returns the rows and the visible columns of the cells to copy.
+    ///
+    /// When the selection state is `Row` or `Column`, the entire row or column is copied,
+    /// otherwise the bounding range of the selected cells is copied.
+    fn copy_range(&self, cx: &App) -> Option<(Range<usize>, Vec<usize>)> {
+        let rows_count = self.delegate.rows_count(cx);
+
+        let (rows, cols) = match self.selection_state {
+            SelectionState::Row => self
+                .selected_row
+                .map(|row_ix| (row_ix..row_ix + 1, 0..self.col_groups.len()))?,
+            SelectionState::Column => self
+                .selected_col
+                .map(|col_ix| (0..rows_count, col_ix..col_ix + 1))?,
+            SelectionState::Cell => self
+                .selection
+                .bounding_range()
+                .map(|range| (range.rows(), range.cols()))?,
+        };
+        let cols = self.visible_cols().filter(|ix| cols.contains(ix)).collect();
+        Some((rows, cols))
+    }
+
+    fn action_copy(&mut self, _: &Copy, _: &mut Window, cx: &mut Context<Self>) {
+        let Some((rows, cols)) = self.copy_range(cx) else {
+            return;
+        };
+
+        let is_cell_selection = self.selection_state == SelectionState::Cell;
+        let grid = rows
+            .map(|row_ix| {
+                cols.iter()
+                    .map(|&col_ix| {
+                        // The cells out of the disjoint ranges are copied as empty.
+                        if is_cell_selection
+                            && !self.selection.contains(CellPos::new(row_ix, col_ix))
+                        {
+                            return String::new();
+                        }
+
+                        self.delegate.cell_text(row_ix, col_ix, cx).to_string()
+                    })
+                    .collect()
+            })
+            .collect::<Vec<Vec<String>>>();
+
+        // The TSV text is the plain text of the HTML table, use the GPUI clipboard if the system
+        // clipboard is not available.
+        let text = clipboard::to_tsv(&grid);
+        if !clipboard::write_html(&clipboard::to_html(&grid), &text, cx) {
+            cx.write_to_clipboard(ClipboardItem::new_string(text));
+        }
+    }
+
+    /// Returns the row indices to export.
+    fn export_row_indices(&self, rows: ExportRows, cx: &App) -> Vec<usize> {
+        let rows_count = self.delegate.rows_count(cx);
+        match rows {
+            ExportRows::All => (0..rows_count).collect(),
+            ExportRows::Visible => {
+                let frozen_rows_count = self.delegate.frozen_rows_count(cx).min(rows_count);
+                let visible_rows = self.visible_range.rows.start.max(frozen_rows_count)
+                    ..self.visible_range.rows.end.min(rows_count);
+                (0..frozen_rows_count).chain(visible_rows).collect()
+            }
+            ExportRows::Selected => match self.selection_state {
+                SelectionState::Row => self.selected_row.into_iter().collect(),
+                SelectionState::Column => (0..rows_count).collect(),
+                SelectionState::Cell => {
+                    let mut rows = self
+                        .selection
+                        .ranges()
+                        .iter()
+                        .flat_map(|range| range.rows())
+                        .filter(|&row_ix| row_ix < rows_count)
+                        .collect::<Vec<_>>();
+                    rows.sort_unstable();
+                    rows.dedup();
+                    rows
+                }
+            },
+        }
+    }
+
+    /// Export the rows of the visible (not hidden) columns to the writer in the given format,
+    /// the values are from `TableDelegate::cell_value`.
+    pub fn export(
+        &self,
+        writer: &mut impl io::Write,
+        format: ExportFormat,
+        rows: ExportRows,
+        cx: &App,
+    ) -> io::Result<()> {
+        let col_ixs = (0..self.col_groups.len())
+            .filter(|&col_ix| !self.col_groups[col_ix].hidden)
+            .collect::<Vec<_>>();
+        let cols = col_ixs
+            .iter()
+            .map(|&col_ix| {
+                (
+                    self.delegate.col_key(col_ix, cx),
+                    self.delegate.col_name(col_ix, cx),
+                )
+            })
+            .collect::<Vec<_>>();
+        let rows = self.export_row_indices(rows, cx).into_iter().map(|row_ix| {
+            col_ixs
+                .iter()
+                .map(|&col_ix| self.delegate.cell_value(row_ix, col_ix, cx))
+                .collect::<Vec<_>>()
+        });
+
+        export::write_export(writer, format, &cols, rows)
+    }
+
+    fn action_paste(&mut self, _: &Paste, window: &mut Window, cx: &mut Context<Self>) {
+        let Some(text) = cx.read_from_clipboard().and_then(|item| item.text()) else {
+            return;
+        };
+        let grid = clipboard::parse_grid(&text);
+        if grid.is_empty() {
+            return;
+        }
+
+        let origin = match self.selection_state {
+            SelectionState::Cell => self.selection.bounding_range().map(|range| range.start()),
+            _ => self.selection.focus(),
+        };
+        let Some(origin) = origin else {
+            return;
+        };
+
+        let rows = grid.len();
+        let cols = grid.iter().map(|row| row.len()).max().unwrap_or(1).max(1);
+        self.delegate.paste_cells(origin, grid, window, cx);
+        self.rows_dirty = true;
+
+        // Select the pasted cells.
+        let rows_count = self.delegate.rows_count(cx);
+        let cols_count = self.delegate.cols_count(cx);
+        if rows_count > 0 && cols_count > 0 {
+            let focus = CellPos::new(
+                (origin.row + rows - 1).min(rows_count - 1),
+                (origin.col + cols - 1).min(cols_count - 1),
+            );
+            self.select_range(CellRange::new(origin, focus), cx);
+        }
+    }
+
+    fn action_edit_cell(&mut self, _: &EditCell, window: &mut Window, cx: &mut Context<Self>) {
+        // Commit the editing cell and move to the cell below.
+        if let Some((row_ix, col_ix)) = self.editing_cell {
+            if self.commit_edit(window, cx) && row_ix + 1 < self.delegate.rows_count(cx) {
+                self.select_cell(row_ix + 1, col_ix, cx);
+            }
+            return;
+        }
+
+        // Toggle the selected group.
+        if let Some(key) = self
+            .selected_group
+            .and_then(|group_ix| self.row_groups.group(group_ix))
+            .map(|group| group.key.clone())
+        {
+            self.toggle_group(key, cx);
+            return;
+        }
+
+        let Some(pos) = self.selection.focus() else {
+            return;
+        };
+        self.edit_cell(pos.row, pos.col, window, cx);
+    }
+
+    fn action_select_prev_cell(
+        &mut self,
+        _: &SelectPrevCell,
+        window: &mut Window,
+        cx: &mut Context<Self>,
+    ) {
+        let Some(CellPos {
+            row: row_ix,
+            col: col_ix,
+        }) = self
+            .editing_cell
+            .map(CellPos::from)
+            .or(self.selection.focus())
+        else {
+            return;
+        };
+        if !self.commit_edit(window, cx) {
+            return;
+        }
+
+        if let Some(col_ix) = self.adjacent_visible_col(col_ix, false) {
+            self.select_cell(row_ix, col_ix, cx);
+        } else if row_ix > 0 {
+            if let Some(col_ix) = self.visible_cols().next_back() {
+                self.select_cell(row_ix - 1, col_ix, cx);
+            }
+        }
+    }
+
+    fn action_select_next_cell(
+        &mut self,
+        _: &SelectNextCell,
+        window: &mut Window,
+        cx: &mut Context<Self>,
+    ) {
+        let Some(CellPos {
+            row: row_ix,
+            col: col_ix,
+        }) = self
+            .editing_cell
+            .map(CellPos::from)
+            .or(self.selection.focus())
+        else {
+            return;
+        };
+        if !self.commit_edit(window, cx) {
+            return;
+        }
+
+        if let Some(col_ix) = self.adjacent_visible_col(col_ix, true) {
+            self.select_cell(row_ix, col_ix, cx);
+        } else if row_ix + 1 < self.delegate.rows_count(cx) {
+            if let Some(col_ix) = self.visible_cols().next() {
+                self.select_cell(row_ix + 1, col_ix, cx);
+            }
+        }
+    }
+
     fn action_select_prev(&mut self, _: &SelectPrev, _: &mut Window, cx: &mut Context<Self>) {
+        if self.row_groups.is_grouped() {
+            self.select_list_item_by(-1, cx);
+            return;
+        }
+
         let mut selected_row = self.selected_row.unwrap_or(0);
         let rows_count = self.delegate.rows_count(cx);
         if selected_row > 0 {
@@ -597,6 +2759,11 @@ where
     }
 
     fn action_select_next(&mut self, _: &SelectNext, _: &mut Window, cx: &mut Context<Self>) {
+        if self.row_groups.is_grouped() {
+            self.select_list_item_by(1, cx);
+            return;
+        }
+
         let mut selected_row = self.selected_row.unwrap_or(0);
         if selected_row < self.delegate.rows_count(cx) - 1 {
             selected_row += 1;
@@ -612,37 +2779,490 @@ where
     fn action_select_prev_col(
         &mut self,
         _: &SelectPrevColumn,
-        _: &mut Window,
+        window: &mut Window,
         cx: &mut Context<Self>,
     ) {
-        let mut selected_col = self.selected_col.unwrap_or(0);
-        let cols_count = self.delegate.cols_count(cx);
-        if selected_col > 0 {
-            selected_col -= 1;
-        } else {
-            if self.delegate.can_loop_select(cx) {
-                selected_col = cols_count - 1;
-            }
+        // Collapse the selected group.
+        if self.set_selected_group_collapsed(true, cx) {
+            return;
+        }
+        if self.tree_select_by_key(false, window, cx) {
+            return;
         }
+
+        let selected_col = self.selected_col.unwrap_or(0);
+        let selected_col = self
+            .adjacent_visible_col(selected_col, false)
+            .or_else(|| {
+                self.visible_cols()
+                    .next_back()
+                    .filter(|_| self.delegate.can_loop_select(cx))
+            })
+            .unwrap_or(selected_col);
         self.set_selected_col(selected_col, cx);
     }
 
     fn action_select_next_col(
         &mut self,
         _: &SelectNextColumn,
-        _: &mut Window,
+        window: &mut Window,
         cx: &mut Context<Self>,
     ) {
-        let mut selected_col = self.selected_col.unwrap_or(0);
-        if selected_col < self.delegate.cols_count(cx) - 1 {
-            selected_col += 1;
+        // Expand the selected group.
+        if self.set_selected_group_collapsed(false, cx) {
+            return;
+        }
+        if self.tree_select_by_key(true, window, cx) {
+            return;
+        }
+
+        let selected_col = self.selected_col.unwrap_or(0);
+        let selected_col = self
+            .adjacent_visible_col(selected_col, true)
+            .or_else(|| {
+                self.visible_cols()
+                    .next()
+                    .filter(|_| self.delegate.can_loop_select(cx))
+            })
+            .unwrap_or(selected_col);
+        self.set_selected_col(selected_col, cx);
+    }
+
+    /// Returns the cell to move from by the keyboard navigation.
+    fn nav_cell(&self) -> CellPos {
+        self.selection.focus().unwrap_or_else(|| {
+            CellPos::new(
+                self.selected_row.unwrap_or(0),
+                self.selected_col.unwrap_or(0),
+            )
+        })
+    }
+
+    /// Returns the indexes of the columns that are not hidden, in the display order.
+    fn visible_cols(&self) -> impl DoubleEndedIterator<Item = usize> + '_ {
+        self.col_groups
+            .iter()
+            .enumerate()
+            .filter(|(_, col)| !col.hidden)
+            .map(|(col_ix, _)| col_ix)
+    }
+
+    /// Returns the visible column after the `col_ix`, or before it if `forward` is false.
+    fn adjacent_visible_col(&self, col_ix: usize, forward: bool) -> Option<usize> {
+        if forward {
+            self.visible_cols().find(|&ix| ix > col_ix)
         } else {
-            if self.delegate.can_loop_select(cx) {
-                selected_col = 0;
+            self.visible_cols().rev().find(|&ix| ix < col_ix)
+        }
+    }
+
+    /// Returns the number of the rows to move by a page, that is the rows fit in the table body.
+    ///
+    /// The variable height rows are counted from the current scroll offset.
+    fn page_rows_count(&self) -> usize {
+        let state = self.vertical_scroll_handle.0.borrow();
+        let body_height = state.base_handle.bounds().size.height;
+        if self.list_item_heights.is_none() {
+            return (body_height / self.size.table_row_height()).floor().max(1.) as usize;
+        }
+
+        let scroll_top = -state.base_handle.offset().y;
+        page_items_count(&self.list_item_tops, scroll_top, body_height)
+    }
+
+    fn is_cell_empty(&self, row_ix: usize, col_ix: usize, cx: &App) -> bool {
+        self.delegate.cell_text(row_ix, col_ix, cx).is_empty()
+    }
+
+    /// Commit the editing cell, then select the given cell and scroll it into view.
+    fn move_to_cell(
+        &mut self,
+        row_ix: usize,
+        col_ix: usize,
+        window: &mut Window,
+        cx: &mut Context<Self>,
+    ) {
+        if !self.commit_edit(window, cx) {
+            return;
+        }
+        self.select_cell(row_ix, col_ix, cx);
+    }
+
+    /// Move the selection by a page of rows, the `pages` is -1 or 1.
+    fn select_page_by(&mut self, pages: isize, window: &mut Window, cx: &mut Context<Self>) {
+        let rows_count = self.delegate.rows_count(cx);
+        if rows_count == 0 {
+            return;
+        }
+
+        let pos = self.nav_cell();
+        let rows = self.page_rows_count() as isize * pages;
+        let row_ix = pos.row.saturating_add_signed(rows).min(rows_count - 1);
+        self.move_to_cell(row_ix, pos.col, window, cx);
+    }
+
+    fn action_select_page_up(
+        &mut self,
+        _: &SelectPageUp,
+        window: &mut Window,
+        cx: &mut Context<Self>,
+    ) {
+        self.select_page_by(-1, window, cx);
+    }
+
+    fn action_select_page_down(
+        &mut self,
+        _: &SelectPageDown,
+        window: &mut Window,
+        cx: &mut Context<Self>,
+    ) {
+        self.select_page_by(1, window, cx);
+    }
+
+    fn action_select_row_start(
+        &mut self,
+        _: &SelectRowStart,
+        window: &mut Window,
+        cx: &mut Context<Self>,
+    ) {
+        let row_ix = self.nav_cell().row;
+        if let Some(col_ix) = self.visible_cols().next() {
+            self.move_to_cell(row_ix, col_ix, window, cx);
+        }
+    }
+
+    fn action_select_row_end(
+        &mut self,
+        _: &SelectRowEnd,
+        window: &mut Window,
+        cx: &mut Context<Self>,
+    ) {
+        let row_ix = self.nav_cell().row;
+        if let Some(col_ix) = self.visible_cols().next_back() {
+            self.move_to_cell(row_ix, col_ix, window, cx);
+        }
+    }
+
+    fn action_select_first_cell(
+        &mut self,
+        _: &SelectFirstCell,
+        window: &mut Window,
+        cx: &mut Context<Self>,
+    ) {
+        if self.delegate.rows_count(cx) == 0 {
+            return;
+        }
+        if let Some(col_ix) = self.visible_cols().next() {
+            self.move_to_cell(0, col_ix, window, cx);
+        }
+    }
+
+    fn action_select_last_cell(
+        &mut self,
+        _: &SelectLastCell,
+        window: &mut Window,
+        cx: &mut Context<Self>,
+    ) {
+        let rows_count = self.delegate.rows_count(cx);
+        if rows_count == 0 {
+            return;
+        }
+        if let Some(col_ix) = self.visible_cols().next_back() {
+            self.move_to_cell(rows_count - 1, col_ix, window, cx);
+        }
+    }
+
+    /// Move the selection to the edge of the data in the given direction, see [`data_edge`].
+    fn select_data_edge(
+        &mut self,
+        rows: isize,
+        cols: isize,
+        window: &mut Window,
+        cx: &mut Context<Self>,
+    ) {
+        let rows_count = self.delegate.rows_count(cx);
+        if rows_count == 0 || self.col_groups.is_empty() {
+            return;
+        }
+
+        let pos = self.nav_cell();
+        let current_empty = self.is_cell_empty(pos.row, pos.col, cx);
+        let target = if rows != 0 {
+            let next_rows: Box<dyn Iterator<Item = usize>> = if rows < 0 {
+                Box::new((0..pos.row).rev())
+            } else {
+                Box::new(pos.row + 1..rows_count)
+            };
+            data_edge(current_empty, next_rows, |row_ix| {
+                self.is_cell_empty(row_ix, pos.col, cx)
+            })
+            .map(|row_ix| CellPos::new(row_ix, pos.col))
+        } else {
+            let next_cols: Vec<usize> = if cols < 0 {
+                self.visible_cols()
+                    .rev()
+                    .filter(|&ix| ix < pos.col)
+                    .collect()
+            } else {
+                self.visible_cols().filter(|&ix| ix > pos.col).collect()
+            };
+            data_edge(current_empty, next_cols, |col_ix| {
+                self.is_cell_empty(pos.row, col_ix, cx)
+            })
+            .map(|col_ix| CellPos::new(pos.row, col_ix))
+        };
+
+        if let Some(target) = target {
+            self.move_to_cell(target.row, target.col, window, cx);
+        }
+    }
+
+    fn action_select_edge_up(
+        &mut self,
+        _: &SelectEdgeUp,
+        window: &mut Window,
+        cx: &mut Context<Self>,
+    ) {
+        self.select_data_edge(-1, 0, window, cx);
+    }
+
+    fn action_select_edge_down(
+        &mut self,
+        _: &SelectEdgeDown,
+        window: &mut Window,
+        cx: &mut Context<Self>,
+    ) {
+        self.select_data_edge(1, 0, window, cx);
+    }
+
+    fn action_select_edge_left(
+        &mut self,
+        _: &SelectEdgeLeft,
+        window: &mut Window,
+        cx: &mut Context<Self>,
+    ) {
+        self.select_data_edge(0, -1, window, cx);
+    }
+
+    fn action_select_edge_right(
+        &mut self,
+        _: &SelectEdgeRight,
+        window: &mut Window,
+        cx: &mut Context<Self>,
+    ) {
+        self.select_data_edge(0, 1, window, cx);
+    }
+
+    /// Type to search the rows by the text of the focused column, the typed text is reset after a short pause.
+    fn on_key_down(&mut self, ev: &KeyDownEvent, window: &mut Window, cx: &mut Context<Self>) {
+        const RESET_TIMEOUT: Duration = Duration::from_secs(1);
+
+        if self.editing_cell.is_some() || !self.focus_handle.is_focused(window) {
+            return;
+        }
+        let modifiers = ev.keystroke.modifiers;
+        if modifiers.control || modifiers.platform || modifiers.alt || modifiers.function {
+            return;
+        }
+        let Some(key_char) = ev.keystroke.key_char.as_ref() else {
+            return;
+        };
+        if key_char.chars().any(|c| c.is_control()) {
+            return;
+        }
+
+        let now = Instant::now();
+        if self
+            .type_search_at
+            .map_or(true, |at| now.duration_since(at) > RESET_TIMEOUT)
+        {
+            self.type_search.clear();
+        }
+        // Do not start the search with a space.
+        if self.type_search.is_empty() && key_char.trim().is_empty() {
+            return;
+        }
+        self.type_search_at = Some(now);
+        self.type_search.push_str(key_char);
+        cx.stop_propagation();
+
+        let rows_count = self.delegate.rows_count(cx);
+        if rows_count == 0 {
+            return;
+        }
+
+        let pos = self.nav_cell();
+        let query = self.type_search.to_lowercase();
+        // Find from the next row for a new search, to cycle the rows with the same first char.
+        let start = if self.type_search.chars().count() > 1 {
+            pos.row
+        } else {
+            pos.row + 1
+        };
+        let row_ix = (0..rows_count)
+            .map(|ix| (start + ix) % rows_count)
+            .find(|&row_ix| {
+                self.delegate
+                    .cell_text(row_ix, pos.col, cx)
+                    .to_lowercase()
+                    .starts_with(&query)
+            });
+        if let Some(row_ix) = row_ix {
+            self.select_cell(row_ix, pos.col, cx);
+        }
+    }
+
+    /// Open the search bar to find the cells by the `cell_value` text.
+    pub fn find(&mut self, window: &mut Window, cx: &mut Context<Self>) {
+        if !self.commit_edit(window, cx) {
+            return;
+        }
+
+        self.find_visible = true;
+        self.find_input
+            .update(cx, |input, cx| input.focus(window, cx));
+        // Search again, the rows may be changed since the last search.
+        let query = self.find_input.read(cx).text();
+        self.search(query, cx);
+    }
+
+    /// Close the search bar and focus the table.
+    pub fn close_find(&mut self, window: &mut Window, cx: &mut Context<Self>) {
+        if !self.find_visible {
+            return;
+        }
+
+        self.find_visible = false;
+        self.find.clear();
+        self.find_searching = false;
+        self._find_task = Task::ready(());
+        self.focus_handle.focus(window);
+        cx.notify();
+    }
+
+    /// Search the cells of the visible columns that contain the query text, case-insensitive.
+    ///
+    /// The search starts after the typing is stopped, the previous search is cancelled.
+    /// The cell values are read on the main thread by chunks of rows and cached until the rows are changed,
+    /// and matched in a background task. The search is restarted if the rows are changed in the meantime.
+    fn search(&mut self, query: SharedString, cx: &mut Context<Self>) {
+        if query.is_empty() {
+            self.find.clear();
+            self.find_searching = false;
+            self._find_task = Task::ready(());
+            cx.notify();
+            return;
+        }
+
+        self.find_searching = true;
+        self._find_task = cx.spawn(|view, mut cx| async move {
+            Timer::after(FIND_DEBOUNCE).await;
+
+            // Search again from the start if the rows are changed during the search.
+            'search: loop {
+                let Ok((cached_cells, cols, rows_count, rows_version)) =
+                    view.update(&mut cx, |view, cx| {
+                        view.prepare_rows(cx);
+                        (
+                            view.find_cells.clone(),
+                            view.visible_cols().collect::<Vec<_>>(),
+                            view.delegate.rows_count(cx),
+                            view.rows_version,
+                        )
+                    })
+                else {
+                    return;
+                };
+
+                let cells = match cached_cells {
+                    Some(cells) => cells,
+                    None => {
+                        let mut cells = Vec::new();
+                        for start in (0..rows_count).step_by(FIND_CHUNK_ROWS) {
+                            let Ok(rows_changed) = view.update(&mut cx, |view, cx| {
+                                view.prepare_rows(cx);
+                                if view.rows_version != rows_version {
+                                    return true;
+                                }
+
+                                let end =
+                                    (start + FIND_CHUNK_ROWS).min(view.delegate.rows_count(cx));
+                                for row_ix in start..end {
+                                    for &col_ix in &cols {
+                                        let value = view.delegate.cell_value(row_ix, col_ix, cx);
+                                        if !value.is_empty() {
+                                            cells.push((CellPos::new(row_ix, col_ix), value));
+                                        }
+                                    }
+                                }
+                                false
+                            }) else {
+                                return;
+                            };
+                            if rows_changed {
+                                continue 'search;
+                            }
+                            // Let the main thread handle the other events between the chunks.
+                            smol::future::yield_now().await;
+                        }
+
+                        let cells = Arc::new(cells);
+                        _ = view.update(&mut cx, |view, _| {
+                            if view.rows_version == rows_version {
+                                view.find_cells = Some(cells.clone());
+                            }
+                        });
+                        cells
+                    }
+                };
+
+                let query = query.clone();
+                let matches = cx
+                    .background_executor()
+                    .spawn(async move { find::find_matches(&cells, &query) })
+                    .await;
+                let Ok(rows_changed) = view.update(&mut cx, |view, cx| {
+                    view.prepare_rows(cx);
+                    if view.rows_version != rows_version {
+                        return true;
+                    }
+
+                    let from = view.nav_cell();
+                    view.find_searching = false;
+                    if let Some(pos) = view.find.set_matches(matches, |pos| *pos < from) {
+                        view.select_cell(pos.row, pos.col, cx);
+                    }
+                    cx.notify();
+                    false
+                }) else {
+                    return;
+                };
+                if !rows_changed {
+                    break;
+                }
             }
+        });
+        cx.notify();
+    }
+
+    /// Select the next match, or the previous one if `reverse` is true.
+    fn find_next(&mut self, reverse: bool, cx: &mut Context<Self>) {
+        if let Some(pos) = self.find.next(reverse) {
+            self.select_cell(pos.row, pos.col, cx);
+            cx.notify();
         }
+    }
 
-        self.set_selected_col(selected_col, cx);
+    fn action_find(&mut self, _: &Find, window: &mut Window, cx: &mut Context<Self>) {
+        self.find(window, cx);
+    }
+
+    fn action_find_prev(&mut self, _: &FindPrev, _: &mut Window, cx: &mut Context<Self>) {
+        self.find_next(true, cx);
+    }
+
+    fn action_close_find(&mut self, _: &CloseFind, window: &mut Window, cx: &mut Context<Self>) {
+        self.close_find(window, cx);
     }
 
     /// Scroll table when mouse position is near the edge of the table bounds.
@@ -669,46 +3289,162 @@ where
     /// The `ix`` is the index of the col to resize,
     /// and the `size` is the new size for the col.
     fn resize_cols(&mut self, ix: usize, size: Pixels, _: &mut Window, cx: &mut Context<Self>) {
-        const MIN_WIDTH: Pixels = px(10.0);
-        const MAX_WIDTH: Pixels = px(1200.0);
+        if !self.delegate.can_resize_col(ix, cx) {
+            return;
+        }
+        let size = size.floor();
+
+        let old_width = self.col_groups[ix].width;
+        let new_width = size;
+        if new_width < self.delegate.col_min_width(ix, cx) {
+            return;
+        }
+        let changed_width = new_width - old_width;
+        // If change size is less than 1px, do nothing.
+        if changed_width > px(-1.0) && changed_width < px(1.0) {
+            return;
+        }
+        self.col_groups[ix].width = new_width.min(self.delegate.col_max_width(ix, cx));
+
+        // Resize next col, table not need to resize the right cols.
+        // let next_width = self.col_groups[ix + 1].width.unwrap_or_default();
+        // let next_width = (next_width - changed_width).max(MIN_WIDTH);
+        // self.col_groups[ix + 1].width = Some(next_width);
+
+        cx.notify();
+    }
+
+    /// Auto-fit the width of the column at the given index to the widest text of its header and the visible cells.
+    ///
+    /// The width is clamped by `TableDelegate::col_min_width` and `TableDelegate::col_max_width`.
+    pub fn auto_fit_col(&mut self, col_ix: usize, window: &mut Window, cx: &mut Context<Self>) {
+        if self.fit_col_width(col_ix, window, cx) {
+            let new_widths = self.col_groups.iter().map(|g| g.width).collect();
+            cx.emit(TableEvent::ColWidthsChanged(new_widths));
+            cx.notify();
+        }
+    }
+
+    /// Auto-fit the width of all resizable columns, see [`Table::auto_fit_col`].
+    pub fn auto_fit_columns(&mut self, window: &mut Window, cx: &mut Context<Self>) {
+        let mut changed = false;
+        for col_ix in 0..self.col_groups.len() {
+            changed |= self.fit_col_width(col_ix, window, cx);
+        }
+
+        if changed {
+            let new_widths = self.col_groups.iter().map(|g| g.width).collect();
+            cx.emit(TableEvent::ColWidthsChanged(new_widths));
+            cx.notify();
+        }
+    }
+
+    /// Set the width of the column to fit the content, returns true if the width has changed.
+    fn fit_col_width(&mut self, col_ix: usize, window: &mut Window, cx: &App) -> bool {
+        if self.is_col_hidden(col_ix)
+            || col_ix >= self.col_groups.len()
+            || !self.delegate.can_resize_col(col_ix, cx)
+        {
+            return false;
+        }
+
+        let min_width = self.delegate.col_min_width(col_ix, cx);
+        let max_width = self.delegate.col_max_width(col_ix, cx).max(min_width);
+        let width = self
+            .measure_col_width(col_ix, window, cx)
+            .ceil()
+            .clamp(min_width, max_width);
+
+        let col_group = &mut self.col_groups[col_ix];
+        if col_group.width == width {
+            return false;
+        }
+        col_group.width = width;
+        true
+    }
+
+    /// Measure the width of the column content, that is the header and the visible cells text with the paddings.
+    fn measure_col_width(&self, col_ix: usize, window: &mut Window, cx: &App) -> Pixels {
+        /// The width of the icon button (sort or filter) in the header.
+        const ICON_WIDTH: Pixels = px(20.);
+
+        let text_style = &self.text_style;
+        let font_size = text_style.font_size.to_pixels(window.rem_size());
+        let text_system = window.text_system().clone();
+        let measure = |text: SharedString| {
+            if text.is_empty() {
+                return px(0.);
+            }
 
-        if !self.delegate.can_resize_col(ix, cx) {
-            return;
-        }
-        let size = size.floor();
+            let run = TextRun {
+                len: text.len(),
+                font: text_style.font(),
+                color: text_style.color,
+                background_color: None,
+                underline: None,
+                strikethrough: None,
+            };
+            text_system
+                .shape_line(text, font_size, &[run])
+                .map_or(px(0.), |line| line.width)
+        };
 
-        let old_width = self.col_groups[ix].width;
-        let new_width = size;
-        if new_width < MIN_WIDTH {
-            return;
+        let col_group = &self.col_groups[col_ix];
+        let padding = col_group
+            .padding
+            .unwrap_or_else(|| self.size.table_cell_padding());
+
+        let mut head_width = measure(self.delegate.col_name(col_ix, cx));
+        if col_group.sort.is_some() {
+            head_width += ICON_WIDTH;
         }
-        let changed_width = new_width - old_width;
-        // If change size is less than 1px, do nothing.
-        if changed_width > px(-1.0) && changed_width < px(1.0) {
-            return;
+        if self.delegate.col_filter_kind(col_ix, cx).is_some() {
+            head_width += ICON_WIDTH;
         }
-        self.col_groups[ix].width = new_width.min(MAX_WIDTH);
 
-        // Resize next col, table not need to resize the right cols.
-        // let next_width = self.col_groups[ix + 1].width.unwrap_or_default();
-        // let next_width = (next_width - changed_width).max(MIN_WIDTH);
-        // self.col_groups[ix + 1].width = Some(next_width);
+        // Only the frozen rows and the visible rows are measured, the other rows are not rendered.
+        let rows_count = self.delegate.rows_count(cx);
+        let frozen_rows_count = self.delegate.frozen_rows_count(cx).min(rows_count);
+        let visible_rows = self.visible_range.rows.start.max(frozen_rows_count)
+            ..self.visible_range.rows.end.min(rows_count);
+        let is_tree = col_ix == self.first_visible_col() && self.delegate.is_tree(cx);
+        let cells_width = (0..frozen_rows_count)
+            .chain(visible_rows)
+            .map(|row_ix| {
+                let width = measure(self.delegate.cell_text(row_ix, col_ix, cx));
+                if is_tree {
+                    // The indent and the toggle icon of the tree row.
+                    let depth = self.delegate.row_depth(row_ix, cx);
+                    width + TREE_INDENT * depth as f32 + px(20.)
+                } else {
+                    width
+                }
+            })
+            .fold(px(0.), |max_width, width| max_width.max(width));
 
-        cx.notify();
+        head_width.max(cells_width) + padding.left + padding.right
     }
 
     fn perform_sort(&mut self, col_ix: usize, window: &mut Window, cx: &mut Context<Self>) {
-        let sort = self.col_groups.get(col_ix).and_then(|g| g.sort);
-        if sort.is_none() {
+        let Some(sort) = self.next_col_sort(col_ix) else {
             return;
-        }
-
-        let sort = sort.unwrap();
-        let sort = match sort {
-            ColSort::Ascending => ColSort::Default,
-            ColSort::Descending => ColSort::Ascending,
-            ColSort::Default => ColSort::Descending,
         };
+        self.set_col_sort(col_ix, sort, window, cx);
+    }
+
+    /// Sort the table by the column at the given index, and clear the sort of the other columns.
+    ///
+    /// Only the sortable column (see `TableDelegate::col_sort`) can be sorted.
+    pub fn set_col_sort(
+        &mut self,
+        col_ix: usize,
+        sort: ColSort,
+        window: &mut Window,
+        cx: &mut Context<Self>,
+    ) {
+        if self.col_groups.get(col_ix).and_then(|g| g.sort).is_none() {
+            return;
+        }
 
         for (ix, col_group) in self.col_groups.iter_mut().enumerate() {
             if ix == col_ix {
@@ -720,11 +3456,64 @@ where
             }
         }
 
+        self.sort_keys = match sort {
+            ColSort::Default => vec![],
+            _ => vec![col_ix],
+        };
         self.delegate_mut().perform_sort(col_ix, sort, window, cx);
+        self.rows_dirty = true;
+
+        cx.notify();
+    }
+
+    /// Add or update the column in the sort keys, and keep the other sort keys.
+    fn perform_multi_sort(&mut self, col_ix: usize, window: &mut Window, cx: &mut Context<Self>) {
+        let Some(sort) = self.next_col_sort(col_ix) else {
+            return;
+        };
+
+        self.col_groups[col_ix].sort = Some(sort);
+        match sort {
+            ColSort::Default => self.sort_keys.retain(|&ix| ix != col_ix),
+            _ => {
+                if !self.sort_keys.contains(&col_ix) {
+                    self.sort_keys.push(col_ix);
+                }
+            }
+        }
 
+        self.perform_sort_keys(col_ix, window, cx);
         cx.notify();
     }
 
+    /// Perform the sort by the sort keys, or reset the sort by the column at `reset_col_ix`
+    /// if there is no sort key left.
+    fn perform_sort_keys(
+        &mut self,
+        reset_col_ix: usize,
+        window: &mut Window,
+        cx: &mut Context<Self>,
+    ) {
+        let sorts = self.sort_keys();
+        if sorts.is_empty() {
+            self.delegate_mut()
+                .perform_sort(reset_col_ix, ColSort::Default, window, cx);
+        } else {
+            self.delegate_mut().perform_multi_sort(&sorts, window, cx);
+        }
+        self.rows_dirty = true;
+    }
+
+    /// Returns the next sort state of the column by the cycle: Default -> Descending -> Ascending -> Default.
+    fn next_col_sort(&self, col_ix: usize) -> Option<ColSort> {
+        let sort = self.col_groups.get(col_ix).and_then(|g| g.sort)?;
+        Some(match sort {
+            ColSort::Ascending => ColSort::Default,
+            ColSort::Descending => ColSort::Ascending,
+            ColSort::Default => ColSort::Descending,
+        })
+    }
+
     fn move_col(
         &mut self,
         col_ix: usize,
@@ -737,8 +3526,16 @@ where
         }
 
         self.delegate.move_col(col_ix, to_ix, window, cx);
+        self.rows_dirty = true;
         let col_group = self.col_groups.remove(col_ix);
         self.col_groups.insert(to_ix, col_group);
+        let col_filter = self.col_filters.remove(col_ix);
+        self.col_filters.insert(to_ix, col_filter);
+
+        // Keep the sort keys point to the moved columns.
+        for ix in self.sort_keys.iter_mut() {
+            *ix = moved_col_ix(*ix, col_ix, to_ix);
+        }
 
         cx.emit(TableEvent::MoveCol(col_ix, to_ix));
         cx.notify();
@@ -800,6 +3597,11 @@ where
     /// Returns the size of the content area.
     fn head_content_bounds(&self) -> gpui::Bounds<Pixels> {
         let has_fixed_cols = self.fixed_head_cols_bounds.size.width > px(0.0);
+        let right_fixed_width = if self.fixed_cols.right > 0 {
+            self.fixed_right_head_cols_bounds.size.width
+        } else {
+            px(0.)
+        };
         Bounds {
             origin: if has_fixed_cols {
                 self.fixed_head_cols_bounds.origin
@@ -807,7 +3609,9 @@ where
                 self.head_content_bounds.origin
             },
             size: gpui::size(
-                self.fixed_head_cols_bounds.size.width + self.head_content_bounds.size.width,
+                self.fixed_head_cols_bounds.size.width
+                    + self.head_content_bounds.size.width
+                    + right_fixed_width,
                 self.head_content_bounds.size.height,
             ),
         }
@@ -818,6 +3622,9 @@ where
         let Some(col_group) = self.col_groups.get(col_ix) else {
             return div();
         };
+        if col_group.hidden {
+            return div();
+        }
 
         let col_width = col_group.width;
         let col_padding = col_group.padding;
@@ -853,6 +3660,214 @@ where
         }
     }
 
+    /// Render the indentation and the disclosure chevron of the tree row, the cell content should be appended.
+    fn render_tree_toggle(&self, row_ix: usize, cx: &mut Context<Self>) -> Div {
+        let depth = self.delegate.row_depth(row_ix, cx);
+        let has_children = self.delegate.row_has_children(row_ix, cx);
+        let expanded = has_children && self.delegate.is_row_expanded(row_ix, cx);
+
+        h_flex()
+            .size_full()
+            .items_center()
+            .pl(TREE_INDENT * depth as f32)
+            .child(
+                h_flex()
+                    .id(("tree-toggle", row_ix))
+                    .flex_shrink_0()
+                    .size_4()
+                    .mr_1()
+                    .justify_center()
+                    .when(has_children, |this| {
+                        this.cursor_pointer()
+                            .rounded(cx.theme().radius / 2.)
+                            .hover(|this| this.bg(cx.theme().secondary))
+                            .child(
+                                Icon::new(if expanded {
+                                    IconName::ChevronDown
+                                } else {
+                                    IconName::ChevronRight
+                                })
+                                .size_3(),
+                            )
+                            .on_mouse_down(
+                                MouseButton::Left,
+                                cx.listener(move |this, _, window, cx| {
+                                    cx.stop_propagation();
+                                    this.toggle_expand(row_ix, window, cx);
+                                }),
+                            )
+                    }),
+            )
+    }
+
+    /// Render the cell at the given row and column, or the cell editor when it is in edit mode.
+    fn render_td_cell(
+        &mut self,
+        row_ix: usize,
+        col_ix: usize,
+        window: &mut Window,
+        cx: &mut Context<Self>,
+    ) -> Div {
+        if self.is_col_hidden(col_ix) {
+            return div();
+        }
+
+        let is_editing = self.editing_cell == Some((row_ix, col_ix));
+        let editable = self.delegate.can_edit_cell(row_ix, col_ix, cx);
+
+        let content = if is_editing {
+            div()
+                .size_full()
+                // Commit on enter, before the editor (e.g. `TextInput`) handles it.
+                .capture_action(cx.listener(|this, _: &crate::input::Enter, window, cx| {
+                    this.action_edit_cell(&EditCell, window, cx);
+                    cx.stop_propagation();
+                }))
+                .on_mouse_down(MouseButton::Left, |_, _, cx| cx.stop_propagation())
+                .child(self.delegate.render_cell_editor(row_ix, col_ix, window, cx))
+                .into_any_element()
+        } else {
+            self.measure_render_td(row_ix, col_ix, window, cx)
+                .into_any_element()
+        };
+        let content = if col_ix == self.first_visible_col() && self.delegate.is_tree(cx) {
+            self.render_tree_toggle(row_ix, cx)
+                .child(content)
+                .into_any_element()
+        } else {
+            content
+        };
+
+        let pos = CellPos::new(row_ix, col_ix);
+        let is_cell_selection = self.selection_state == SelectionState::Cell;
+        let is_selected = is_cell_selection && self.selection.contains(pos);
+        let is_focused = is_cell_selection && self.selection.focus() == Some(pos);
+        let is_col_selected =
+            self.selection_state == SelectionState::Column && self.selected_col == Some(col_ix);
+        let format = self.cell_format(row_ix, col_ix, cx).unwrap_or_default();
+        let is_found = self.find_visible && self.find.contains(&pos);
+        let is_current_found = is_found && self.find.current() == Some(pos);
+        let has_custom_height = self.row_height_of(row_ix, cx) != self.size.table_row_height();
+
+        self.render_col_wrap(col_ix, window, cx)
+            .relative()
+            // Keep the selected background over the formatted background.
+            .when(!is_col_selected, |this| {
+                this.when_some(format.style.background, |this, bg| this.bg(bg))
+            })
+            .when(is_selected, |this| this.bg(cx.theme().table_active))
+            .when(is_found, |this| {
+                let opacity = if is_current_found { 0.5 } else { 0.2 };
+                this.bg(ColorName::Yellow.scale(500).opacity(opacity))
+            })
+            .when_some(format.bar, |this, (ratio, color)| {
+                this.child(
+                    div()
+                        .absolute()
+                        .top(px(4.))
+                        .bottom(px(4.))
+                        .left_0()
+                        .w(relative(ratio))
+                        .bg(color),
+                )
+            })
+            .child(
+                self.render_cell(col_ix, window, cx)
+                    // Wrap the text in the rows with custom height.
+                    .when(has_custom_height, |this| this.whitespace_normal())
+                    .when_some(format.style.foreground, |this, color| {
+                        this.text_color(color)
+                    })
+                    .child(content),
+            )
+            .when(is_focused, |this| {
+                this.child(
+                    div()
+                        .absolute()
+                        .top_0()
+                        .left_0()
+                        .right_0()
+                        .bottom_0()
+                        .border_1()
+                        .border_color(cx.theme().table_active_border),
+                )
+            })
+            .when(editable && !is_editing, |this| {
+                this.on_mouse_down(
+                    MouseButton::Left,
+                    cx.listener(move |this, ev: &MouseDownEvent, window, cx| {
+                        if ev.click_count == 2 && this.edit_cell(row_ix, col_ix, window, cx) {
+                            // Keep the focus on the cell editor.
+                            window.prevent_default();
+                            cx.stop_propagation();
+                        }
+                    }),
+                )
+            })
+    }
+
+    /// Render the search bar over the top-right of the table head.
+    fn render_find_bar(&self, cx: &mut Context<Self>) -> impl IntoElement {
+        let (index, count) = self.find.position();
+        let query = self.find_input.read(cx).text();
+        let status: SharedString = if query.is_empty() || self.find_searching {
+            "".into()
+        } else if count == 0 {
+            t!("Table.No Results").into()
+        } else {
+            t!("Table.Match Position", index = index, count = count)
+                .to_string()
+                .into()
+        };
+
+        h_flex()
+            .key_context(FIND_CONTEXT)
+            .id("table-find-bar")
+            .occlude()
+            .absolute()
+            .top_1()
+            .right(scroll::WIDTH)
+            .w(px(300.))
+            .gap_1()
+            .px_1()
+            .bg(cx.theme().popover)
+            .border_1()
+            .border_color(cx.theme().border)
+            .rounded(cx.theme().radius)
+            .shadow_md()
+            .child(div().flex_1().child(self.find_input.clone()))
+            .child(
+                div()
+                    .flex_shrink_0()
+                    .text_xs()
+                    .text_color(cx.theme().muted_foreground)
+                    .child(status),
+            )
+            .child(
+                Button::new("find-prev")
+                    .ghost()
+                    .xsmall()
+                    .icon(IconName::ChevronUp)
+                    .disabled(count == 0)
+                    .on_click(cx.listener(|this, _, _, cx| this.find_next(true, cx))),
+            )
+            .child(
+                Button::new("find-next")
+                    .ghost()
+                    .xsmall()
+                    .icon(IconName::ChevronDown)
+                    .disabled(count == 0)
+                    .on_click(cx.listener(|this, _, _, cx| this.find_next(false, cx))),
+            )
+            .child(
+                Button::new("find-close")
+                    .ghost()
+                    .xsmall()
+                    .icon(IconName::Close)
+                    .on_click(cx.listener(|this, _, window, cx| this.close_find(window, cx))),
+            )
+    }
+
     fn render_vertical_scrollbar(
         &self,
         _: &mut Window,
@@ -864,21 +3879,35 @@ where
             div()
                 .occlude()
                 .absolute()
-                .top(self.size.table_row_height())
+                // Below the header and the frozen rows, to match the scrolled body.
+                .top(self.size.table_row_height() + self.frozen_rows_height(cx))
                 .right_0()
-                .bottom_0()
+                .map(|this| {
+                    if self.delegate.has_footer(cx) {
+                        this.bottom(self.size.table_row_height())
+                    } else {
+                        this.bottom_0()
+                    }
+                })
                 .w(scroll::WIDTH)
                 .on_scroll_wheel(cx.listener(|_, _: &ScrollWheelEvent, _, cx| {
                     cx.notify();
                 }))
-                .child(
-                    Scrollbar::uniform_scroll(
+                .child(match self.list_item_heights {
+                    Some(_) => Scrollbar::vertical(
+                        cx.entity().entity_id(),
+                        state,
+                        self.vertical_scroll_handle.0.borrow().base_handle.clone(),
+                        gpui::size(self.bounds.size.width, self.list_content_height(cx)),
+                    )
+                    .max_fps(60),
+                    None => Scrollbar::uniform_scroll(
                         cx.entity().entity_id(),
                         state,
                         self.vertical_scroll_handle.clone(),
                     )
                     .max_fps(60),
-                ),
+                }),
         )
     }
 
@@ -948,8 +3977,10 @@ where
                             }
 
                             // sync col widths into real widths
-                            for (_, col_group) in view.col_groups.iter_mut().enumerate() {
-                                col_group.width = col_group.bounds.size.width;
+                            for col_group in view.col_groups.iter_mut() {
+                                if !col_group.hidden {
+                                    col_group.width = col_group.bounds.size.width;
+                                }
                             }
 
                             let ix = *ix;
@@ -976,6 +4007,12 @@ where
                     };
                 }),
             )
+            .on_click(cx.listener(move |view, ev: &ClickEvent, window, cx| {
+                // Double click to auto-fit the column to its content.
+                if ev.down.click_count == 2 {
+                    view.auto_fit_col(ix, window, cx);
+                }
+            }))
             .on_drag(ResizeCol((cx.entity_id(), ix)), |drag, _, _, cx| {
                 cx.stop_propagation();
                 cx.new(|_| drag.clone())
@@ -1013,11 +4050,18 @@ where
             ColSort::Descending => (IconName::SortDescending, true),
             ColSort::Default => (IconName::ChevronsUpDown, false),
         };
+        // Show the sort priority when sorting by multiple columns.
+        let priority = if self.sort_keys.len() > 1 {
+            self.sort_keys.iter().position(|&ix| ix == col_ix)
+        } else {
+            None
+        };
 
         Some(
-            div()
+            h_flex()
                 .id(("icon-sort", col_ix))
                 .cursor_pointer()
+                .items_center()
                 .p(px(2.))
                 .rounded(cx.theme().radius / 2.)
                 .map(|this| match is_on {
@@ -1026,14 +4070,87 @@ where
                 })
                 .hover(|this| this.bg(cx.theme().secondary).opacity(7.))
                 .active(|this| this.bg(cx.theme().secondary_active).opacity(1.))
-                .on_click(
-                    cx.listener(move |table, _, window, cx| table.perform_sort(col_ix, window, cx)),
-                )
+                .on_click(cx.listener(move |table, ev: &ClickEvent, window, cx| {
+                    // The shift+click has been handled by the header as multiple sort.
+                    if ev.down.modifiers.shift {
+                        return;
+                    }
+
+                    table.perform_sort(col_ix, window, cx)
+                }))
                 .child(
                     Icon::new(icon)
                         .size_3()
                         .text_color(cx.theme().secondary_foreground),
-                ),
+                )
+                .when_some(priority, |this, priority| {
+                    this.child(
+                        div()
+                            .text_xs()
+                            .line_height(px(12.))
+                            .text_color(cx.theme().secondary_foreground)
+                            .child(format!("{}", priority + 1)),
+                    )
+                }),
+        )
+    }
+
+    fn render_filter_button(
+        &self,
+        col_ix: usize,
+        _: &mut Window,
+        cx: &mut Context<Self>,
+    ) -> Option<impl IntoElement> {
+        let kind = self.delegate.col_filter_kind(col_ix, cx)?;
+        let is_active = self
+            .col_filters
+            .get(col_ix)
+            .map_or(false, |filter| filter.is_some());
+        let view = cx.entity().downgrade();
+
+        Some(
+            Popover::new(("table-filter", col_ix))
+                .anchor(Corner::TopRight)
+                .trigger(
+                    Button::new(("filter", col_ix))
+                        .icon(
+                            Icon::new(IconName::Filter)
+                                .when(is_active, |this| this.text_color(cx.theme().primary)),
+                        )
+                        .ghost()
+                        .xsmall(),
+                )
+                .content(move |window, cx| {
+                    let view = view.clone();
+                    let (filter, values) = view
+                        .upgrade()
+                        .map(|table| {
+                            let table = table.read(cx);
+                            let values = match kind {
+                                ColFilterKind::Values => {
+                                    table.delegate.col_filter_values(col_ix, cx)
+                                }
+                                _ => vec![],
+                            };
+                            (table.col_filters.get(col_ix).cloned().flatten(), values)
+                        })
+                        .unwrap_or_default();
+
+                    cx.new(|cx| {
+                        FilterPanel::new(
+                            kind,
+                            values,
+                            filter,
+                            move |filter, window, cx| {
+                                _ = view.update(cx, |table, cx| {
+                                    table.set_col_filter(col_ix, filter, window, cx);
+                                });
+                            },
+                            window,
+                            cx,
+                        )
+                    })
+                }),
         )
     }
 
@@ -1041,14 +4158,12 @@ where
     /// The children must be one by one items.
     /// Because the horizontal scroll handle will use the child_item_bounds to
     /// calculate the item position for itself's `scroll_to_item` method.
-    fn render_th(
-        &self,
-        col_ix: usize,
-        window: &mut Window,
-        cx: &mut Context<Self>,
-    ) -> impl IntoElement {
+    fn render_th(&self, col_ix: usize, window: &mut Window, cx: &mut Context<Self>) -> AnyElement {
         let entity_id = cx.entity_id();
         let col_group = self.col_groups.get(col_ix).expect("BUG: invalid col index");
+        if col_group.hidden {
+            return div().into_any_element();
+        }
         let moveable = self.delegate.can_move_col(col_ix, cx);
         let paddings = self.delegate.col_padding(col_ix, cx);
         let name = self.delegate.col_name(col_ix, cx);
@@ -1059,8 +4174,8 @@ where
                     .id(("col-header", col_ix))
                     .on_mouse_down(
                         MouseButton::Left,
-                        cx.listener(move |this, _, window, cx| {
-                            this.on_col_head_click(col_ix, window, cx);
+                        cx.listener(move |this, ev, window, cx| {
+                            this.on_col_head_click(ev, col_ix, window, cx);
                         }),
                     )
                     .child(
@@ -1075,7 +4190,15 @@ where
                                     self.size.table_cell_padding().right - paddings.right;
                                 this.pr(offset_pr.max(px(0.)))
                             })
-                            .children(self.render_sort_icon(col_ix, &col_group, window, cx)),
+                            .child(
+                                h_flex()
+                                    .gap_1()
+                                    .items_center()
+                                    .children(self.render_filter_button(col_ix, window, cx))
+                                    .children(
+                                        self.render_sort_icon(col_ix, &col_group, window, cx),
+                                    ),
+                            ),
                     )
                     .when(moveable, |this| {
                         this.on_drag(
@@ -1122,11 +4245,13 @@ where
                 .absolute()
                 .size_full()
             })
+            .into_any_element()
     }
 
     fn render_table_head(
         &mut self,
         left_cols_count: usize,
+        right_cols_count: usize,
         window: &mut Window,
         cx: &mut Context<Self>,
     ) -> impl IntoElement {
@@ -1180,39 +4305,251 @@ where
                 )
             })
             .child(
-                // Columns
-                h_flex()
-                    .id("table-head")
+                // Columns
+                h_flex()
+                    .id("table-head")
+                    .size_full()
+                    .overflow_scroll()
+                    .relative()
+                    .track_scroll(&horizontal_scroll_handle)
+                    .bg(cx.theme().table_head)
+                    .child(
+                        h_flex()
+                            .relative()
+                            .children(
+                                self.col_groups
+                                    .iter()
+                                    .filter(|col| col.fixed == None)
+                                    .enumerate()
+                                    .map(|(col_ix, _)| {
+                                        self.render_th(left_cols_count + col_ix, window, cx)
+                                    }),
+                            )
+                            .child(self.delegate.render_last_empty_col(window, cx))
+                            .child({
+                                let view = view.clone();
+                                canvas(
+                                    move |bounds, _, cx| {
+                                        view.update(cx, |r, _| r.head_content_bounds = bounds)
+                                    },
+                                    |_, _, _, _| {},
+                                )
+                                .absolute()
+                                .size_full()
+                            }),
+                    ),
+            )
+            .when(right_cols_count > 0, |this| {
+                let cols_count = self.col_groups.len();
+                // Render right fixed columns
+                this.child(
+                    h_flex()
+                        .relative()
+                        .h_full()
+                        .flex_shrink_0()
+                        .bg(cx.theme().table_head)
+                        .children(
+                            (cols_count - right_cols_count..cols_count)
+                                .map(|col_ix| self.render_th(col_ix, window, cx)),
+                        )
+                        .child(
+                            // Fixed columns border
+                            div()
+                                .absolute()
+                                .top_0()
+                                .left_0()
+                                .bottom_0()
+                                .w_0()
+                                .flex_shrink_0()
+                                .border_l_1()
+                                .border_color(cx.theme().border),
+                        )
+                        .child(
+                            canvas(
+                                move |bounds, _, cx| {
+                                    view.update(cx, |r, _| r.fixed_right_head_cols_bounds = bounds)
+                                },
+                                |_, _, _, _| {},
+                            )
+                            .absolute()
+                            .size_full(),
+                        ),
+                )
+            })
+    }
+
+    /// Render a row of the cells without virtualization, it follows the column widths,
+    /// fixed columns and horizontal scroll of the header, e.g.: the footer and group header rows.
+    fn render_static_row(
+        &mut self,
+        left_cols_count: usize,
+        right_cols_count: usize,
+        render_content: impl Fn(&Self, usize, &mut Window, &mut Context<Self>) -> AnyElement,
+        window: &mut Window,
+        cx: &mut Context<Self>,
+    ) -> Div {
+        let cols_count = self.col_groups.len();
+        let scroll_offset_x = self.horizontal_scroll_handle.offset().x;
+        let render_cells = |cols: Range<usize>, window: &mut Window, cx: &mut Context<Self>| {
+            cols.filter(|&col_ix| !self.col_groups[col_ix].hidden)
+                .map(|col_ix| {
+                    self.render_cell(col_ix, window, cx)
+                        .child(render_content(self, col_ix, window, cx))
+                })
+                .collect::<Vec<_>>()
+        };
+
+        let left_cells = render_cells(0..left_cols_count, window, cx);
+        let cells = render_cells(left_cols_count..cols_count - right_cols_count, window, cx);
+        let right_cells = render_cells(cols_count - right_cols_count..cols_count, window, cx);
+
+        h_flex()
+            .w_full()
+            .h(self.size.table_row_height())
+            .flex_shrink_0()
+            .when(left_cols_count > 0, |this| {
+                this.child(
+                    h_flex()
+                        .h_full()
+                        .flex_shrink_0()
+                        .border_r_1()
+                        .border_color(cx.theme().border)
+                        .children(left_cells),
+                )
+            })
+            .child(
+                h_flex().flex_1().h_full().overflow_hidden().child(
+                    h_flex()
+                        .relative()
+                        .h_full()
+                        .left(scroll_offset_x)
+                        .children(cells)
+                        .child(self.delegate.render_last_empty_col(window, cx)),
+                ),
+            )
+            .when(right_cols_count > 0, |this| {
+                this.child(
+                    h_flex()
+                        .h_full()
+                        .flex_shrink_0()
+                        .border_l_1()
+                        .border_color(cx.theme().border)
+                        .children(right_cells),
+                )
+            })
+    }
+
+    /// Render the footer row with the aggregated value of each column.
+    fn render_table_foot(
+        &mut self,
+        left_cols_count: usize,
+        right_cols_count: usize,
+        window: &mut Window,
+        cx: &mut Context<Self>,
+    ) -> impl IntoElement {
+        self.render_static_row(
+            left_cols_count,
+            right_cols_count,
+            |this, col_ix, window, cx| {
+                let value = this.footer_values.get(col_ix).copied().flatten();
+                this.delegate
+                    .render_tfoot(col_ix, value, window, cx)
+                    .into_any_element()
+            },
+            window,
+            cx,
+        )
+        .border_t_1()
+        .border_color(cx.theme().border)
+        .bg(cx.theme().table_head)
+        .text_color(cx.theme().table_head_foreground)
+    }
+
+    /// Render the group header row with the collapse toggle, the label and the subtotals.
+    fn render_group_row(
+        &mut self,
+        group_ix: usize,
+        left_cols_count: usize,
+        right_cols_count: usize,
+        window: &mut Window,
+        cx: &mut Context<Self>,
+    ) -> impl IntoElement {
+        let Some(group) = self.row_groups.group(group_ix).cloned() else {
+            return div().into_any_element();
+        };
+        let is_selected = self.selected_group == Some(group_ix);
+        let key = group.key.clone();
+        let label_col_ix = self.first_visible_col();
+
+        self.render_static_row(
+            left_cols_count,
+            right_cols_count,
+            move |this, col_ix, window, cx| {
+                if col_ix == label_col_ix {
+                    let icon = if group.collapsed {
+                        IconName::ChevronRight
+                    } else {
+                        IconName::ChevronDown
+                    };
+
+                    return h_flex()
+                        .size_full()
+                        .gap_1()
+                        .items_center()
+                        .child(Icon::new(icon).size_3())
+                        .child(this.delegate.render_group_header(
+                            &group.key,
+                            group.rows.len(),
+                            window,
+                            cx,
+                        ))
+                        .into_any_element();
+                }
+
+                let value = this
+                    .group_values
+                    .get(group_ix)
+                    .and_then(|values| values.get(col_ix).copied().flatten());
+                div()
                     .size_full()
-                    .overflow_scroll()
-                    .relative()
-                    .track_scroll(&horizontal_scroll_handle)
-                    .bg(cx.theme().table_head)
-                    .child(
-                        h_flex()
-                            .relative()
-                            .children(
-                                self.col_groups
-                                    .iter()
-                                    .filter(|col| col.fixed == None)
-                                    .enumerate()
-                                    .map(|(col_ix, _)| {
-                                        self.render_th(left_cols_count + col_ix, window, cx)
-                                    }),
-                            )
-                            .child(self.delegate.render_last_empty_col(window, cx))
-                            .child(
-                                canvas(
-                                    move |bounds, _, cx| {
-                                        view.update(cx, |r, _| r.head_content_bounds = bounds)
-                                    },
-                                    |_, _, _, _| {},
-                                )
-                                .absolute()
-                                .size_full(),
-                            ),
-                    ),
+                    .children(value.map(format_aggregate))
+                    .into_any_element()
+            },
+            window,
+            cx,
+        )
+        .id(("table-group", group_ix))
+        .relative()
+        .border_b_1()
+        .border_color(cx.theme().table_row_border)
+        .bg(cx.theme().table_head)
+        .font_semibold()
+        .cursor_pointer()
+        .when(is_selected, |this| {
+            this.child(
+                div()
+                    .absolute()
+                    .top(px(-1.))
+                    .left_0()
+                    .right_0()
+                    .bottom_0()
+                    .bg(cx.theme().table_active)
+                    .border_1()
+                    .border_color(cx.theme().table_active_border),
             )
+        })
+        .on_mouse_down(
+            MouseButton::Left,
+            cx.listener(move |this, _, window, cx| {
+                if !this.commit_edit(window, cx) {
+                    return;
+                }
+
+                this.select_group(group_ix, cx);
+                this.toggle_group(key.clone(), cx);
+            }),
+        )
+        .into_any_element()
     }
 
     #[allow(clippy::too_many_arguments)]
@@ -1221,6 +4558,7 @@ where
         row_ix: usize,
         rows_count: usize,
         left_cols_count: usize,
+        right_cols_count: usize,
         col_sizes: Rc<Vec<gpui::Size<Pixels>>>,
         cols_count: usize,
         window: &mut Window,
@@ -1232,10 +4570,11 @@ where
         let view = cx.entity().clone();
 
         if row_ix < rows_count {
+            let row_height = self.row_height_of(row_ix, cx);
             self.delegate
                 .render_tr(row_ix, window, cx)
                 .w_full()
-                .h(self.size.table_row_height())
+                .h(row_height)
                 .border_b_1()
                 .when(row_ix == rows_count, |this| {
                     this.border_color(gpui::transparent_white())
@@ -1259,11 +4598,7 @@ where
                                 let mut items = Vec::with_capacity(left_cols_count);
 
                                 (0..left_cols_count).for_each(|col_ix| {
-                                    items.push(self.render_col_wrap(col_ix, window, cx).child(
-                                        self.render_cell(col_ix, window, cx).child(
-                                            self.measure_render_td(row_ix, col_ix, window, cx),
-                                        ),
-                                    ));
+                                    items.push(self.render_td_cell(row_ix, col_ix, window, cx));
                                 });
 
                                 items
@@ -1309,16 +4644,9 @@ where
 
                                         visible_range.for_each(|col_ix| {
                                             let col_ix = col_ix + left_cols_count;
-                                            let el =
-                                                table.render_col_wrap(col_ix, window, cx).child(
-                                                    table.render_cell(col_ix, window, cx).child(
-                                                        table.measure_render_td(
-                                                            row_ix, col_ix, window, cx,
-                                                        ),
-                                                    ),
-                                                );
-
-                                            items.push(el);
+                                            items.push(
+                                                table.render_td_cell(row_ix, col_ix, window, cx),
+                                            );
                                         });
 
                                         items
@@ -1329,6 +4657,36 @@ where
                         )
                         .child(self.delegate.render_last_empty_col(window, cx)),
                 )
+                .when(right_cols_count > 0, |this| {
+                    // Right fixed columns
+                    this.child(
+                        h_flex()
+                            .relative()
+                            .h_full()
+                            .flex_shrink_0()
+                            .children({
+                                let mut items = Vec::with_capacity(right_cols_count);
+
+                                (cols_count - right_cols_count..cols_count).for_each(|col_ix| {
+                                    items.push(self.render_td_cell(row_ix, col_ix, window, cx));
+                                });
+
+                                items
+                            })
+                            .child(
+                                // Fixed columns border
+                                div()
+                                    .absolute()
+                                    .top_0()
+                                    .left_0()
+                                    .bottom_0()
+                                    .w_0()
+                                    .flex_shrink_0()
+                                    .border_l_1()
+                                    .border_color(cx.theme().border),
+                            ),
+                    )
+                })
                 // Row selected style
                 .when_some(self.selected_row, |this, _| {
                     this.when(
@@ -1367,6 +4725,10 @@ where
                         this.on_row_click(ev, row_ix, window, cx);
                     }),
                 )
+                .on_drag(SelectCells(cx.entity_id()), |drag, _, _, cx| {
+                    cx.stop_propagation();
+                    cx.new(|_| drag.clone())
+                })
                 .on_mouse_down(
                     MouseButton::Right,
                     cx.listener(move |this, ev, window, cx| {
@@ -1391,8 +4753,94 @@ where
         }
     }
 
+    /// Render the body list items in the given visible range, the items out of the list are fake rows to fill the table.
+    fn render_list_items(
+        &mut self,
+        visible_range: Range<usize>,
+        left_cols_count: usize,
+        right_cols_count: usize,
+        cols_count: usize,
+        window: &mut Window,
+        cx: &mut Context<Self>,
+    ) -> Vec<AnyElement> {
+        let rows_count = self.delegate.rows_count(cx);
+        let frozen_rows_count = self.delegate.frozen_rows_count(cx).min(rows_count);
+        let list_rows_count = self.row_groups.len();
+
+        // We must calculate the col sizes here, because the col sizes
+        // need render_th first, then that method will set the bounds of each col.
+        let col_sizes: Rc<Vec<gpui::Size<Pixels>>> = Rc::new(self.scrollable_col_sizes());
+
+        // The row index of the list item, the fake rows are after the `rows_count`.
+        let row_ix_of = |table: &Self, ix: usize| match table.row_groups.get(ix) {
+            Some(item) => table.row_groups.row_of_item(item),
+            None => rows_count + ix - list_rows_count,
+        };
+        let visible_rows = if visible_range.is_empty() {
+            frozen_rows_count..frozen_rows_count
+        } else {
+            row_ix_of(self, visible_range.start)..row_ix_of(self, visible_range.end - 1) + 1
+        };
+
+        self.load_more_if_need(rows_count, visible_rows.end, window, cx);
+        self.update_visible_range_if_need(visible_rows.clone(), Axis::Vertical, window, cx);
+
+        if visible_range.end > list_rows_count && list_rows_count > 0 {
+            self.scroll_to_list_item(std::cmp::min(visible_range.start, list_rows_count - 1));
+            cx.notify();
+        }
+
+        let mut items = Vec::with_capacity(visible_range.end.saturating_sub(visible_range.start));
+
+        visible_range.for_each(|ix| {
+            if let Some(ListItem::Group(group_ix)) = self.row_groups.get(ix) {
+                items.push(
+                    self.render_group_row(group_ix, left_cols_count, right_cols_count, window, cx)
+                        .into_any_element(),
+                );
+                return;
+            }
+
+            // Render real rows for available data, and fake rows to fill the table
+            items.push(
+                self.render_table_row(
+                    row_ix_of(self, ix),
+                    rows_count,
+                    left_cols_count,
+                    right_cols_count,
+                    col_sizes.clone(),
+                    cols_count,
+                    window,
+                    cx,
+                )
+                .into_any_element(),
+            );
+        });
+
+        items
+    }
+
+    /// Returns the sizes of the scrollable columns, that is the columns between the left and right fixed columns.
+    fn scrollable_col_sizes(&self) -> Vec<gpui::Size<Pixels>> {
+        let cols_count = self.col_groups.len();
+        self.col_groups
+            .iter()
+            .skip(self.fixed_cols.left)
+            .take(cols_count.saturating_sub(self.fixed_cols.left + self.fixed_cols.right))
+            .map(|col| {
+                if col.hidden {
+                    gpui::Size::default()
+                } else {
+                    col.bounds.size
+                }
+            })
+            .collect()
+    }
+
     /// Calculate the extra rows needed to fill the table empty space when `stripe` is true.
-    fn calculate_extra_rows_needed(&self, rows_count: usize) -> usize {
+    ///
+    /// The `actual_height` is the total height of the body list items.
+    fn calculate_extra_rows_needed(&self, actual_height: Pixels) -> usize {
         if !self.stripe {
             return 0;
         }
@@ -1409,7 +4857,6 @@ where
             .size
             .height;
 
-        let actual_height = row_height * rows_count as f32;
         let remaining_height = total_height - actual_height;
 
         if remaining_height > px(0.) {
@@ -1463,6 +4910,46 @@ where
     }
 }
 
+/// Returns true if the mouse event has the modifier to add a disjoint selection,
+/// that is `cmd` on macOS and `ctrl` on other platforms.
+fn is_multi_select_modifier(ev: &MouseDownEvent) -> bool {
+    #[cfg(target_os = "macos")]
+    return ev.modifiers.platform;
+    #[cfg(not(target_os = "macos"))]
+    return ev.modifiers.control;
+}
+
+/// Returns the number of the list items in the viewport, at least 1.
+///
+/// The `tops` are the top of each item and the bottom of the last one, the items are counted
+/// from the item at `scroll_top` to the last item whose bottom is in the viewport.
+fn page_items_count(tops: &[Pixels], scroll_top: Pixels, viewport_height: Pixels) -> usize {
+    let first = tops
+        .partition_point(|top| *top <= scroll_top)
+        .saturating_sub(1);
+    // The bottom of the item at `ix` is `tops[ix + 1]`.
+    let end = tops
+        .partition_point(|top| *top <= scroll_top + viewport_height)
+        .saturating_sub(1);
+    end.saturating_sub(first).max(1)
+}
+
+/// Returns the new index of the column at `ix`, after the column at `from` is moved to `to`.
+///
+/// The moving is the same as `Vec::remove(from)` then `Vec::insert(to, col)`.
+fn moved_col_ix(ix: usize, from: usize, to: usize) -> usize {
+    if ix == from {
+        return to;
+    }
+
+    let ix = if ix > from { ix - 1 } else { ix };
+    if ix >= to {
+        ix + 1
+    } else {
+        ix
+    }
+}
+
 impl<D> Sizable for Table<D>
 where
     D: TableDelegate,
@@ -1494,9 +4981,44 @@ where
         let horizontal_scroll_handle = self.horizontal_scroll_handle.clone();
         let cols_count: usize = self.delegate.cols_count(cx);
         let left_cols_count = self.fixed_cols.left;
+        let right_cols_count = self.fixed_cols.right;
         let rows_count = self.delegate.rows_count(cx);
+        let frozen_rows_count = self.delegate.frozen_rows_count(cx).min(rows_count);
+        self.prepare_rows(cx);
+        // The rows and group headers in the body list, the frozen rows are rendered above it.
+        let list_rows_count = self.row_groups.len();
         let loading = self.delegate.loading(cx);
-        let extra_rows_needed = self.calculate_extra_rows_needed(rows_count);
+        let has_footer = self.delegate.has_footer(cx);
+        let row_height = self.size.table_row_height();
+        let extra_rows_needed = self.calculate_extra_rows_needed(self.list_content_height(cx));
+
+        let frozen_rows = if frozen_rows_count > 0 {
+            let col_sizes: Rc<Vec<gpui::Size<Pixels>>> = Rc::new(self.scrollable_col_sizes());
+            let rows = (0..frozen_rows_count)
+                .map(|row_ix| {
+                    self.render_table_row(
+                        row_ix,
+                        rows_count,
+                        left_cols_count,
+                        right_cols_count,
+                        col_sizes.clone(),
+                        cols_count,
+                        window,
+                        cx,
+                    )
+                })
+                .collect::<Vec<_>>();
+            Some(
+                v_flex()
+                    .w_full()
+                    .flex_shrink_0()
+                    .border_b_1()
+                    .border_color(cx.theme().border)
+                    .children(rows),
+            )
+        } else {
+            None
+        };
 
         let inner_table = v_flex()
             .key_context("Table")
@@ -1507,16 +5029,53 @@ where
             .on_action(cx.listener(Self::action_select_prev))
             .on_action(cx.listener(Self::action_select_next_col))
             .on_action(cx.listener(Self::action_select_prev_col))
+            .on_action(cx.listener(Self::action_edit_cell))
+            .on_action(cx.listener(Self::action_select_prev_cell))
+            .on_action(cx.listener(Self::action_select_next_cell))
+            .on_action(cx.listener(Self::action_select_all))
+            .on_action(cx.listener(Self::action_copy))
+            .on_action(cx.listener(Self::action_paste))
+            .on_action(cx.listener(Self::action_extend_selection_up))
+            .on_action(cx.listener(Self::action_extend_selection_down))
+            .on_action(cx.listener(Self::action_extend_selection_left))
+            .on_action(cx.listener(Self::action_extend_selection_right))
+            .on_action(cx.listener(Self::action_toggle_col_visible))
+            .on_action(cx.listener(Self::action_sort_col))
+            .on_action(cx.listener(Self::action_fix_col))
+            .on_action(cx.listener(Self::action_auto_fit_col))
+            .on_action(cx.listener(Self::action_reset_col_width))
+            .on_action(cx.listener(Self::action_select_page_up))
+            .on_action(cx.listener(Self::action_select_page_down))
+            .on_action(cx.listener(Self::action_select_row_start))
+            .on_action(cx.listener(Self::action_select_row_end))
+            .on_action(cx.listener(Self::action_select_first_cell))
+            .on_action(cx.listener(Self::action_select_last_cell))
+            .on_action(cx.listener(Self::action_select_edge_up))
+            .on_action(cx.listener(Self::action_select_edge_down))
+            .on_action(cx.listener(Self::action_select_edge_left))
+            .on_action(cx.listener(Self::action_select_edge_right))
+            .on_action(cx.listener(Self::action_find))
+            .on_action(cx.listener(Self::action_find_prev))
+            .on_action(cx.listener(Self::action_close_find))
+            .on_key_down(cx.listener(Self::on_key_down))
+            .on_drag_move(cx.listener(Self::on_drag_select_move))
+            .on_mouse_up(MouseButton::Left, cx.listener(Self::on_drag_select_end))
+            .on_mouse_up_out(MouseButton::Left, cx.listener(Self::on_drag_select_end))
             .size_full()
             .overflow_hidden()
-            .child(self.render_table_head(left_cols_count, window, cx))
+            .child(self.render_table_head(left_cols_count, right_cols_count, window, cx))
+            .when(self.find_visible, |this| {
+                this.child(self.render_find_bar(cx))
+            })
+            .children(frozen_rows)
             .context_menu({
                 let view = view.clone();
                 move |this, window: &mut Window, cx: &mut Context<PopupMenu>| {
-                    if let Some(row_ix) = view.read(cx).right_clicked_row {
-                        view.read(cx)
-                            .delegate
-                            .context_menu(row_ix, this, window, cx)
+                    let table = view.read(cx);
+                    if let Some(col_ix) = table.head_col_at(window.mouse_position()) {
+                        Self::header_menu(&view, col_ix, this, window, cx)
+                    } else if let Some(row_ix) = table.right_clicked_row {
+                        table.delegate.context_menu(row_ix, this, window, cx)
                     } else {
                         this
                     }
@@ -1531,74 +5090,68 @@ where
                     )
                 } else {
                     this.child(
-                        h_flex().id("table-body").flex_grow().size_full().child(
-                            uniform_list(
-                                view,
-                                "table-uniform-list",
-                                rows_count + extra_rows_needed,
-                                {
-                                    move |table, visible_range, window, cx| {
-                                        // We must calculate the col sizes here, because the col sizes
-                                        // need render_th first, then that method will set the bounds of each col.
-                                        let col_sizes: Rc<Vec<gpui::Size<Pixels>>> = Rc::new(
-                                            table
-                                                .col_groups
-                                                .iter()
-                                                .skip(left_cols_count)
-                                                .map(|col| col.bounds.size)
-                                                .collect(),
-                                        );
-
-                                        table.load_more_if_need(
-                                            rows_count,
-                                            visible_range.end,
-                                            window,
-                                            cx,
-                                        );
-                                        table.update_visible_range_if_need(
-                                            visible_range.clone(),
-                                            Axis::Vertical,
-                                            window,
-                                            cx,
-                                        );
-
-                                        if visible_range.end > rows_count {
-                                            table.scroll_to_row(
-                                                std::cmp::min(visible_range.start, rows_count - 1),
-                                                cx,
-                                            );
-                                        }
-
-                                        let mut items = Vec::with_capacity(
-                                            visible_range.end.saturating_sub(visible_range.start),
-                                        );
-
-                                        // Render fake rows to fill the table
-                                        visible_range.for_each(|row_ix| {
-                                            // Render real rows for available data
-                                            items.push(table.render_table_row(
-                                                row_ix,
-                                                rows_count,
+                        h_flex()
+                            .id("table-body")
+                            .flex_grow()
+                            .size_full()
+                            .map(|this| match self.list_item_heights.clone() {
+                                Some(heights) => {
+                                    // Append the fake rows with the default row height.
+                                    let mut item_sizes = (*heights).clone();
+                                    item_sizes.extend(
+                                        (0..extra_rows_needed)
+                                            .map(|_| gpui::size(px(0.), row_height)),
+                                    );
+                                    this.child(
+                                        crate::virtual_list::v_virtual_list(
+                                            view,
+                                            "table-virtual-list",
+                                            Rc::new(item_sizes),
+                                            move |table, visible_range, _, window, cx| {
+                                                table.render_list_items(
+                                                    visible_range,
+                                                    left_cols_count,
+                                                    right_cols_count,
+                                                    cols_count,
+                                                    window,
+                                                    cx,
+                                                )
+                                            },
+                                        )
+                                        .flex_grow()
+                                        .size_full()
+                                        .track_scroll(
+                                            &vertical_scroll_handle.0.borrow().base_handle,
+                                        ),
+                                    )
+                                }
+                                None => this.child(
+                                    uniform_list(
+                                        view,
+                                        "table-uniform-list",
+                                        list_rows_count + extra_rows_needed,
+                                        move |table, visible_range, window, cx| {
+                                            table.render_list_items(
+                                                visible_range,
                                                 left_cols_count,
-                                                col_sizes.clone(),
+                                                right_cols_count,
                                                 cols_count,
                                                 window,
                                                 cx,
-                                            ));
-                                        });
-
-                                        items
-                                    }
-                                },
-                            )
-                            .flex_grow()
-                            .size_full()
-                            .with_sizing_behavior(ListSizingBehavior::Auto)
-                            .track_scroll(vertical_scroll_handle)
-                            .into_any_element(),
-                        ),
+                                            )
+                                        },
+                                    )
+                                    .flex_grow()
+                                    .size_full()
+                                    .with_sizing_behavior(ListSizingBehavior::Auto)
+                                    .track_scroll(vertical_scroll_handle),
+                                ),
+                            }),
                     )
                 }
+            })
+            .when(has_footer, |this| {
+                this.child(self.render_table_foot(left_cols_count, right_cols_count, window, cx))
             });
 
         let view = cx.entity().clone();
@@ -1628,7 +5181,13 @@ where
                     })
             })
             .child(canvas(
-                move |bounds, _, cx| view.update(cx, |r, _| r.bounds = bounds),
+                move |bounds, window, cx| {
+                    let text_style = window.text_style();
+                    view.update(cx, |r, _| {
+                        r.bounds = bounds;
+                        r.text_style = text_style;
+                    })
+                },
                 |_, _, _, _| {},
             ))
             .child(
@@ -1645,3 +5204,255 @@ where
             )
     }
 }
+
+#[cfg(test)]
+mod tests {
+    use gpui::{TestAppContext, VisualTestContext};
+
+    use super::*;
+
+    struct TestDelegate {
+        cols: Vec<(&'static str, Option<ColFixed>)>,
+        /// The calls of `perform_sort`.
+        sorts: Vec<(usize, ColSort)>,
+        movable: bool,
+    }
+
+    impl TestDelegate {
+        fn new(cols: &[(&'static str, Option<ColFixed>)]) -> Self {
+            Self {
+                cols: cols.to_vec(),
+                sorts: Vec::new(),
+                movable: true,
+            }
+        }
+    }
+
+    impl TableDelegate for TestDelegate {
+        fn cols_count(&self, _: &App) -> usize {
+            self.cols.len()
+        }
+
+        fn rows_count(&self, _: &App) -> usize {
+            3
+        }
+
+        fn col_name(&self, col_ix: usize, _: &App) -> SharedString {
+            self.cols[col_ix].0.into()
+        }
+
+        fn col_sort(&self, _: usize, _: &App) -> Option<ColSort> {
+            Some(ColSort::Default)
+        }
+
+        fn col_fixed(&self, col_ix: usize, _: &App) -> Option<ColFixed> {
+            self.cols[col_ix].1
+        }
+
+        fn can_move_col(&self, _: usize, _: &App) -> bool {
+            self.movable
+        }
+
+        fn move_col(
+            &mut self,
+            col_ix: usize,
+            to_ix: usize,
+            _: &mut Window,
+            _: &mut Context<Table<Self>>,
+        ) {
+            let col = self.cols.remove(col_ix);
+            self.cols.insert(to_ix, col);
+        }
+
+        fn perform_sort(
+            &mut self,
+            col_ix: usize,
+            sort: ColSort,
+            _: &mut Window,
+            _: &mut Context<Table<Self>>,
+        ) {
+            self.sorts.push((col_ix, sort));
+        }
+
+        fn render_td(
+            &self,
+            row_ix: usize,
+            col_ix: usize,
+            _: &mut Window,
+            _: &mut Context<Table<Self>>,
+        ) -> impl IntoElement {
+            format!("{}-{}", row_ix, col_ix)
+        }
+    }
+
+    fn build_table(
+        cx: &mut TestAppContext,
+        delegate: TestDelegate,
+    ) -> (Entity<Table<TestDelegate>>, &mut VisualTestContext) {
+        cx.update(crate::init);
+        let cx = cx.add_empty_window();
+        let table = cx.update(|window, cx| cx.new(|cx| Table::new(delegate, window, cx)));
+        (table, cx)
+    }
+
+    #[gpui::test]
+    fn test_multi_sort_remove_last_key(cx: &mut TestAppContext) {
+        let (table, cx) = build_table(cx, TestDelegate::new(&[("a", None), ("b", None)]));
+
+        cx.update(|window, cx| {
+            table.update(cx, |table, cx| {
+                // Default -> Descending -> Ascending -> Default
+                table.perform_multi_sort(1, window, cx);
+                table.perform_multi_sort(1, window, cx);
+                assert_eq!(table.sort_keys(), vec![(1, ColSort::Ascending)]);
+
+                table.perform_multi_sort(1, window, cx);
+                assert!(table.sort_keys().is_empty());
+                // The sort is reset by the removed key.
+                assert_eq!(
+                    table.delegate().sorts,
+                    vec![
+                        (1, ColSort::Descending),
+                        (1, ColSort::Ascending),
+                        (1, ColSort::Default)
+                    ]
+                );
+            })
+        });
+    }
+
+    #[test]
+    fn test_page_items_count() {
+        // The rows are 20px, except the tall rows 2 and 3 of 100px.
+        let heights = [20., 20., 100., 100., 20., 20., 20., 20.];
+        let mut top = px(0.);
+        let tops = std::iter::once(top)
+            .chain(heights.iter().map(|height| {
+                top += px(*height);
+                top
+            }))
+            .collect::<Vec<_>>();
+
+        // 2 rows + 1 tall row, the next tall row is not entirely in the viewport.
+        assert_eq!(page_items_count(&tops, px(0.), px(200.)), 3);
+        // From the first tall row: 2 tall rows.
+        assert_eq!(page_items_count(&tops, px(40.), px(200.)), 2);
+        // From the last tall row: 1 tall row + 4 rows.
+        assert_eq!(page_items_count(&tops, px(140.), px(200.)), 5);
+        // A tall row is higher than the viewport.
+        assert_eq!(page_items_count(&tops, px(40.), px(50.)), 1);
+        assert_eq!(page_items_count(&[], px(0.), px(200.)), 1);
+    }
+
+    #[test]
+    fn test_moved_col_ix() {
+        // Move the column 1 to 3: [a, b, c, d, e] -> [a, c, d, b, e]
+        assert_eq!(moved_col_ix(0, 1, 3), 0);
+        assert_eq!(moved_col_ix(1, 1, 3), 3);
+        assert_eq!(moved_col_ix(2, 1, 3), 1);
+        assert_eq!(moved_col_ix(3, 1, 3), 2);
+        assert_eq!(moved_col_ix(4, 1, 3), 4);
+
+        // Move the column 3 to 0: [a, b, c, d, e] -> [d, a, b, c, e]
+        assert_eq!(moved_col_ix(3, 3, 0), 0);
+        assert_eq!(moved_col_ix(0, 3, 0), 1);
+        assert_eq!(moved_col_ix(2, 3, 0), 3);
+        assert_eq!(moved_col_ix(4, 3, 0), 4);
+
+        // Same as moving the columns in a Vec, for every move in 5 columns.
+        let cols = ["a", "b", "c", "d", "e"];
+        for from in 0..cols.len() {
+            for to in 0..cols.len() {
+                let mut moved = cols.to_vec();
+                let col = moved.remove(from);
+                moved.insert(to, col);
+                for (ix, col) in cols.iter().enumerate() {
+                    assert_eq!(moved[moved_col_ix(ix, from, to)], *col);
+                }
+            }
+        }
+    }
+
+    fn layout(cols: &[(&str, Option<ColFixed>)]) -> TableLayoutState {
+        TableLayoutState {
+            cols: cols
+                .iter()
+                .map(|&(key, fixed)| ColLayoutState {
+                    key: key.to_string(),
+                    width: px(100.),
+                    hidden: false,
+                    sort: None,
+                    fixed,
+                })
+                .collect(),
+            sort_keys: vec![],
+        }
+    }
+
+    #[gpui::test]
+    fn test_load_layout_moves_fixed_cols_to_edges(cx: &mut TestAppContext) {
+        let cols = [("a", None), ("b", None), ("c", None), ("d", None)];
+        let (table, cx) = build_table(cx, TestDelegate::new(&cols));
+
+        cx.update(|window, cx| {
+            table.update(cx, |table, cx| {
+                // The fixed columns of a stale layout are in the middle.
+                table.load_layout(
+                    layout(&[
+                        ("a", None),
+                        ("b", Some(ColFixed::Right)),
+                        ("c", Some(ColFixed::Left)),
+                        ("d", None),
+                    ]),
+                    window,
+                    cx,
+                );
+                let names = table
+                    .delegate()
+                    .cols
+                    .iter()
+                    .map(|col| col.0)
+                    .collect::<Vec<_>>();
+                assert_eq!(names, vec!["c", "a", "d", "b"]);
+                let fixed = table
+                    .col_groups
+                    .iter()
+                    .map(|col| col.fixed)
+                    .collect::<Vec<_>>();
+                assert_eq!(
+                    fixed,
+                    vec![Some(ColFixed::Left), None, None, Some(ColFixed::Right)]
+                );
+                assert_eq!((table.fixed_cols.left, table.fixed_cols.right), (1, 1));
+            })
+        });
+    }
+
+    #[gpui::test]
+    fn test_load_layout_unpins_unmovable_cols(cx: &mut TestAppContext) {
+        let cols = [("a", None), ("b", None), ("c", None)];
+        let mut delegate = TestDelegate::new(&cols);
+        delegate.movable = false;
+        let (table, cx) = build_table(cx, delegate);
+
+        cx.update(|window, cx| {
+            table.update(cx, |table, cx| {
+                table.load_layout(
+                    layout(&[("c", None), ("b", Some(ColFixed::Left)), ("a", None)]),
+                    window,
+                    cx,
+                );
+                // The columns keep their order, and the fixed column in the middle is unpinned.
+                let names = table
+                    .delegate()
+                    .cols
+                    .iter()
+                    .map(|col| col.0)
+                    .collect::<Vec<_>>();
+                assert_eq!(names, vec!["a", "b", "c"]);
+                assert!(table.col_groups.iter().all(|col| col.fixed.is_none()));
+                assert_eq!((table.fixed_cols.left, table.fixed_cols.right), (0, 0));
+            })
+        });
+    }
+}
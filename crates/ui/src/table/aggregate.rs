@@ -0,0 +1,76 @@
+/// The aggregate function of a column, used to render the footer row.
+#[derive(Debug, Clone, Copy, PartialEq, Eq)]
+pub enum Aggregate {
+    /// The sum of the values.
+    Sum,
+    /// The average of the values.
+    Avg,
+    /// The minimum value.
+    Min,
+    /// The maximum value.
+    Max,
+    /// The number of the values.
+    Count,
+}
+
+impl Aggregate {
+    /// Compute the aggregate of the given values.
+    ///
+    /// Returns None for `Avg`, `Min` and `Max` if there is no value.
+    pub fn compute(&self, values: impl IntoIterator<Item = f64>) -> Option<f64> {
+        let mut count = 0;
+        let mut sum = 0.;
+        let mut min = f64::INFINITY;
+        let mut max = f64::NEG_INFINITY;
+        for value in values {
+            count += 1;
+            sum += value;
+            min = min.min(value);
+            max = max.max(value);
+        }
+
+        match self {
+            Self::Sum => Some(sum),
+            Self::Count => Some(count as f64),
+            _ if count == 0 => None,
+            Self::Avg => Some(sum / count as f64),
+            Self::Min => Some(min),
+            Self::Max => Some(max),
+        }
+    }
+}
+
+/// Format the aggregate value for display, rounded to 2 decimal places.
+pub(crate) fn format_aggregate(value: f64) -> String {
+    let value = (value * 100.).round() / 100.;
+    format!("{}", value)
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+
+    #[test]
+    fn test_aggregate() {
+        let values = [3., 1.5, -2., 5.5];
+        assert_eq!(Aggregate::Sum.compute(values), Some(8.));
+        assert_eq!(Aggregate::Avg.compute(values), Some(2.));
+        assert_eq!(Aggregate::Min.compute(values), Some(-2.));
+        assert_eq!(Aggregate::Max.compute(values), Some(5.5));
+        assert_eq!(Aggregate::Count.compute(values), Some(4.));
+
+        let empty: [f64; 0] = [];
+        assert_eq!(Aggregate::Sum.compute(empty), Some(0.));
+        assert_eq!(Aggregate::Count.compute(empty), Some(0.));
+        assert_eq!(Aggregate::Avg.compute(empty), None);
+        assert_eq!(Aggregate::Min.compute(empty), None);
+        assert_eq!(Aggregate::Max.compute(empty), None);
+    }
+
+    #[test]
+    fn test_format_aggregate() {
+        assert_eq!(format_aggregate(10.), "10");
+        assert_eq!(format_aggregate(1. / 3.), "0.33");
+        assert_eq!(format_aggregate(-2.006), "-2.01");
+    }
+}
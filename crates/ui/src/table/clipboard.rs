@@ -0,0 +1,225 @@
+use gpui::{App, Global};
+
+/// The system clipboard to write the HTML table flavour, the GPUI clipboard only has the text and image entries.
+///
+/// It is kept alive in the global, because it serves the clipboard content on Linux.
+struct HtmlClipboard(Option<arboard::Clipboard>);
+
+impl Global for HtmlClipboard {}
+
+/// Write the HTML with the plain text as its alternative to the system clipboard.
+///
+/// Returns false if the system clipboard is not available.
+pub(crate) fn write_html(html: &str, text: &str, cx: &mut App) -> bool {
+    if !cx.has_global::<HtmlClipboard>() {
+        cx.set_global(HtmlClipboard(arboard::Clipboard::new().ok()));
+    }
+    let Some(clipboard) = cx.global_mut::<HtmlClipboard>().0.as_mut() else {
+        return false;
+    };
+    clipboard.set_html(html, Some(text)).is_ok()
+}
+
+/// Encode the grid of cells into the tab-separated text, which can be pasted into Excel or LibreOffice.
+///
+/// The cell that contains a tab, newline or quote will be quoted, and the quote will be escaped as `""`.
+pub(crate) fn to_tsv(grid: &[Vec<String>]) -> String {
+    to_delimited(grid, '\t')
+}
+
+/// Encode the grid of cells into the delimiter-separated text, rows are separated by `\n`.
+pub(crate) fn to_delimited(grid: &[Vec<String>], delimiter: char) -> String {
+    let mut out = String::new();
+    for (row_ix, row) in grid.iter().enumerate() {
+        if row_ix > 0 {
+            out.push('\n');
+        }
+
+        for (col_ix, cell) in row.iter().enumerate() {
+            if col_ix > 0 {
+                out.push(delimiter);
+            }
+
+            if cell.contains([delimiter, '\n', '\r', '"']) {
+                out.push('"');
+                out.push_str(&cell.replace('"', "\"\""));
+                out.push('"');
+            } else {
+                out.push_str(cell);
+            }
+        }
+    }
+    out
+}
+
+/// Encode the grid of cells into a HTML table, which keeps the cells when pasted into Excel or LibreOffice.
+pub(crate) fn to_html(grid: &[Vec<String>]) -> String {
+    let mut out = String::from("<table>");
+    for row in grid {
+        out.push_str(&to_html_row(row, "td"));
+    }
+    out.push_str("</table>");
+    out
+}
+
+/// Encode a row of cells into a HTML table row, the `tag` of the cells is `td` or `th`.
+pub(crate) fn to_html_row(row: &[String], tag: &str) -> String {
+    let mut out = String::from("<tr>");
+    for cell in row {
+        out.push_str(&format!("<{}>", tag));
+        for c in cell.chars() {
+            match c {
+                '&' => out.push_str("&amp;"),
+                '<' => out.push_str("&lt;"),
+                '>' => out.push_str("&gt;"),
+                '"' => out.push_str("&quot;"),
+                '\n' => out.push_str("<br>"),
+                _ => out.push(c),
+            }
+        }
+        out.push_str(&format!("</{}>", tag));
+    }
+    out.push_str("</tr>");
+    out
+}
+
+/// Parse the TSV or CSV text into a grid of cells.
+///
+/// If the text contains a tab, it will be parsed as TSV. Otherwise it is parsed as CSV only if it
+/// has multiple lines with the same number of (more than one) cells, e.g. `1,234.56` is a single cell.
+/// Any other text is a single column with one cell per line, like a column copied from a spreadsheet.
+/// The quoted cells (with `""` as escaped quote) can contain delimiters and newlines.
+pub(crate) fn parse_grid(text: &str) -> Vec<Vec<String>> {
+    if text.contains('\t') {
+        return parse_delimited(text, '\t');
+    }
+
+    let grid = parse_delimited(text, ',');
+    let is_csv =
+        grid.len() > 1 && grid[0].len() > 1 && grid.iter().all(|row| row.len() == grid[0].len());
+    if is_csv {
+        return grid;
+    }
+
+    // No tab in the text, so each line is a single cell.
+    parse_delimited(text, '\t')
+}
+
+/// Parse the delimiter-separated text into a grid of cells.
+fn parse_delimited(text: &str, delimiter: char) -> Vec<Vec<String>> {
+    let mut grid = vec![];
+    let mut row = vec![];
+    let mut cell = String::new();
+    let mut in_quotes = false;
+    let mut chars = text.chars().peekable();
+
+    while let Some(c) = chars.next() {
+        if in_quotes {
+            if c == '"' {
+                if chars.peek() == Some(&'"') {
+                    chars.next();
+                    cell.push('"');
+                } else {
+                    in_quotes = false;
+                }
+            } else {
+                cell.push(c);
+            }
+            continue;
+        }
+
+        match c {
+            '"' if cell.is_empty() => in_quotes = true,
+            '\r' => {}
+            '\n' => {
+                row.push(std::mem::take(&mut cell));
+                grid.push(std::mem::take(&mut row));
+            }
+            c if c == delimiter => row.push(std::mem::take(&mut cell)),
+            c => cell.push(c),
+        }
+    }
+
+    // The last line without a trailing newline.
+    if !cell.is_empty() || !row.is_empty() {
+        row.push(cell);
+        grid.push(row);
+    }
+
+    grid
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+
+    fn grid(rows: &[&[&str]]) -> Vec<Vec<String>> {
+        rows.iter()
+            .map(|row| row.iter().map(|cell| cell.to_string()).collect())
+            .collect()
+    }
+
+    #[test]
+    fn test_to_tsv() {
+        let cells = grid(&[
+            &["Name", "Value"],
+            &["a\tb", "1"],
+            &["say \"hi\"", "line\nbreak"],
+        ]);
+        assert_eq!(
+            to_tsv(&cells),
+            "Name\tValue\n\"a\tb\"\t1\n\"say \"\"hi\"\"\"\t\"line\nbreak\""
+        );
+        assert_eq!(to_delimited(&grid(&[&["a,b", "c"]]), ','), "\"a,b\",c");
+    }
+
+    #[test]
+    fn test_to_html() {
+        let cells = grid(&[&["<b>", "a & b"], &["1", "x\ny"]]);
+        assert_eq!(
+            to_html_row(&cells[0], "td"),
+            "<tr><td>&lt;b&gt;</td><td>a &amp; b</td></tr>"
+        );
+        assert_eq!(
+            to_html_row(&cells[1], "th"),
+            "<tr><th>1</th><th>x<br>y</th></tr>"
+        );
+        assert_eq!(
+            to_html(&cells),
+            "<table><tr><td>&lt;b&gt;</td><td>a &amp; b</td></tr><tr><td>1</td><td>x<br>y</td></tr></table>"
+        );
+    }
+
+    #[test]
+    fn test_parse_grid() {
+        assert_eq!(
+            parse_grid("a\tb\r\nc\td\r\n"),
+            grid(&[&["a", "b"], &["c", "d"]])
+        );
+        assert_eq!(parse_grid("a,b\n1,"), grid(&[&["a", "b"], &["1", ""]]));
+        assert_eq!(
+            parse_grid("\"a,b\",\"say \"\"hi\"\"\"\n\"line\nbreak\",c"),
+            grid(&[&["a,b", "say \"hi\""], &["line\nbreak", "c"]])
+        );
+        assert_eq!(parse_grid("single"), grid(&[&["single"]]));
+        assert_eq!(parse_grid("1,234.56"), grid(&[&["1,234.56"]]));
+        assert_eq!(parse_grid("1,234.56\n"), grid(&[&["1,234.56"]]));
+        assert_eq!(
+            parse_grid("Hello, world\nBye"),
+            grid(&[&["Hello, world"], &["Bye"]])
+        );
+        assert_eq!(parse_grid("1\n2\r\n3\n"), grid(&[&["1"], &["2"], &["3"]]));
+        assert_eq!(
+            parse_grid("\"line\nbreak\"\n2"),
+            grid(&[&["line\nbreak"], &["2"]])
+        );
+        assert_eq!(parse_grid(""), Vec::<Vec<String>>::new());
+
+        let cells = grid(&[
+            &["Name", "Value"],
+            &["a\tb", "1"],
+            &["say \"hi\"", "line\nbreak"],
+        ]);
+        assert_eq!(parse_grid(&to_tsv(&cells)), cells);
+    }
+}
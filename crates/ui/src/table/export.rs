@@ -0,0 +1,257 @@
+use std::{fmt, io};
+
+use gpui::SharedString;
+use serde::{ser::SerializeMap as _, Serialize, Serializer};
+
+use super::clipboard;
+
+/// The plain data value of a cell, returned by `TableDelegate::cell_value`.
+#[derive(Debug, Clone, Default, PartialEq)]
+pub enum CellValue {
+    #[default]
+    Empty,
+    Text(SharedString),
+    Number(f64),
+    Bool(bool),
+}
+
+impl CellValue {
+    /// Returns true if the value is `Empty`.
+    pub fn is_empty(&self) -> bool {
+        matches!(self, Self::Empty)
+    }
+
+    /// Returns the number of the value, the text will be parsed as a number.
+    pub fn as_number(&self) -> Option<f64> {
+        match self {
+            Self::Number(value) => Some(*value),
+            Self::Text(text) => text.trim().parse().ok(),
+            _ => None,
+        }
+    }
+}
+
+/// Serialize the value as a JSON value, the `Empty` is `null`.
+impl Serialize for CellValue {
+    fn serialize<S: Serializer>(&self, serializer: S) -> Result<S::Ok, S::Error> {
+        match self {
+            Self::Empty => serializer.serialize_none(),
+            Self::Text(text) => serializer.serialize_str(text),
+            // The NaN and infinity are not valid JSON numbers.
+            Self::Number(value) if value.is_finite() => serializer.serialize_f64(*value),
+            Self::Number(_) => serializer.serialize_none(),
+            Self::Bool(value) => serializer.serialize_bool(*value),
+        }
+    }
+}
+
+impl fmt::Display for CellValue {
+    fn fmt(&self, f: &mut fmt::Formatter<'_>) -> fmt::Result {
+        match self {
+            Self::Empty => Ok(()),
+            Self::Text(text) => write!(f, "{}", text),
+            Self::Number(value) => write!(f, "{}", value),
+            Self::Bool(value) => write!(f, "{}", value),
+        }
+    }
+}
+
+impl From<SharedString> for CellValue {
+    fn from(value: SharedString) -> Self {
+        Self::Text(value)
+    }
+}
+
+impl From<String> for CellValue {
+    fn from(value: String) -> Self {
+        Self::Text(value.into())
+    }
+}
+
+impl From<&str> for CellValue {
+    fn from(value: &str) -> Self {
+        Self::Text(value.to_string().into())
+    }
+}
+
+impl From<f64> for CellValue {
+    fn from(value: f64) -> Self {
+        Self::Number(value)
+    }
+}
+
+impl From<bool> for CellValue {
+    fn from(value: bool) -> Self {
+        Self::Bool(value)
+    }
+}
+
+impl<T: Into<CellValue>> From<Option<T>> for CellValue {
+    fn from(value: Option<T>) -> Self {
+        value.map_or(Self::Empty, Into::into)
+    }
+}
+
+/// The format of the exported table data.
+#[derive(Debug, Clone, Copy, PartialEq, Eq)]
+pub enum ExportFormat {
+    /// Comma-separated values, with a header row of the column names.
+    Csv,
+    /// Tab-separated values, with a header row of the column names.
+    Tsv,
+    /// An array of objects keyed by the column keys, see `TableDelegate::col_key`.
+    Json,
+    /// A HTML table, with a header row of the column names.
+    Html,
+}
+
+/// The rows of the table to export.
+#[derive(Debug, Clone, Copy, Default, PartialEq, Eq)]
+pub enum ExportRows {
+    /// All rows of the delegate, that is the filtered rows if the delegate applies the filters.
+    #[default]
+    All,
+    /// The rows in the current visible range.
+    Visible,
+    /// The selected rows, or the rows of the selected cells.
+    Selected,
+}
+
+/// Write the rows of cells to the writer in the given format, row by row.
+///
+/// The `cols` are the (key, name) of the columns, the key is used by JSON, and the name is used by the header row.
+pub(crate) fn write_export(
+    writer: &mut impl io::Write,
+    format: ExportFormat,
+    cols: &[(SharedString, SharedString)],
+    rows: impl IntoIterator<Item = Vec<CellValue>>,
+) -> io::Result<()> {
+    match format {
+        ExportFormat::Csv | ExportFormat::Tsv => {
+            let delimiter = if format == ExportFormat::Csv {
+                ','
+            } else {
+                '\t'
+            };
+            let header = cols.iter().map(|(_, name)| name.to_string()).collect();
+            let rows = rows.into_iter().map(|row| {
+                row.iter()
+                    .map(|value| value.to_string())
+                    .collect::<Vec<_>>()
+            });
+            for row in std::iter::once(header).chain(rows) {
+                let line = clipboard::to_delimited(std::slice::from_ref(&row), delimiter);
+                writer.write_all(line.as_bytes())?;
+                writer.write_all(b"\n")?;
+            }
+        }
+        ExportFormat::Html => {
+            writer.write_all(b"<table>\n")?;
+            let header = cols
+                .iter()
+                .map(|(_, name)| name.to_string())
+                .collect::<Vec<_>>();
+            writer.write_all(clipboard::to_html_row(&header, "th").as_bytes())?;
+            writer.write_all(b"\n")?;
+            for row in rows {
+                let row = row
+                    .iter()
+                    .map(|value| value.to_string())
+                    .collect::<Vec<_>>();
+                writer.write_all(clipboard::to_html_row(&row, "td").as_bytes())?;
+                writer.write_all(b"\n")?;
+            }
+            writer.write_all(b"</table>\n")?;
+        }
+        ExportFormat::Json => {
+            writer.write_all(b"[")?;
+            let mut is_empty = true;
+            for row in rows {
+                let separator: &[u8] = if is_empty { b"\n  " } else { b",\n  " };
+                writer.write_all(separator)?;
+                serde_json::to_writer(&mut *writer, &JsonRow { cols, values: &row })?;
+                is_empty = false;
+            }
+            let end: &[u8] = if is_empty { b"]\n" } else { b"\n]\n" };
+            writer.write_all(end)?;
+        }
+    }
+
+    Ok(())
+}
+
+/// A row of cells serialized as a JSON object keyed by the column keys, in the column order.
+struct JsonRow<'a> {
+    cols: &'a [(SharedString, SharedString)],
+    values: &'a [CellValue],
+}
+
+impl Serialize for JsonRow<'_> {
+    fn serialize<S: Serializer>(&self, serializer: S) -> Result<S::Ok, S::Error> {
+        let mut map = serializer.serialize_map(Some(self.values.len()))?;
+        for ((key, _), value) in self.cols.iter().zip(self.values) {
+            map.serialize_entry(key.as_ref(), value)?;
+        }
+        map.end()
+    }
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+
+    fn export(format: ExportFormat) -> String {
+        let cols = [
+            ("id".into(), "ID".into()),
+            ("name".into(), "Name".into()),
+            ("done".into(), "Done".into()),
+        ];
+        let rows = vec![
+            vec![1.0.into(), "Labor, \"A\"".into(), true.into()],
+            vec![2.5.into(), CellValue::Empty, false.into()],
+        ];
+        let mut out = vec![];
+        write_export(&mut out, format, &cols, rows).unwrap();
+        String::from_utf8(out).unwrap()
+    }
+
+    #[test]
+    fn test_cell_value() {
+        assert_eq!(CellValue::from(1.5).to_string(), "1.5");
+        assert_eq!(CellValue::from("CO2").to_string(), "CO2");
+        assert_eq!(CellValue::from(None::<f64>), CellValue::Empty);
+        assert_eq!(CellValue::Empty.to_string(), "");
+        assert_eq!(CellValue::from(" 12 ").as_number(), Some(12.));
+        assert_eq!(CellValue::from(true).as_number(), None);
+        assert!(CellValue::default().is_empty());
+    }
+
+    #[test]
+    fn test_write_export() {
+        assert_eq!(
+            export(ExportFormat::Csv),
+            "ID,Name,Done\n1,\"Labor, \"\"A\"\"\",true\n2.5,,false\n"
+        );
+        assert_eq!(
+            export(ExportFormat::Tsv),
+            "ID\tName\tDone\n1\t\"Labor, \"\"A\"\"\"\ttrue\n2.5\t\tfalse\n"
+        );
+        assert_eq!(
+            export(ExportFormat::Json),
+            "[\n  {\"id\":1.0,\"name\":\"Labor, \\\"A\\\"\",\"done\":true},\n  {\"id\":2.5,\"name\":null,\"done\":false}\n]\n"
+        );
+        assert_eq!(
+            export(ExportFormat::Html),
+            "<table>\n<tr><th>ID</th><th>Name</th><th>Done</th></tr>\n<tr><td>1</td><td>Labor, &quot;A&quot;</td><td>true</td></tr>\n<tr><td>2.5</td><td></td><td>false</td></tr>\n</table>\n"
+        );
+        assert_eq!(
+            serde_json::to_string(&vec![
+                CellValue::from("a\tb\u{1}"),
+                f64::NAN.into(),
+                CellValue::Empty,
+            ])
+            .unwrap(),
+            "[\"a\\tb\\u0001\",null,null]"
+        );
+    }
+}
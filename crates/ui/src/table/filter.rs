@@ -0,0 +1,397 @@
+use std::{collections::HashSet, rc::Rc};
+
+use gpui::{
+    prelude::FluentBuilder as _, px, App, AppContext, Context, DismissEvent, Entity, EventEmitter,
+    FocusHandle, Focusable, InteractiveElement as _, IntoElement, ParentElement, Render,
+    SharedString, StatefulInteractiveElement as _, Styled, Subscription, Window,
+};
+use rust_i18n::t;
+
+use crate::{
+    button::{Button, ButtonVariants as _},
+    checkbox::Checkbox,
+    h_flex,
+    input::{InputEvent, TextInput},
+    v_flex, ActiveTheme, Selectable as _, Sizable as _,
+};
+
+/// The kind of the column filter UI, returned by `TableDelegate::col_filter_kind`.
+#[derive(Debug, Clone, Copy, PartialEq, Eq)]
+pub enum ColFilterKind {
+    /// A checklist of the distinct values, the values are from `TableDelegate::col_filter_values`.
+    Values,
+    /// A text filter with contains or equals.
+    Text,
+    /// A numeric range filter.
+    Number,
+}
+
+/// The operator of the text filter.
+#[derive(Debug, Clone, Copy, Default, PartialEq, Eq)]
+pub enum TextFilterOp {
+    #[default]
+    Contains,
+    Equals,
+}
+
+/// The filter state of a column.
+#[derive(Debug, Clone, PartialEq)]
+pub enum ColFilter {
+    /// Only the rows with the checked values are visible.
+    Values(Vec<SharedString>),
+    /// Match the text case-insensitively.
+    Text {
+        op: TextFilterOp,
+        text: SharedString,
+    },
+    /// Match the number in the range, `None` means unbounded.
+    Number { min: Option<f64>, max: Option<f64> },
+}
+
+impl ColFilter {
+    /// Returns true if the given cell text matches this filter.
+    ///
+    /// For the `Number` filter, the text will be parsed as a number, and not matched if it is not a number.
+    pub fn matches(&self, text: &str) -> bool {
+        match self {
+            Self::Values(values) => values.iter().any(|value| value.as_ref() == text),
+            Self::Text { op, text: query } => {
+                let text = text.to_lowercase();
+                let query = query.to_lowercase();
+                match op {
+                    TextFilterOp::Contains => text.contains(&query),
+                    TextFilterOp::Equals => text == query,
+                }
+            }
+            Self::Number { .. } => text
+                .trim()
+                .parse::<f64>()
+                .map_or(false, |value| self.matches_number(value)),
+        }
+    }
+
+    /// Returns true if the given number matches this filter, only for the `Number` filter.
+    pub fn matches_number(&self, value: f64) -> bool {
+        match self {
+            Self::Number { min, max } => {
+                min.map_or(true, |min| value >= min) && max.map_or(true, |max| value <= max)
+            }
+            _ => false,
+        }
+    }
+
+    /// Returns true if this filter not filter anything, e.g.: empty text or unbounded range.
+    pub fn is_empty(&self) -> bool {
+        match self {
+            Self::Values(_) => false,
+            Self::Text { text, .. } => text.is_empty(),
+            Self::Number { min, max } => min.is_none() && max.is_none(),
+        }
+    }
+}
+
+/// The popover content to edit the filter of a column.
+pub(crate) struct FilterPanel {
+    focus_handle: FocusHandle,
+    kind: ColFilterKind,
+    values: Vec<SharedString>,
+    checked_values: HashSet<SharedString>,
+    text_op: TextFilterOp,
+    text_input: Entity<TextInput>,
+    min_input: Entity<TextInput>,
+    max_input: Entity<TextInput>,
+    on_apply: Rc<dyn Fn(Option<ColFilter>, &mut Window, &mut App)>,
+    _subscriptions: Vec<Subscription>,
+}
+
+impl FilterPanel {
+    pub(crate) fn new(
+        kind: ColFilterKind,
+        values: Vec<SharedString>,
+        filter: Option<ColFilter>,
+        on_apply: impl Fn(Option<ColFilter>, &mut Window, &mut App) + 'static,
+        window: &mut Window,
+        cx: &mut Context<Self>,
+    ) -> Self {
+        fn number_input(
+            placeholder: SharedString,
+            window: &mut Window,
+            cx: &mut App,
+        ) -> Entity<TextInput> {
+            cx.new(|cx| {
+                TextInput::new(window, cx)
+                    .small()
+                    .placeholder(placeholder)
+                    .validate(|s| s.is_empty() || s == "-" || s.parse::<f64>().is_ok())
+            })
+        }
+
+        let text_input = cx.new(|cx| TextInput::new(window, cx).small());
+        let min_input = number_input(t!("Table.Min").into(), window, cx);
+        let max_input = number_input(t!("Table.Max").into(), window, cx);
+
+        // All values are checked by default.
+        let mut checked_values: HashSet<SharedString> = values.iter().cloned().collect();
+        let mut text_op = TextFilterOp::default();
+        match filter {
+            Some(ColFilter::Values(filter_values)) => {
+                checked_values = filter_values.into_iter().collect();
+            }
+            Some(ColFilter::Text { op, text }) => {
+                text_op = op;
+                text_input.update(cx, |input, cx| input.set_text(text, window, cx));
+            }
+            Some(ColFilter::Number { min, max }) => {
+                if let Some(min) = min {
+                    min_input.update(cx, |input, cx| input.set_text(min.to_string(), window, cx));
+                }
+                if let Some(max) = max {
+                    max_input.update(cx, |input, cx| input.set_text(max.to_string(), window, cx));
+                }
+            }
+            None => {}
+        }
+
+        let _subscriptions = [&text_input, &min_input, &max_input]
+            .into_iter()
+            .map(|input| {
+                cx.subscribe_in(input, window, |this, _, ev: &InputEvent, window, cx| {
+                    if let InputEvent::PressEnter = ev {
+                        this.apply(window, cx);
+                    }
+                })
+            })
+            .collect();
+
+        Self {
+            focus_handle: cx.focus_handle(),
+            kind,
+            values,
+            checked_values,
+            text_op,
+            text_input,
+            min_input,
+            max_input,
+            on_apply: Rc::new(on_apply),
+            _subscriptions,
+        }
+    }
+
+    /// Returns the filter from the current inputs, None if it is not filter anything.
+    fn filter(&self, cx: &App) -> Option<ColFilter> {
+        let filter = match self.kind {
+            ColFilterKind::Values => {
+                if self.checked_values.len() == self.values.len() {
+                    return None;
+                }
+
+                ColFilter::Values(
+                    self.values
+                        .iter()
+                        .filter(|value| self.checked_values.contains(*value))
+                        .cloned()
+                        .collect(),
+                )
+            }
+            ColFilterKind::Text => ColFilter::Text {
+                op: self.text_op,
+                text: self.text_input.read(cx).text(),
+            },
+            ColFilterKind::Number => ColFilter::Number {
+                min: self.min_input.read(cx).text().parse().ok(),
+                max: self.max_input.read(cx).text().parse().ok(),
+            },
+        };
+
+        if filter.is_empty() {
+            None
+        } else {
+            Some(filter)
+        }
+    }
+
+    fn apply(&mut self, window: &mut Window, cx: &mut Context<Self>) {
+        let filter = self.filter(cx);
+        (self.on_apply)(filter, window, cx);
+        cx.emit(DismissEvent);
+    }
+
+    fn clear(&mut self, window: &mut Window, cx: &mut Context<Self>) {
+        (self.on_apply)(None, window, cx);
+        cx.emit(DismissEvent);
+    }
+
+    fn render_values(&self, cx: &mut Context<Self>) -> impl IntoElement {
+        let all_checked = self.checked_values.len() == self.values.len();
+
+        v_flex()
+            .gap_1()
+            .child(
+                Checkbox::new("select-all")
+                    .label(SharedString::from(t!("Table.Select All")))
+                    .checked(all_checked)
+                    .on_click(cx.listener(|this, checked: &bool, _, cx| {
+                        this.checked_values = if *checked {
+                            this.values.iter().cloned().collect()
+                        } else {
+                            HashSet::new()
+                        };
+                        cx.notify();
+                    })),
+            )
+            .child(
+                v_flex()
+                    .id("values")
+                    .gap_1()
+                    .max_h(px(240.))
+                    .overflow_y_scroll()
+                    .children(self.values.iter().enumerate().map(|(ix, value)| {
+                        let value = value.clone();
+                        Checkbox::new(("value", ix))
+                            .label(value.clone())
+                            .checked(self.checked_values.contains(&value))
+                            .on_click(cx.listener(move |this, checked: &bool, _, cx| {
+                                if *checked {
+                                    this.checked_values.insert(value.clone());
+                                } else {
+                                    this.checked_values.remove(&value);
+                                }
+                                cx.notify();
+                            }))
+                    })),
+            )
+    }
+
+    fn render_text(&self, cx: &mut Context<Self>) -> impl IntoElement {
+        v_flex()
+            .gap_2()
+            .child(
+                h_flex().gap_1().children(
+                    [
+                        (TextFilterOp::Contains, t!("Table.Contains")),
+                        (TextFilterOp::Equals, t!("Table.Equals")),
+                    ]
+                    .into_iter()
+                    .map(|(op, label)| {
+                        Button::new(SharedString::from(format!("op-{:?}", op)))
+                            .label(label)
+                            .xsmall()
+                            .ghost()
+                            .selected(self.text_op == op)
+                            .on_click(cx.listener(move |this, _, _, cx| {
+                                this.text_op = op;
+                                cx.notify();
+                            }))
+                    }),
+                ),
+            )
+            .child(self.text_input.clone())
+    }
+
+    fn render_number(&self, _: &mut Context<Self>) -> impl IntoElement {
+        h_flex()
+            .gap_2()
+            .items_center()
+            .child(self.min_input.clone())
+            .child("-")
+            .child(self.max_input.clone())
+    }
+}
+
+impl EventEmitter<DismissEvent> for FilterPanel {}
+
+impl Focusable for FilterPanel {
+    fn focus_handle(&self, _: &App) -> FocusHandle {
+        self.focus_handle.clone()
+    }
+}
+
+impl Render for FilterPanel {
+    fn render(&mut self, _: &mut Window, cx: &mut Context<Self>) -> impl IntoElement {
+        v_flex()
+            .track_focus(&self.focus_handle)
+            .w(px(220.))
+            .p_2()
+            .gap_3()
+            .text_color(cx.theme().popover_foreground)
+            .map(|this| match self.kind {
+                ColFilterKind::Values => this.child(self.render_values(cx)),
+                ColFilterKind::Text => this.child(self.render_text(cx)),
+                ColFilterKind::Number => this.child(self.render_number(cx)),
+            })
+            .child(
+                h_flex()
+                    .gap_2()
+                    .justify_end()
+                    .child(
+                        Button::new("clear")
+                            .label(t!("Table.Clear"))
+                            .small()
+                            .ghost()
+                            .on_click(cx.listener(|this, _, window, cx| this.clear(window, cx))),
+                    )
+                    .child(
+                        Button::new("apply")
+                            .label(t!("Table.Apply"))
+                            .small()
+                            .primary()
+                            .on_click(cx.listener(|this, _, window, cx| this.apply(window, cx))),
+                    ),
+            )
+    }
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+
+    #[test]
+    fn test_col_filter_matches() {
+        let filter = ColFilter::Values(vec!["Labor".into(), "Material".into()]);
+        assert!(filter.matches("Labor"));
+        assert!(!filter.matches("Machine"));
+        assert!(!filter.is_empty());
+
+        let filter = ColFilter::Text {
+            op: TextFilterOp::Contains,
+            text: "co2".into(),
+        };
+        assert!(filter.matches("CO2 emission"));
+        assert!(!filter.matches("CH4"));
+
+        let filter = ColFilter::Text {
+            op: TextFilterOp::Equals,
+            text: "co2".into(),
+        };
+        assert!(filter.matches("CO2"));
+        assert!(!filter.matches("CO2 emission"));
+
+        let filter = ColFilter::Text {
+            op: TextFilterOp::Contains,
+            text: "".into(),
+        };
+        assert!(filter.is_empty());
+
+        let filter = ColFilter::Number {
+            min: Some(1.5),
+            max: Some(10.),
+        };
+        assert!(filter.matches("1.5"));
+        assert!(filter.matches(" 10 "));
+        assert!(!filter.matches("10.1"));
+        assert!(!filter.matches("abc"));
+        assert!(filter.matches_number(5.));
+
+        let filter = ColFilter::Number {
+            min: None,
+            max: Some(0.),
+        };
+        assert!(filter.matches_number(-100.));
+        assert!(!filter.matches_number(1.));
+        assert!(ColFilter::Number {
+            min: None,
+            max: None
+        }
+        .is_empty());
+    }
+}
@@ -0,0 +1,44 @@
+use super::{CellPos, CellValue};
+
+/// Returns the cells that contain the query text (case-insensitive),
+/// the `cells` must be in the order of (row, col).
+pub(crate) fn find_matches(cells: &[(CellPos, CellValue)], query: &str) -> Vec<CellPos> {
+    let query = query.to_lowercase();
+    if query.is_empty() {
+        return vec![];
+    }
+
+    cells
+        .iter()
+        .filter(|(_, value)| match value {
+            CellValue::Empty => false,
+            CellValue::Text(text) => text.to_lowercase().contains(&query),
+            value => value.to_string().to_lowercase().contains(&query),
+        })
+        .map(|(pos, _)| *pos)
+        .collect()
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+
+    #[test]
+    fn test_find_matches() {
+        let cells = [
+            (CellPos::new(0, 0), "Apple".into()),
+            (CellPos::new(0, 1), 12.5.into()),
+            (CellPos::new(1, 0), CellValue::Empty),
+            (CellPos::new(1, 1), "pineapple".into()),
+            (CellPos::new(2, 0), "Banana".into()),
+        ];
+
+        assert_eq!(
+            find_matches(&cells, "APP"),
+            vec![CellPos::new(0, 0), CellPos::new(1, 1)]
+        );
+        assert_eq!(find_matches(&cells, "2.5"), vec![CellPos::new(0, 1)]);
+        assert!(find_matches(&cells, "").is_empty());
+        assert!(find_matches(&cells, "cherry").is_empty());
+    }
+}
@@ -0,0 +1,322 @@
+use gpui::Hsla;
+
+use crate::{ColorName, ThemeColor};
+
+/// The background and foreground colors of a cell, used by the [`FormatRule`].
+#[derive(Debug, Clone, Copy, Default, PartialEq)]
+pub struct CellStyle {
+    pub background: Option<Hsla>,
+    pub foreground: Option<Hsla>,
+}
+
+impl CellStyle {
+    pub fn new() -> Self {
+        Self::default()
+    }
+
+    /// Set the background color of the cell.
+    pub fn bg(mut self, color: impl Into<Hsla>) -> Self {
+        self.background = Some(color.into());
+        self
+    }
+
+    /// Set the text color of the cell.
+    pub fn text_color(mut self, color: impl Into<Hsla>) -> Self {
+        self.foreground = Some(color.into());
+        self
+    }
+
+    /// The style to highlight the cell by the given color, with a light background.
+    pub fn color(name: ColorName) -> Self {
+        let color = name.scale(500);
+        Self::new().bg(color.opacity(0.15)).text_color(color)
+    }
+
+    /// The style to highlight the cell by the `danger` color of the theme.
+    pub fn danger(theme: &ThemeColor) -> Self {
+        Self::new()
+            .bg(theme.danger.opacity(0.15))
+            .text_color(theme.danger)
+    }
+
+    /// Merge the other style, the colors of the other one take precedence.
+    fn merge(self, other: Self) -> Self {
+        Self {
+            background: other.background.or(self.background),
+            foreground: other.foreground.or(self.foreground),
+        }
+    }
+}
+
+/// A conditional formatting rule of a column, see `TableDelegate::col_format_rules`.
+///
+/// The rules are applied to the numeric value of the cell (see `TableDelegate::cell_number`),
+/// the top-N, data bar and color scale are relative to the values of all rows in the column.
+#[derive(Debug, Clone, PartialEq)]
+pub enum FormatRule {
+    /// Style the cells with the value in `min..=max`, None means no limit.
+    Threshold {
+        min: Option<f64>,
+        max: Option<f64>,
+        style: CellStyle,
+    },
+    /// Style the cells with the top `n` values of the column, or the bottom `n` values if `bottom` is true.
+    ///
+    /// The cells with the same value as the n-th value are included.
+    TopN {
+        n: usize,
+        bottom: bool,
+        style: CellStyle,
+    },
+    /// Draw a bar in the cell, the length is in proportion to the value in the range of the column,
+    /// the range includes zero.
+    DataBar { color: Hsla },
+    /// Fill the background with the color between `low` and `high` by the value in the range of the column.
+    ColorScale { low: Hsla, high: Hsla },
+}
+
+impl FormatRule {
+    /// Style the cells with the value greater than or equal to `min`.
+    pub fn above(min: f64, style: CellStyle) -> Self {
+        Self::Threshold {
+            min: Some(min),
+            max: None,
+            style,
+        }
+    }
+
+    /// Style the cells with the value less than or equal to `max`.
+    pub fn below(max: f64, style: CellStyle) -> Self {
+        Self::Threshold {
+            min: None,
+            max: Some(max),
+            style,
+        }
+    }
+
+    /// Style the cells with the value in `min..=max`.
+    pub fn between(min: f64, max: f64, style: CellStyle) -> Self {
+        Self::Threshold {
+            min: Some(min),
+            max: Some(max),
+            style,
+        }
+    }
+
+    /// Style the cells with the top `n` values of the column.
+    pub fn top(n: usize, style: CellStyle) -> Self {
+        Self::TopN {
+            n,
+            bottom: false,
+            style,
+        }
+    }
+
+    /// Style the cells with the bottom `n` values of the column.
+    pub fn bottom(n: usize, style: CellStyle) -> Self {
+        Self::TopN {
+            n,
+            bottom: true,
+            style,
+        }
+    }
+
+    /// Draw a data bar with the given color.
+    pub fn data_bar(name: ColorName) -> Self {
+        Self::DataBar {
+            color: name.scale(500).opacity(0.3),
+        }
+    }
+
+    /// Fill the background with the given color, from transparent for the minimum value
+    /// to half opacity for the maximum value, so it works with the light and dark themes.
+    pub fn color_scale(name: ColorName) -> Self {
+        let color = name.scale(500);
+        Self::ColorScale {
+            low: color.opacity(0.),
+            high: color.opacity(0.5),
+        }
+    }
+
+    /// Returns true if the rule depends on the values of the column.
+    fn needs_values(&self) -> bool {
+        !matches!(self, Self::Threshold { .. })
+    }
+}
+
+/// The format of a cell computed by the [`FormatRule`]s.
+#[derive(Debug, Clone, Copy, Default, PartialEq)]
+pub(crate) struct CellFormat {
+    pub(crate) style: CellStyle,
+    /// The ratio (0.0 to 1.0) of the bar width to the cell width, and the color of the bar.
+    pub(crate) bar: Option<(f32, Hsla)>,
+}
+
+/// The format rules of a column, with the statistics of the column values.
+#[derive(Debug, Clone)]
+pub(crate) struct ColFormat {
+    rules: Vec<FormatRule>,
+    min: f64,
+    max: f64,
+    /// The n-th value of the `TopN` rule at the same index.
+    top_n_values: Vec<Option<f64>>,
+}
+
+impl ColFormat {
+    /// Returns true if the rules need the values of the column to build the format.
+    pub(crate) fn needs_values(rules: &[FormatRule]) -> bool {
+        rules.iter().any(|rule| rule.needs_values())
+    }
+
+    pub(crate) fn new(rules: Vec<FormatRule>, mut values: Vec<f64>) -> Self {
+        values.retain(|value| !value.is_nan());
+        let (min, max) = values
+            .iter()
+            .fold((f64::INFINITY, f64::NEG_INFINITY), |(min, max), &value| {
+                (min.min(value), max.max(value))
+            });
+
+        let top_n_values = rules
+            .iter()
+            .map(|rule| match *rule {
+                FormatRule::TopN { n, bottom, .. } if n > 0 && !values.is_empty() => {
+                    let ix = n.min(values.len()) - 1;
+                    let (_, value, _) = values.select_nth_unstable_by(ix, |a, b| {
+                        if bottom {
+                            a.total_cmp(b)
+                        } else {
+                            b.total_cmp(a)
+                        }
+                    });
+                    Some(*value)
+                }
+                _ => None,
+            })
+            .collect();
+
+        Self {
+            rules,
+            min,
+            max,
+            top_n_values,
+        }
+    }
+
+    /// Returns the format of the cell with the given value, the later rule takes precedence.
+    pub(crate) fn format(&self, value: f64) -> CellFormat {
+        let mut format = CellFormat::default();
+        if value.is_nan() {
+            return format;
+        }
+
+        for (rule, top_n_value) in self.rules.iter().zip(self.top_n_values.iter()) {
+            match *rule {
+                FormatRule::Threshold { min, max, style } => {
+                    if min.map_or(true, |min| value >= min) && max.map_or(true, |max| value <= max)
+                    {
+                        format.style = format.style.merge(style);
+                    }
+                }
+                FormatRule::TopN { bottom, style, .. } => {
+                    let Some(top_n_value) = *top_n_value else {
+                        continue;
+                    };
+                    if (bottom && value <= top_n_value) || (!bottom && value >= top_n_value) {
+                        format.style = format.style.merge(style);
+                    }
+                }
+                FormatRule::DataBar { color } => {
+                    let ratio = ratio_in(value, self.min.min(0.), self.max.max(0.));
+                    format.bar = Some((ratio, color));
+                }
+                FormatRule::ColorScale { low, high } => {
+                    let ratio = ratio_in(value, self.min, self.max);
+                    format.style = format
+                        .style
+                        .merge(CellStyle::new().bg(mix(low, high, ratio)));
+                }
+            }
+        }
+
+        format
+    }
+}
+
+/// Returns the ratio (0.0 to 1.0) of the value in `min..=max`, 1.0 if the range is empty.
+fn ratio_in(value: f64, min: f64, max: f64) -> f32 {
+    if max <= min {
+        return 1.;
+    }
+
+    ((value - min) / (max - min)).clamp(0., 1.) as f32
+}
+
+/// Mix the two colors by the ratio, 0.0 is the `from` color, 1.0 is the `to` color.
+fn mix(from: Hsla, to: Hsla, ratio: f32) -> Hsla {
+    let lerp = |a: f32, b: f32| a + (b - a) * ratio;
+    Hsla {
+        h: lerp(from.h, to.h),
+        s: lerp(from.s, to.s),
+        l: lerp(from.l, to.l),
+        a: lerp(from.a, to.a),
+    }
+}
+
+#[cfg(test)]
+mod tests {
+    use gpui::{hsla, red};
+
+    use super::*;
+
+    #[test]
+    fn test_threshold_and_top_n() {
+        let high = CellStyle::new().bg(red());
+        let low = CellStyle::new().text_color(hsla(0.5, 0.5, 0.5, 1.));
+        let format = ColFormat::new(
+            vec![
+                FormatRule::above(10., high),
+                FormatRule::bottom(2, low),
+                FormatRule::top(1, CellStyle::new().text_color(red())),
+            ],
+            vec![12., 3., 8., f64::NAN, 3., 20.],
+        );
+
+        assert_eq!(format.format(12.).style, high);
+        assert_eq!(format.format(8.).style, CellStyle::default());
+        // The same values as the n-th value are included.
+        assert_eq!(format.format(3.).style, low);
+        assert_eq!(
+            format.format(20.).style,
+            CellStyle::new().bg(red()).text_color(red())
+        );
+        assert_eq!(format.format(f64::NAN), CellFormat::default());
+    }
+
+    #[test]
+    fn test_data_bar_and_color_scale() {
+        let low = hsla(0., 1., 0.5, 0.);
+        let high = hsla(0., 1., 0.5, 1.);
+        let format = ColFormat::new(
+            vec![
+                FormatRule::DataBar { color: red() },
+                FormatRule::ColorScale { low, high },
+            ],
+            vec![10., 20., 30., 50.],
+        );
+
+        let cell = format.format(25.);
+        assert_eq!(cell.bar, Some((0.5, red())));
+        assert_eq!(cell.style.background, Some(hsla(0., 1., 0.5, 0.375)));
+        assert_eq!(format.format(10.).style.background, Some(low));
+        assert_eq!(format.format(60.).style.background, Some(high));
+
+        assert!(ColFormat::needs_values(&[FormatRule::top(
+            3,
+            CellStyle::new()
+        )]));
+        assert!(!ColFormat::needs_values(&[FormatRule::above(
+            1.,
+            CellStyle::new()
+        )]));
+    }
+}
@@ -0,0 +1,245 @@
+use std::{collections::HashSet, ops::Range};
+
+use gpui::SharedString;
+
+/// A group of adjacent rows with the same group key.
+#[derive(Debug, Clone, PartialEq)]
+pub(crate) struct RowGroup {
+    pub(crate) key: SharedString,
+    pub(crate) rows: Range<usize>,
+    pub(crate) collapsed: bool,
+}
+
+/// An item of the table body list, that is a group header or a row.
+#[derive(Debug, Clone, Copy, PartialEq, Eq)]
+pub(crate) enum ListItem {
+    /// The group header with the group index.
+    Group(usize),
+    /// The row with the row index.
+    Row(usize),
+}
+
+/// The mapping of the table body list items to the row groups and rows.
+///
+/// When there is no group, the list items are the rows in `start..end` one by one.
+#[derive(Debug, Default)]
+pub(crate) struct RowGroups {
+    start: usize,
+    end: usize,
+    groups: Vec<RowGroup>,
+    items: Vec<ListItem>,
+}
+
+impl RowGroups {
+    /// Create the list items of the rows in `rows` without any group.
+    pub(crate) fn new(rows: Range<usize>) -> Self {
+        Self {
+            start: rows.start,
+            end: rows.end,
+            groups: vec![],
+            items: vec![],
+        }
+    }
+
+    /// Build the groups of the rows in `rows`, by the group key of each row.
+    ///
+    /// The adjacent rows with the same key are in the same group, the rows without key are not grouped.
+    pub(crate) fn build(
+        rows: Range<usize>,
+        mut key: impl FnMut(usize) -> Option<SharedString>,
+        collapsed: &HashSet<SharedString>,
+    ) -> Self {
+        let mut groups: Vec<RowGroup> = vec![];
+        let mut items = vec![];
+
+        for row_ix in rows.clone() {
+            let Some(key) = key(row_ix) else {
+                items.push(ListItem::Row(row_ix));
+                continue;
+            };
+
+            match groups.last_mut() {
+                Some(group) if group.key == key && group.rows.end == row_ix => {
+                    group.rows.end = row_ix + 1;
+                }
+                _ => {
+                    items.push(ListItem::Group(groups.len()));
+                    groups.push(RowGroup {
+                        collapsed: collapsed.contains(&key),
+                        key,
+                        rows: row_ix..row_ix + 1,
+                    });
+                }
+            }
+
+            let group = groups.last().expect("group must exist");
+            if !group.collapsed {
+                items.push(ListItem::Row(row_ix));
+            }
+        }
+
+        if groups.is_empty() {
+            items.clear();
+        }
+
+        Self {
+            start: rows.start,
+            end: rows.end,
+            groups,
+            items,
+        }
+    }
+
+    /// Returns true if there is any group.
+    pub(crate) fn is_grouped(&self) -> bool {
+        !self.groups.is_empty()
+    }
+
+    pub(crate) fn group(&self, group_ix: usize) -> Option<&RowGroup> {
+        self.groups.get(group_ix)
+    }
+
+    pub(crate) fn groups(&self) -> &[RowGroup] {
+        &self.groups
+    }
+
+    /// Returns the number of the list items.
+    pub(crate) fn len(&self) -> usize {
+        if self.is_grouped() {
+            self.items.len()
+        } else {
+            self.end.saturating_sub(self.start)
+        }
+    }
+
+    /// Returns the list item at the given index.
+    pub(crate) fn get(&self, ix: usize) -> Option<ListItem> {
+        if self.is_grouped() {
+            self.items.get(ix).copied()
+        } else if ix < self.len() {
+            Some(ListItem::Row(self.start + ix))
+        } else {
+            None
+        }
+    }
+
+    /// Returns the list index of the given row, None if the row is not in the list or in a collapsed group.
+    pub(crate) fn position_of_row(&self, row_ix: usize) -> Option<usize> {
+        if !(self.start..self.end).contains(&row_ix) {
+            return None;
+        }
+        if !self.is_grouped() {
+            return Some(row_ix - self.start);
+        }
+
+        self.items
+            .binary_search_by(|item| match *item {
+                ListItem::Row(ix) => ix.cmp(&row_ix),
+                // The group header is always before the rows of the group.
+                ListItem::Group(group_ix) => {
+                    if self.groups[group_ix].rows.start <= row_ix {
+                        std::cmp::Ordering::Less
+                    } else {
+                        std::cmp::Ordering::Greater
+                    }
+                }
+            })
+            .ok()
+    }
+
+    /// Returns the list index of the given group header.
+    pub(crate) fn position_of_group(&self, group_ix: usize) -> Option<usize> {
+        self.items
+            .iter()
+            .position(|item| *item == ListItem::Group(group_ix))
+    }
+
+    /// Returns the group index of the given row.
+    pub(crate) fn group_of_row(&self, row_ix: usize) -> Option<usize> {
+        self.groups
+            .iter()
+            .position(|group| group.rows.contains(&row_ix))
+    }
+
+    /// Returns the first row index of the list item at the given index.
+    pub(crate) fn row_of_item(&self, item: ListItem) -> usize {
+        match item {
+            ListItem::Row(row_ix) => row_ix,
+            ListItem::Group(group_ix) => self.groups[group_ix].rows.start,
+        }
+    }
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+
+    fn build(keys: &[Option<&'static str>], collapsed: &[&'static str]) -> RowGroups {
+        let collapsed = collapsed
+            .iter()
+            .map(|key| SharedString::from(*key))
+            .collect();
+        RowGroups::build(
+            0..keys.len(),
+            |row_ix| keys[row_ix].map(SharedString::from),
+            &collapsed,
+        )
+    }
+
+    #[test]
+    fn test_row_groups() {
+        let keys = [
+            Some("Labor"),
+            Some("Labor"),
+            Some("Material"),
+            Some("Material"),
+            Some("Material"),
+            None,
+            Some("Machine"),
+        ];
+        let groups = build(&keys, &[]);
+        assert!(groups.is_grouped());
+        assert_eq!(groups.group(3), None);
+        assert_eq!(groups.group(1).map(|g| g.rows.clone()), Some(2..5));
+        assert_eq!(groups.len(), 10);
+        assert_eq!(groups.get(0), Some(ListItem::Group(0)));
+        assert_eq!(groups.get(1), Some(ListItem::Row(0)));
+        assert_eq!(groups.get(3), Some(ListItem::Group(1)));
+        assert_eq!(groups.get(7), Some(ListItem::Row(5)));
+        assert_eq!(groups.get(8), Some(ListItem::Group(2)));
+        assert_eq!(groups.get(10), None);
+        assert_eq!(groups.position_of_row(2), Some(4));
+        assert_eq!(groups.position_of_row(5), Some(7));
+        assert_eq!(groups.position_of_row(6), Some(9));
+        assert_eq!(groups.position_of_row(7), None);
+        assert_eq!(groups.position_of_group(2), Some(8));
+        assert_eq!(groups.group_of_row(3), Some(1));
+        assert_eq!(groups.group_of_row(5), None);
+        assert_eq!(groups.row_of_item(ListItem::Group(1)), 2);
+
+        let groups = build(&keys, &["Material"]);
+        assert_eq!(groups.len(), 7);
+        assert!(groups.group(1).map_or(false, |g| g.collapsed));
+        assert_eq!(groups.get(3), Some(ListItem::Group(1)));
+        assert_eq!(groups.get(4), Some(ListItem::Row(5)));
+        assert_eq!(groups.position_of_row(3), None);
+        assert_eq!(groups.position_of_row(6), Some(6));
+    }
+
+    #[test]
+    fn test_row_groups_without_keys() {
+        let groups = RowGroups::build(2..5, |_| None, &HashSet::new());
+        assert!(!groups.is_grouped());
+        assert_eq!(groups.len(), 3);
+        assert_eq!(groups.get(0), Some(ListItem::Row(2)));
+        assert_eq!(groups.get(3), None);
+        assert_eq!(groups.position_of_row(4), Some(2));
+        assert_eq!(groups.position_of_row(1), None);
+
+        let groups = RowGroups::new(2..5);
+        assert!(!groups.is_grouped());
+        assert_eq!(groups.len(), 3);
+        assert_eq!(groups.get(2), Some(ListItem::Row(4)));
+        assert_eq!(groups.position_of_row(3), Some(1));
+    }
+}
@@ -0,0 +1,307 @@
+use std::ops::Range;
+
+/// The position of a cell in the table, ordered by row and then by column.
+#[derive(Debug, Clone, Copy, Default, PartialEq, Eq, PartialOrd, Ord, Hash)]
+pub struct CellPos {
+    pub row: usize,
+    pub col: usize,
+}
+
+impl CellPos {
+    pub fn new(row: usize, col: usize) -> Self {
+        Self { row, col }
+    }
+}
+
+impl From<(usize, usize)> for CellPos {
+    fn from((row, col): (usize, usize)) -> Self {
+        Self::new(row, col)
+    }
+}
+
+/// A rectangular range of cells.
+///
+/// The `anchor` is the cell where the selection started, and the `focus` is the cell
+/// where the selection ends, the `focus` can be before the `anchor`.
+#[derive(Debug, Clone, Copy, Default, PartialEq, Eq)]
+pub struct CellRange {
+    pub anchor: CellPos,
+    pub focus: CellPos,
+}
+
+impl CellRange {
+    pub fn new(anchor: impl Into<CellPos>, focus: impl Into<CellPos>) -> Self {
+        Self {
+            anchor: anchor.into(),
+            focus: focus.into(),
+        }
+    }
+
+    /// Create a range that only contains a single cell.
+    pub fn cell(pos: impl Into<CellPos>) -> Self {
+        let pos = pos.into();
+        Self::new(pos, pos)
+    }
+
+    /// Returns the rows covered by this range.
+    pub fn rows(&self) -> Range<usize> {
+        self.anchor.row.min(self.focus.row)..self.anchor.row.max(self.focus.row) + 1
+    }
+
+    /// Returns the columns covered by this range.
+    pub fn cols(&self) -> Range<usize> {
+        self.anchor.col.min(self.focus.col)..self.anchor.col.max(self.focus.col) + 1
+    }
+
+    /// Returns the top-left cell of this range.
+    pub fn start(&self) -> CellPos {
+        CellPos::new(self.rows().start, self.cols().start)
+    }
+
+    /// Returns the number of cells in this range.
+    pub fn len(&self) -> usize {
+        self.rows().len() * self.cols().len()
+    }
+
+    /// Returns true if the given cell is in this range.
+    pub fn contains(&self, pos: CellPos) -> bool {
+        self.rows().contains(&pos.row) && self.cols().contains(&pos.col)
+    }
+}
+
+/// The cell selection of the table.
+///
+/// A selection is made of one or more (possibly disjoint) rectangular ranges,
+/// the last one is the active range that is extended by shift+click or shift+arrow.
+#[derive(Debug, Clone, Default, PartialEq, Eq)]
+pub struct TableSelection {
+    ranges: Vec<CellRange>,
+}
+
+impl TableSelection {
+    /// Returns all the selected ranges, the last one is the active range.
+    pub fn ranges(&self) -> &[CellRange] {
+        &self.ranges
+    }
+
+    /// Returns the active range.
+    pub fn active_range(&self) -> Option<&CellRange> {
+        self.ranges.last()
+    }
+
+    /// Returns the anchor cell of the active range.
+    pub fn anchor(&self) -> Option<CellPos> {
+        self.active_range().map(|range| range.anchor)
+    }
+
+    /// Returns the focus cell of the active range.
+    pub fn focus(&self) -> Option<CellPos> {
+        self.active_range().map(|range| range.focus)
+    }
+
+    pub fn is_empty(&self) -> bool {
+        self.ranges.is_empty()
+    }
+
+    /// Returns true if more than one cell is selected.
+    pub fn is_multiple(&self) -> bool {
+        self.ranges.len() > 1 || self.ranges.first().map_or(false, |range| range.len() > 1)
+    }
+
+    /// Returns true if the given cell is selected.
+    pub fn contains(&self, pos: CellPos) -> bool {
+        self.ranges.iter().any(|range| range.contains(pos))
+    }
+
+    /// Returns the bounding range of all the selected ranges.
+    pub fn bounding_range(&self) -> Option<CellRange> {
+        let first = self.ranges.first()?;
+        let (mut rows, mut cols) = (first.rows(), first.cols());
+        for range in self.ranges.iter().skip(1) {
+            rows = rows.start.min(range.rows().start)..rows.end.max(range.rows().end);
+            cols = cols.start.min(range.cols().start)..cols.end.max(range.cols().end);
+        }
+
+        Some(CellRange::new(
+            (rows.start, cols.start),
+            (rows.end - 1, cols.end - 1),
+        ))
+    }
+
+    /// Select only the given cell.
+    pub(crate) fn select(&mut self, pos: CellPos) {
+        self.ranges.clear();
+        self.ranges.push(CellRange::cell(pos));
+    }
+
+    /// Select only the given range.
+    pub(crate) fn select_range(&mut self, range: CellRange) {
+        self.ranges.clear();
+        self.ranges.push(range);
+    }
+
+    /// Select all the cells of a table with the given rows and columns count.
+    ///
+    /// The anchor is the first cell and the focus is the last cell.
+    pub(crate) fn select_all(&mut self, rows_count: usize, cols_count: usize) {
+        if rows_count == 0 || cols_count == 0 {
+            self.clear();
+            return;
+        }
+
+        self.select_range(CellRange::new((0, 0), (rows_count - 1, cols_count - 1)));
+    }
+
+    /// Add the given cell as a new disjoint range, it becomes the active range.
+    pub(crate) fn add(&mut self, pos: CellPos) {
+        self.ranges.push(CellRange::cell(pos));
+    }
+
+    /// Extend the active range to the given cell.
+    pub(crate) fn extend_to(&mut self, pos: CellPos) {
+        match self.ranges.last_mut() {
+            Some(range) => range.focus = pos,
+            None => self.select(pos),
+        }
+    }
+
+    pub(crate) fn clear(&mut self) {
+        self.ranges.clear();
+    }
+}
+
+/// Returns the index to jump to from the current cell to the edge of the data, like `ctrl-arrow` in spreadsheets.
+///
+/// The `next` are the indexes after the current cell in the moving direction.
+///
+/// - If the current and the next cell are not empty, jump to the last non-empty cell before an empty cell.
+/// - Otherwise, jump to the next non-empty cell, or the last cell if all cells are empty.
+///
+/// Returns None if there is no next cell.
+pub(crate) fn data_edge(
+    current_empty: bool,
+    next: impl IntoIterator<Item = usize>,
+    is_empty: impl Fn(usize) -> bool,
+) -> Option<usize> {
+    let mut next = next.into_iter();
+    let mut target = next.next()?;
+    if !current_empty && !is_empty(target) {
+        for ix in next {
+            if is_empty(ix) {
+                break;
+            }
+            target = ix;
+        }
+    } else if is_empty(target) {
+        for ix in next {
+            target = ix;
+            if !is_empty(ix) {
+                break;
+            }
+        }
+    }
+
+    Some(target)
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+
+    #[test]
+    fn test_cell_range() {
+        let range = CellRange::new((5, 3), (2, 1));
+        assert_eq!(range.rows(), 2..6);
+        assert_eq!(range.cols(), 1..4);
+        assert_eq!(range.start(), CellPos::new(2, 1));
+        assert_eq!(range.len(), 12);
+        assert!(range.contains(CellPos::new(2, 1)));
+        assert!(range.contains(CellPos::new(5, 3)));
+        assert!(!range.contains(CellPos::new(6, 3)));
+        assert!(!range.contains(CellPos::new(5, 0)));
+
+        let range = CellRange::cell((1, 1));
+        assert_eq!(range.len(), 1);
+        assert!(range.contains(CellPos::new(1, 1)));
+    }
+
+    #[test]
+    fn test_table_selection() {
+        let mut selection = TableSelection::default();
+        assert!(selection.is_empty());
+        assert_eq!(selection.focus(), None);
+
+        selection.extend_to(CellPos::new(1, 1));
+        assert_eq!(selection.anchor(), Some(CellPos::new(1, 1)));
+        assert!(!selection.is_multiple());
+
+        selection.extend_to(CellPos::new(3, 2));
+        assert_eq!(selection.anchor(), Some(CellPos::new(1, 1)));
+        assert_eq!(selection.focus(), Some(CellPos::new(3, 2)));
+        assert!(selection.is_multiple());
+        assert!(selection.contains(CellPos::new(2, 2)));
+
+        selection.add(CellPos::new(10, 5));
+        assert_eq!(selection.ranges().len(), 2);
+        assert_eq!(selection.anchor(), Some(CellPos::new(10, 5)));
+        assert!(selection.contains(CellPos::new(10, 5)));
+        assert!(selection.contains(CellPos::new(1, 1)));
+        assert!(!selection.contains(CellPos::new(5, 5)));
+        assert_eq!(
+            selection.bounding_range(),
+            Some(CellRange::new((1, 1), (10, 5)))
+        );
+
+        selection.select(CellPos::new(0, 0));
+        assert_eq!(selection.ranges(), &[CellRange::cell((0, 0))]);
+
+        selection.clear();
+        assert!(selection.is_empty());
+        assert_eq!(selection.bounding_range(), None);
+    }
+
+    #[test]
+    fn test_select_all() {
+        let mut selection = TableSelection::default();
+        selection.select(CellPos::new(3, 2));
+        selection.add(CellPos::new(1, 4));
+        selection.select_all(10, 5);
+        assert_eq!(selection.ranges(), &[CellRange::new((0, 0), (9, 4))]);
+        assert_eq!(selection.focus(), Some(CellPos::new(9, 4)));
+        for row in 0..10 {
+            for col in 0..5 {
+                assert!(selection.contains(CellPos::new(row, col)));
+            }
+        }
+        assert!(!selection.contains(CellPos::new(10, 0)));
+
+        selection.select_all(0, 5);
+        assert!(selection.is_empty());
+    }
+
+    #[test]
+    fn test_data_edge() {
+        let cells = ["a", "b", "c", "", "", "d", "e", ""];
+        let is_empty = |ix: usize| cells[ix].is_empty();
+        let edge = |ix: usize, forward: bool| {
+            if forward {
+                data_edge(is_empty(ix), ix + 1..cells.len(), is_empty)
+            } else {
+                data_edge(is_empty(ix), (0..ix).rev(), is_empty)
+            }
+        };
+
+        assert_eq!(edge(0, true), Some(2));
+        assert_eq!(edge(2, true), Some(5));
+        assert_eq!(edge(3, true), Some(5));
+        assert_eq!(edge(5, true), Some(6));
+        assert_eq!(edge(6, true), Some(7));
+        assert_eq!(edge(7, true), None);
+        assert_eq!(edge(6, false), Some(5));
+        assert_eq!(edge(5, false), Some(2));
+        assert_eq!(edge(4, false), Some(2));
+        assert_eq!(edge(2, false), Some(0));
+        assert_eq!(edge(0, false), None);
+        assert_eq!(data_edge(true, 1..4, |_| true), Some(3));
+    }
+}
@@ -0,0 +1,62 @@
+use gpui::Pixels;
+use serde::{Deserialize, Serialize};
+
+use super::{ColFixed, ColSort};
+
+/// Used to serialize and deserialize the columns layout of the Table.
+///
+/// See also [`super::Table::dump_layout`] and [`super::Table::load_layout`].
+#[derive(Debug, Default, Clone, Serialize, Deserialize, PartialEq)]
+pub struct TableLayoutState {
+    /// The columns in the display order.
+    pub cols: Vec<ColLayoutState>,
+    /// The keys of the sorted columns, the first one is the primary sort key.
+    #[serde(default, skip_serializing_if = "Vec::is_empty")]
+    pub sort_keys: Vec<String>,
+}
+
+/// Used to serialize and deserialize the layout of a column.
+#[derive(Debug, Clone, Serialize, Deserialize, PartialEq)]
+pub struct ColLayoutState {
+    /// The key of the column, see `TableDelegate::col_key`.
+    pub key: String,
+    pub width: Pixels,
+    #[serde(default)]
+    pub hidden: bool,
+    #[serde(default, skip_serializing_if = "Option::is_none")]
+    pub sort: Option<ColSort>,
+    #[serde(default, skip_serializing_if = "Option::is_none")]
+    pub fixed: Option<ColFixed>,
+}
+
+#[cfg(test)]
+mod tests {
+    use gpui::px;
+
+    use super::*;
+
+    #[test]
+    fn test_deserialize_table_layout_state() {
+        let json = r#"{
+            "cols": [
+                { "key": "symbol", "width": 120.0, "fixed": "Left" },
+                { "key": "name", "width": 80.0, "hidden": true },
+                { "key": "price", "width": 100.0, "sort": "Descending" }
+            ],
+            "sort_keys": ["price"]
+        }"#;
+        let state: TableLayoutState = serde_json::from_str(json).unwrap();
+        assert_eq!(state.cols.len(), 3);
+        assert_eq!(state.cols[0].key, "symbol");
+        assert_eq!(state.cols[0].width, px(120.));
+        assert_eq!(state.cols[0].fixed, Some(ColFixed::Left));
+        assert!(!state.cols[0].hidden);
+        assert!(state.cols[1].hidden);
+        assert_eq!(state.cols[2].sort, Some(ColSort::Descending));
+        assert_eq!(state.sort_keys, vec!["price".to_string()]);
+
+        let json = serde_json::to_string(&state).unwrap();
+        let state2: TableLayoutState = serde_json::from_str(&json).unwrap();
+        assert_eq!(state, state2);
+    }
+}
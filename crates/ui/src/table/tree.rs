@@ -0,0 +1,135 @@
+/// The action of the left or right key on the selected tree row.
+#[derive(Debug, Clone, Copy, PartialEq, Eq)]
+pub(crate) enum TreeKeyAction {
+    /// Expand or collapse the selected row.
+    Toggle,
+    /// Select the row at the given index.
+    Select(usize),
+    /// Nothing to do, e.g.: the root row without children.
+    None,
+}
+
+/// Returns the action of the left (`expand` is false) or right key on the tree row at `row_ix`,
+/// like a file explorer.
+///
+/// - Left: collapse the expanded row, or select the parent row.
+/// - Right: expand the collapsed row, or select the first child row.
+pub(crate) fn tree_key_action(
+    row_ix: usize,
+    expand: bool,
+    rows_count: usize,
+    has_children: bool,
+    expanded: bool,
+    depth_of: impl Fn(usize) -> usize,
+) -> TreeKeyAction {
+    let expanded = has_children && expanded;
+    if expand {
+        if has_children && !expanded {
+            TreeKeyAction::Toggle
+        } else if expanded && row_ix + 1 < rows_count {
+            TreeKeyAction::Select(row_ix + 1)
+        } else {
+            TreeKeyAction::None
+        }
+    } else if expanded {
+        TreeKeyAction::Toggle
+    } else {
+        let depth = depth_of(row_ix);
+        (0..row_ix)
+            .rfind(|&ix| depth_of(ix) < depth)
+            .map_or(TreeKeyAction::None, TreeKeyAction::Select)
+    }
+}
+
+/// Returns the new index of the selected row after the tree row at `row_ix` is expanded or collapsed,
+/// the rows count is changed from `old_rows_count` to `new_rows_count`.
+///
+/// The selected row in the collapsed children is moved to the collapsed row.
+pub(crate) fn selected_row_after_toggle(
+    selected_row: usize,
+    row_ix: usize,
+    old_rows_count: usize,
+    new_rows_count: usize,
+) -> usize {
+    if selected_row <= row_ix {
+        return selected_row;
+    }
+
+    if new_rows_count < old_rows_count && selected_row <= row_ix + (old_rows_count - new_rows_count)
+    {
+        row_ix
+    } else {
+        (selected_row + new_rows_count).saturating_sub(old_rows_count)
+    }
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+
+    #[test]
+    fn test_tree_key_action() {
+        // 0 Root
+        // 1   Child
+        // 2     Leaf
+        // 3   Child
+        // 4 Root
+        let depths = [0, 1, 2, 1, 0];
+        let depth_of = |ix: usize| depths[ix];
+
+        // Right
+        assert_eq!(
+            tree_key_action(0, true, 5, true, false, depth_of),
+            TreeKeyAction::Toggle
+        );
+        assert_eq!(
+            tree_key_action(0, true, 5, true, true, depth_of),
+            TreeKeyAction::Select(1)
+        );
+        assert_eq!(
+            tree_key_action(2, true, 5, false, false, depth_of),
+            TreeKeyAction::None
+        );
+        assert_eq!(
+            tree_key_action(4, true, 5, true, true, depth_of),
+            TreeKeyAction::None
+        );
+
+        // Left
+        assert_eq!(
+            tree_key_action(1, false, 5, true, true, depth_of),
+            TreeKeyAction::Toggle
+        );
+        assert_eq!(
+            tree_key_action(2, false, 5, false, false, depth_of),
+            TreeKeyAction::Select(1)
+        );
+        assert_eq!(
+            tree_key_action(3, false, 5, false, false, depth_of),
+            TreeKeyAction::Select(0)
+        );
+        assert_eq!(
+            tree_key_action(4, false, 5, true, false, depth_of),
+            TreeKeyAction::None
+        );
+        // The row without children is never toggled.
+        assert_eq!(
+            tree_key_action(2, false, 5, false, true, depth_of),
+            TreeKeyAction::Select(1)
+        );
+    }
+
+    #[test]
+    fn test_selected_row_after_toggle() {
+        // Expand the row 1 with 3 children.
+        assert_eq!(selected_row_after_toggle(0, 1, 5, 8), 0);
+        assert_eq!(selected_row_after_toggle(1, 1, 5, 8), 1);
+        assert_eq!(selected_row_after_toggle(3, 1, 5, 8), 6);
+
+        // Collapse the row 1 with 3 children.
+        assert_eq!(selected_row_after_toggle(2, 1, 8, 5), 1);
+        assert_eq!(selected_row_after_toggle(4, 1, 8, 5), 1);
+        assert_eq!(selected_row_after_toggle(5, 1, 8, 5), 2);
+        assert_eq!(selected_row_after_toggle(7, 1, 8, 5), 4);
+    }
+}
@@ -69,6 +69,11 @@ impl RenderOnce for Text {
 }
 
 /// TextViewStyle used to customize the style for [`TextView`].
+///
+/// The theme-derived highlighting of the code blocks is opt-in: the `Default` style has no access
+/// to the current theme, so it keeps the bundled light highlight theme. Use [`TextViewStyle::from_theme`]
+/// to highlight the code blocks by the syntax colors of the current theme, including the colors
+/// changed by [`Theme::apply_color`](crate::theme::Theme::apply_color).
 #[derive(Clone, PartialEq)]
 pub struct TextViewStyle {
     /// Gap of each paragraphs, default is 1 rem.
@@ -76,6 +81,8 @@ pub struct TextViewStyle {
     /// Base font size for headings, default is 14px.
     pub heading_base_font_size: Pixels,
     /// Highlight theme for code blocks. Default: [`HighlightTheme::default_light()`]
+    ///
+    /// This does not follow the current theme, use [`TextViewStyle::from_theme`] for that.
     pub highlight_theme: Rc<HighlightTheme>,
 }
 
@@ -90,6 +97,15 @@ impl Default for TextViewStyle {
 }
 
 impl TextViewStyle {
+    /// Create a style with the code blocks highlighted by the syntax colors of the current theme,
+    /// see [`HighlightTheme::global`].
+    pub fn from_theme(cx: &mut App) -> Self {
+        Self {
+            highlight_theme: HighlightTheme::global(cx),
+            ..Default::default()
+        }
+    }
+
     /// Set paragraph gap, default is 1 rem.
     pub fn paragraph_gap(mut self, gap: Rems) -> Self {
         self.paragraph_gap = gap;
@@ -375,6 +375,56 @@ impl ThemeColor {
     }
 }
 
+/// The syntax highlighting colors of the [`Theme`], used by [`HighlightTheme::from_theme`].
+///
+/// [`HighlightTheme::from_theme`]: crate::highlighter::HighlightTheme::from_theme
+#[derive(Debug, Clone, Copy, PartialEq)]
+pub struct SyntaxColors {
+    /// Color for comments.
+    pub comment: Hsla,
+    /// Color for function names.
+    pub function: Hsla,
+    /// Color for keywords.
+    pub keyword: Hsla,
+    /// Color for numbers and constants.
+    pub number: Hsla,
+    /// Color for strings.
+    pub string: Hsla,
+    /// Color for type names.
+    pub r#type: Hsla,
+}
+
+impl SyntaxColors {
+    pub fn light() -> Self {
+        Self {
+            comment: hsl(211.0, 8.0, 47.0),
+            function: hsl(262.0, 69.0, 59.0),
+            keyword: hsl(357.0, 72.0, 47.0),
+            number: hsl(213.0, 94.0, 35.0),
+            string: hsl(216.0, 83.0, 23.0),
+            r#type: hsl(23.0, 100.0, 29.0),
+        }
+    }
+
+    pub fn dark() -> Self {
+        Self {
+            comment: hsl(212.0, 9.0, 58.0),
+            function: hsl(269.0, 100.0, 83.0),
+            keyword: hsl(3.0, 100.0, 72.0),
+            number: hsl(209.0, 100.0, 74.0),
+            string: hsl(207.0, 100.0, 82.0),
+            r#type: hsl(28.0, 100.0, 67.0),
+        }
+    }
+
+    fn for_mode(mode: ThemeMode) -> Self {
+        match mode {
+            ThemeMode::Light => Self::light(),
+            ThemeMode::Dark => Self::dark(),
+        }
+    }
+}
+
 #[derive(Debug, Clone)]
 pub struct Theme {
     colors: ThemeColor,
@@ -391,6 +441,8 @@ pub struct Theme {
     pub tile_grid_size: Pixels,
     /// The shadow of the tile panel.
     pub tile_shadow: bool,
+    /// The syntax highlighting colors, changed with the theme mode.
+    pub syntax: SyntaxColors,
 }
 
 impl Deref for Theme {
@@ -507,6 +559,12 @@ impl Theme {
         self.description_list_label = self.description_list_label.apply(mask_color);
         self.description_list_label_foreground =
             self.description_list_label_foreground.apply(mask_color);
+        self.syntax.comment = self.syntax.comment.apply(mask_color);
+        self.syntax.function = self.syntax.function.apply(mask_color);
+        self.syntax.keyword = self.syntax.keyword.apply(mask_color);
+        self.syntax.number = self.syntax.number.apply(mask_color);
+        self.syntax.string = self.syntax.string.apply(mask_color);
+        self.syntax.r#type = self.syntax.r#type.apply(mask_color);
     }
 
     /// Sync the theme with the system appearance
@@ -545,6 +603,7 @@ impl Theme {
 
         theme.mode = mode;
         theme.colors = colors;
+        theme.syntax = SyntaxColors::for_mode(mode);
 
         if let Some(window) = window {
             window.refresh();
@@ -571,6 +630,7 @@ impl From<ThemeColor> for Theme {
             scrollbar_show: ScrollbarShow::default(),
             tile_grid_size: px(8.),
             tile_shadow: true,
+            syntax: SyntaxColors::for_mode(mode),
             colors,
         }
     }